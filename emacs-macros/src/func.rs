@@ -1,12 +1,15 @@
 use std::ops::Range;
 
+use proc_macro::{Delimiter, TokenStream, TokenTree};
+
 use darling::FromMeta;
 use quote::{quote, quote_spanned, TokenStreamExt};
 use syn::{
     self,
     export::{Span, TokenStream2},
+    parse::{Parse, ParseStream},
     spanned::Spanned,
-    AttributeArgs, FnArg, FnDecl, Ident, ItemFn,
+    AttributeArgs, FnArg, FnDecl, Ident, ItemFn, Token,
 };
 
 use crate::util::{self, report};
@@ -14,7 +17,18 @@ use crate::util::{self, report};
 #[derive(Debug)]
 enum Arg {
     Env { span: Span },
-    Val { span: Span, access: Access, nth: usize },
+    Val {
+        span: Span,
+        access: Access,
+        nth: usize,
+        name: String,
+        optional: bool,
+        /// Value used when the argument is omitted, or `nil`.
+        default: Option<syn::Expr>,
+    },
+    /// The `&rest` parameter, collecting all remaining arguments into a `Vec`, which is passed
+    /// as-is, or as a slice.
+    Rest { span: Span, nth: usize, name: String, slice: bool },
 }
 
 /// Kinds of argument.
@@ -23,6 +37,9 @@ enum Access {
     Owned,
     Ref,
     RefMut,
+    /// A reference to an `Arc` or a `Handle` embedded directly in a `user-ptr`, e.g.
+    /// `&Arc<Mutex<T>>`.
+    Direct,
 }
 
 /// Kinds of `user-ptr` embedding.
@@ -60,6 +77,10 @@ struct FuncOpts {
     /// Name of the function in Lisp, excluding prefix. `None` means sanitized Rust name is used.
     #[darling(default)]
     name: Option<String>,
+    /// Full name of the function in Lisp, used verbatim, without any prefix. `None` means the name
+    /// is constructed from the prefixes and `name`.
+    #[darling(default)]
+    full_name: Option<String>,
     /// Whether module path should be used to construct the full Lisp name. `None` means using
     /// crate-wide config.
     #[darling(default)]
@@ -67,8 +88,54 @@ struct FuncOpts {
     /// How the return value should be embedded in Lisp as a `user-ptr`. `None` means no embedding.
     #[darling(default)]
     user_ptr: Option<UserPtr>,
+    /// Whether conversion errors of owned arguments should be reported with the argument's name.
+    #[darling(default)]
+    strict_args: bool,
+    /// Whether the function returns a raw `emacs_value`, to be passed to Lisp without conversion.
+    #[darling(default)]
+    raw_return: bool,
+    /// Whether calls should be counted and timed, with the statistics exposed through a generated
+    /// Lisp function.
+    #[darling(default)]
+    trace: bool,
+    /// Lisp code of the interactive form, which makes the function a command. `None` means the
+    /// function is not interactive.
+    #[darling(default)]
+    interactive_form: Option<String>,
+    /// Interactive spec, which makes the function a command. This is a shorthand for
+    /// `interactive_form` with a string literal, or with `nil` if there is no spec.
+    #[darling(default)]
+    interactive: Option<Interactive>,
+    /// Whether the last parameter collects the remaining arguments, like `&rest` in Lisp.
+    #[darling(default)]
+    rest: bool,
+    /// Whether trailing owned parameters of type `Option<T>` are `&optional` in Lisp.
+    #[darling(default)]
+    optional: bool,
+    /// Names of shared reference parameters to `Transfer` values embedded directly in `user-ptr`
+    /// objects, instead of through a `RefCell`.
+    #[darling(default)]
+    direct: Direct,
 }
 
+/// Default values of parameters, used when the arguments are omitted, or `nil`. They are keyed by
+/// the parameters' names, e.g. `defaults(port = 8080, host = "localhost".to_owned())`, where each
+/// value is a Rust expression.
+///
+/// Since `AttributeArgs` only allows literal values, `defaults(...)` is split out of the
+/// attribute's arguments before the other options are parsed.
+#[derive(Debug, Default)]
+pub struct Defaults(Vec<(String, syn::Expr)>);
+
+/// Names of parameters with direct access, e.g. `direct(counter)` for `counter: &Arc<Mutex<i64>>`.
+#[derive(Debug, Default)]
+struct Direct(Vec<String>);
+
+/// Codes of the interactive spec, e.g. `"p"`, set by `interactive = "..."`. `None` means a bare
+/// `interactive`, i.e. a command that takes no arguments when called interactively.
+#[derive(Debug)]
+struct Interactive(Option<String>);
+
 #[derive(Debug)]
 pub struct LispFunc {
     /// The original Rust definition.
@@ -113,13 +180,113 @@ impl FromMeta for UserPtr {
     }
 }
 
+impl Defaults {
+    /// Removes `defaults(...)` from the arguments of `#[defun]`, returning the remaining arguments,
+    /// and the parsed default values.
+    pub fn split(attr_ts: TokenStream) -> syn::Result<(TokenStream, Defaults)> {
+        let mut rest = vec![];
+        let mut defaults: Option<Defaults> = None;
+        let mut tokens = attr_ts.into_iter().peekable();
+        while let Some(token) = tokens.next() {
+            let group = match (&token, tokens.peek()) {
+                (TokenTree::Ident(ident), Some(TokenTree::Group(group)))
+                    if ident.to_string() == "defaults"
+                        && group.delimiter() == Delimiter::Parenthesis =>
+                {
+                    group.clone()
+                }
+                _ => {
+                    rest.push(token);
+                    continue;
+                }
+            };
+            if defaults.is_some() {
+                let span = TokenStream2::from(TokenStream::from(token)).span();
+                return Err(syn::Error::new(span, "defaults must be specified only once"));
+            }
+            defaults = Some(syn::parse(group.stream())?);
+            tokens.next();
+            // Also remove the separator, so that the remaining arguments stay well-formed.
+            if let Some(TokenTree::Punct(punct)) = tokens.peek() {
+                if punct.as_char() == ',' {
+                    tokens.next();
+                }
+            }
+        }
+        Ok((rest.into_iter().collect(), defaults.unwrap_or_default()))
+    }
+}
+
+impl Parse for Defaults {
+    fn parse(input: ParseStream) -> syn::Result<Defaults> {
+        let mut defaults = vec![];
+        while !input.is_empty() {
+            let name: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            let expr: syn::Expr = input.parse()?;
+            defaults.push((name.to_string(), expr));
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        Ok(Defaults(defaults))
+    }
+}
+
+impl FromMeta for Direct {
+    fn from_list(items: &[syn::NestedMeta]) -> darling::Result<Direct> {
+        let mut names = vec![];
+        for item in items {
+            match item {
+                syn::NestedMeta::Meta(syn::Meta::Word(ident)) => names.push(ident.to_string()),
+                _ => {
+                    return Err(darling::Error::custom("Expected a parameter name").with_span(item))
+                }
+            }
+        }
+        Ok(Direct(names))
+    }
+}
+
+impl FromMeta for Interactive {
+    fn from_word() -> darling::Result<Interactive> {
+        Ok(Interactive(None))
+    }
+
+    fn from_string(spec: &str) -> darling::Result<Interactive> {
+        Ok(Interactive(Some(spec.to_owned())))
+    }
+}
+
 impl LispFunc {
-    pub fn parse(attr_args: AttributeArgs, fn_item: ItemFn) -> Result<Self, TokenStream2> {
+    pub fn parse(
+        attr_args: AttributeArgs,
+        defaults: Defaults,
+        fn_item: ItemFn,
+    ) -> Result<Self, TokenStream2> {
         let opts: FuncOpts = match FuncOpts::from_list(&attr_args) {
             Ok(v) => v,
             Err(e) => return Err(e.write_errors()),
         };
-        let (args, arities, output_span) = check_signature(&fn_item.decl)?;
+        let (args, arities, output_span) =
+            check_signature(&fn_item.decl, opts.rest, opts.optional, &opts.direct, &defaults)?;
+        if opts.raw_return && opts.user_ptr.is_some() {
+            let mut err = TokenStream2::new();
+            report(&mut err, &fn_item.ident, "raw_return cannot be used together with user_ptr");
+            return Err(err);
+        }
+        if opts.full_name.is_some() && (opts.name.is_some() || opts.mod_in_name.is_some()) {
+            let mut err = TokenStream2::new();
+            let message = "full_name cannot be used together with name or mod_in_name";
+            report(&mut err, &fn_item.ident, message);
+            return Err(err);
+        }
+        if opts.interactive.is_some() && opts.interactive_form.is_some() {
+            let mut err = TokenStream2::new();
+            let message = "interactive cannot be used together with interactive_form";
+            report(&mut err, &fn_item.ident, message);
+            return Err(err);
+        }
         let def = fn_item;
         Ok(Self { def, args, arities, output_span, opts })
     }
@@ -147,13 +314,31 @@ impl LispFunc {
                     // error is confusing (i.e expecting Env, found &Env).
                     args.append_all(quote_spanned!(span=> &**env,))
                 }
-                Arg::Val { span, access, nth } => {
+                Arg::Val { span, access, nth, name: ref arg_name, optional, ref default } => {
                     let name = util::arg("arg", nth);
+                    let convert = if self.opts.strict_args {
+                        quote_spanned! {span=>
+                            ::emacs::func::check_arg(env.get_arg(#nth).into_rust(), #arg_name)?
+                        }
+                    } else {
+                        quote_spanned!(span=> env.get_arg(#nth).into_rust()?)
+                    };
                     // TODO: Create a slice of `emacs_value` once and iterate through it, instead of
                     // using `get_arg`, which creates a slice each call.
                     bindings.append_all(match access {
+                        Access::Owned if default.is_some() => quote_spanned! {span=>
+                            let #name = if #nth < env.raw_args().len() { #convert } else { None };
+                            let #name = match #name {
+                                Some(v) => v,
+                                None => #default,
+                            };
+                        },
+                        // Omitted optional arguments are `None`, the same as `nil`.
+                        Access::Owned if optional => quote_spanned! {span=>
+                            let #name = if #nth < env.raw_args().len() { #convert } else { None };
+                        },
                         Access::Owned => quote_spanned! {span=>
-                            let #name = env.get_arg(#nth).into_rust()?;
+                            let #name = #convert;
                         },
                         // TODO: Support RwLock/Mutex (for the use case of sharing data with
                         // background Rust threads).
@@ -164,9 +349,29 @@ impl LispFunc {
                         Access::RefMut => quote_spanned! {span=>
                             let #name = &mut *env.get_arg(#nth).into_ref_mut()?;
                         },
+                        Access::Direct => quote_spanned! {span=>
+                            let #name: &_ = env.get_arg(#nth).into_rust()?;
+                        },
                     });
                     args.append_all(quote_spanned!(span=> #name,));
                 }
+                Arg::Rest { span, nth, name: ref arg_name, slice } => {
+                    let name = util::arg("arg", nth);
+                    bindings.append_all(quote_spanned! {span=>
+                        let #name: ::std::vec::Vec<_> = (#nth..env.raw_args().len())
+                            .map(|i| ::emacs::func::check_rest_arg(
+                                env.get_arg(i).into_rust(),
+                                #arg_name,
+                                i - #nth,
+                            ))
+                            .collect::<::emacs::Result<_>>()?;
+                    });
+                    args.append_all(if slice {
+                        quote_spanned!(span=> #name.as_slice(),)
+                    } else {
+                        quote_spanned!(span=> #name,)
+                    });
+                }
             }
         }
         let maybe_embed = match &self.opts.user_ptr {
@@ -187,16 +392,29 @@ impl LispFunc {
             },
         };
         // XXX: result can be (), but we can't easily know when.
-        let into_lisp = quote_spanned! {self.output_span=>
-            #[allow(clippy::unit_arg)]
-            ::emacs::IntoLisp::into_lisp(result, env)
+        let into_lisp = if self.opts.raw_return {
+            quote_spanned! {self.output_span=>
+                let result: ::emacs::raw::emacs_value = result;
+                Ok(unsafe { ::emacs::Value::new(result, env) })
+            }
+        } else {
+            quote_spanned! {self.output_span=>
+                #[allow(clippy::unit_arg)]
+                ::emacs::IntoLisp::into_lisp(result, env)
+            }
         };
         let inner = &self.def.ident;
         let wrapper = self.wrapper_ident();
+        let call = if self.opts.trace {
+            let key = self.trace_key();
+            quote!(::emacs::func::trace(#key, || #inner(#args))?)
+        } else {
+            quote!(#inner(#args)?)
+        };
         quote! {
             fn #wrapper(env: &::emacs::CallEnv) -> ::emacs::Result<::emacs::Value<'_>> {
                 #bindings
-                let result = #inner(#args)?;
+                let result = #call;
                 #maybe_embed
                 #into_lisp
             }
@@ -209,7 +427,11 @@ impl LispFunc {
         let define_wrapper = self.gen_wrapper();
         let wrapper = self.wrapper_ident();
         let exporter = self.exporter_ident();
-        let (min, max) = (self.arities.start, self.arities.end);
+        let min = self.arities.start;
+        let max = match self.arities.end {
+            usize::MAX => quote!(::emacs::func::VARIADIC),
+            max => quote!(#max),
+        };
         let doc = util::doc(&self.def);
         let path = match &self.opts.mod_in_name {
             None => {
@@ -225,21 +447,55 @@ impl LispFunc {
             Some(true) => quote!(module_path!()),
             Some(false) => quote!(""),
         };
-        let lisp_name = match &self.opts.name {
-            Some(name) => name.clone(),
-            None => util::lisp_name(&self.def.ident),
+        let lisp_name = match (&self.opts.full_name, &self.opts.name) {
+            (Some(name), _) | (None, Some(name)) => name.clone(),
+            (None, None) => util::lisp_name(&self.def.ident),
+        };
+        let prefix = match &self.opts.full_name {
+            Some(_) => quote!(""),
+            None => quote!(::emacs::globals::lisp_path(#path)),
+        };
+        let (define_stats, export_stats) = if self.opts.trace {
+            let key = self.trace_key();
+            let stats = self.stats_ident();
+            let stats_name = format!("{}-stats", lisp_name);
+            let stats_doc = format!(
+                "Return the number of calls to the Rust function `{}', and their total time.\n\n\
+                 The return value has the form (:calls N :total-time SECONDS).",
+                self.def.ident
+            );
+            (
+                quote! {
+                    fn #stats(env: &::emacs::CallEnv) -> ::emacs::Result<::emacs::Value<'_>> {
+                        ::emacs::func::trace_stats(env, #key)
+                    }
+                },
+                quote!(#stats_name => (#stats, 0..0, #stats_doc),),
+            )
+        } else {
+            (TokenStream2::new(), TokenStream2::new())
+        };
+        let make_interactive = match self.interactive_form() {
+            Some(form) => quote! {
+                let name = format!("{}{}", prefix, #lisp_name);
+                ::emacs::func::make_interactive(env, &name, #doc, #form)?;
+            },
+            None => TokenStream2::new(),
         };
         // TODO: Consider defining `extern "C" fn` directly instead of using export_functions! and
         // CallEnv wrapper.
         quote! {
             #define_wrapper
+            #define_stats
             fn #exporter(env: &::emacs::Env) -> ::emacs::Result<()> {
-                let prefix = ::emacs::globals::lisp_path(#path);
+                let prefix = #prefix;
                 ::emacs::export_functions! {
                     env, prefix, {
                         #lisp_name => (#wrapper, #min..#max, #doc),
+                        #export_stats
                     }
                 }
+                #make_interactive
                 Ok(())
             }
         }
@@ -269,10 +525,31 @@ impl LispFunc {
         }
     }
 
+    /// Returns the Lisp code of the interactive form, if the function is a command.
+    fn interactive_form(&self) -> Option<String> {
+        match &self.opts.interactive {
+            Some(Interactive(Some(spec))) => {
+                Some(format!("\"{}\"", spec.replace('\\', "\\\\").replace('"', "\\\"")))
+            }
+            Some(Interactive(None)) => Some("nil".to_owned()),
+            None => self.opts.interactive_form.clone(),
+        }
+    }
+
+    /// Generates the key under which call statistics are recorded, for `trace`.
+    fn trace_key(&self) -> TokenStream2 {
+        let name = format!("{}", self.def.ident);
+        quote!(concat!(module_path!(), "::", #name))
+    }
+
     fn wrapper_ident(&self) -> Ident {
         util::concat("__emr_O_", &self.def.ident)
     }
 
+    fn stats_ident(&self) -> Ident {
+        util::concat("__emrs_S_", &self.def.ident)
+    }
+
     fn exporter_ident(&self) -> Ident {
         util::concat("__emrs_E_", &self.def.ident)
     }
@@ -282,18 +559,43 @@ impl LispFunc {
     }
 }
 
-fn check_signature(decl: &FnDecl) -> Result<(Vec<Arg>, Range<usize>, Span), TokenStream2> {
+fn check_signature(
+    decl: &FnDecl,
+    rest: bool,
+    optional: bool,
+    direct: &Direct,
+    defaults: &Defaults,
+) -> Result<(Vec<Arg>, Range<usize>, Span), TokenStream2> {
     let mut i: usize = 0;
+    let mut max = None;
     let mut err = TokenStream2::new();
     let mut has_env = false;
     let mut args: Vec<Arg> = vec![];
     let errors = &mut err;
-    for fn_arg in &decl.inputs {
+    let last = decl.inputs.len().wrapping_sub(1);
+    for (position, fn_arg) in decl.inputs.iter().enumerate() {
         match fn_arg {
             FnArg::Captured(capt) => {
                 let ty = &capt.ty;
                 let span = fn_arg.span();
-                args.push(if is_env(ty) {
+                let name = match &capt.pat {
+                    syn::Pat::Ident(pat) => pat.ident.to_string(),
+                    pat => format!("{}", quote!(#pat)),
+                };
+                args.push(if rest && position == last && !is_env(ty) {
+                    let slice = match ty {
+                        syn::Type::Reference(syn::TypeReference { elem, .. }) => match **elem {
+                            syn::Type::Slice(_) => true,
+                            _ => false,
+                        },
+                        _ => false,
+                    };
+                    if !slice && !is_vec(ty) {
+                        report(errors, fn_arg, "The rest parameter must be a Vec<T> or a &[T]")
+                    }
+                    max = Some(usize::MAX);
+                    Arg::Rest { span, nth: i, name, slice }
+                } else if is_env(ty) {
                     match ty {
                         syn::Type::Reference(_) => (),
                         _ => report(errors, fn_arg, "Can only take an &Env, not an Env"),
@@ -308,12 +610,17 @@ fn check_signature(decl: &FnDecl) -> Result<(Vec<Arg>, Range<usize>, Span), Toke
                         syn::Type::Reference(syn::TypeReference { mutability, .. }) => {
                             match mutability {
                                 Some(_) => Access::RefMut,
+                                None if direct.0.contains(&name) => Access::Direct,
                                 None => Access::Ref,
                             }
                         }
                         _ => Access::Owned,
                     };
-                    let a = Arg::Val { span, access, nth: i };
+                    let default =
+                        defaults.0.iter().find(|(n, _)| *n == name).map(|(_, expr)| expr.clone());
+                    // Whether this is `&optional` is only known after seeing the next parameters.
+                    let optional = (optional && is_option(ty)) || default.is_some();
+                    let a = Arg::Val { span, access, nth: i, name, optional, default };
                     i += 1;
                     a
                 });
@@ -335,13 +642,72 @@ fn check_signature(decl: &FnDecl) -> Result<(Vec<Arg>, Range<usize>, Span), Toke
             decl.fn_token.span()
         }
     };
+    if rest && max.is_none() {
+        report(errors, &decl.fn_token, "rest requires a last parameter that is not &Env");
+    }
+    for name in &direct.0 {
+        let found = args.iter().any(|arg| match arg {
+            Arg::Val { name: n, access: Access::Direct, .. } => n == name,
+            _ => false,
+        });
+        if !found {
+            let message =
+                format!("No shared reference parameter named {} to access directly", name);
+            report(errors, &decl.fn_token, message);
+        }
+    }
+    for (name, _) in &defaults.0 {
+        let found = args.iter().any(|arg| match arg {
+            Arg::Val { name: n, .. } => n == name,
+            _ => false,
+        });
+        if !found {
+            report(errors, &decl.fn_token, format!("No parameter named {} to default", name));
+        }
+    }
+    // Only trailing parameters of type `Option<T>` (with the option `optional`), or with default
+    // values, are `&optional` in Lisp.
+    let mut min = i;
+    let mut trailing = true;
+    for arg in args.iter_mut().rev() {
+        if let Arg::Val { span, access, optional, default, .. } = arg {
+            trailing = trailing && *optional && match *access {
+                Access::Owned => true,
+                _ => false,
+            };
+            *optional = trailing;
+            if trailing {
+                min -= 1;
+            } else if default.is_some() {
+                let message = "Parameters with default values must be owned, and trailing";
+                errors.append_all(syn::Error::new(*span, message).to_compile_error());
+            }
+        }
+    }
     if err.is_empty() {
-        Ok((args, Range { start: i, end: i }, output_span))
+        Ok((args, Range { start: min, end: max.unwrap_or(i) }, output_span))
     } else {
         Err(err)
     }
 }
 
+fn is_option(ty: &syn::Type) -> bool {
+    is_path_to(ty, "Option")
+}
+
+fn is_vec(ty: &syn::Type) -> bool {
+    is_path_to(ty, "Vec")
+}
+
+fn is_path_to(ty: &syn::Type, name: &str) -> bool {
+    match ty {
+        syn::Type::Path(syn::TypePath { qself: None, ref path }) => {
+            path.segments.last().map_or(false, |segment| segment.value().ident == name)
+        }
+        _ => false,
+    }
+}
+
 // XXX
 fn is_env(ty: &syn::Type) -> bool {
     match ty {
@@ -5,11 +5,12 @@ extern crate proc_macro;
 
 use proc_macro::TokenStream;
 
-use syn::{self, AttributeArgs, ItemFn, parse_macro_input};
+use syn::{self, AttributeArgs, DeriveInput, ItemFn, parse_macro_input};
 
 mod util;
 mod module;
 mod func;
+mod lisp_data;
 
 /// Registers a function as the initializer, to be called when Emacs loads the module. Each dynamic
 /// module must have one and only one such function.
@@ -25,6 +26,9 @@ mod func;
 /// `#[module(separator = "/")]`.
 /// - `mod_in_name`: Whether to put module path in function names. Default to `true`. This can also
 /// be overridden for each individual function, by an option of the same name in [`#[defun]`].
+/// - `panic_condition`: Panics are signaled to Lisp as `rust-panic` by default. This option allows
+/// a condition in the package's own namespace to be used instead, which is defined as a sub-type of
+/// `rust-panic`. For example: `#[module(panic_condition = "my-pkg-panic")]`.
 ///
 /// [`#[defun]`]: attr.defun.html
 #[proc_macro_attribute]
@@ -50,6 +54,17 @@ pub fn module(attr_ts: TokenStream, item_ts: TokenStream) -> TokenStream {
 /// - A shared/mutable reference. This gives access to data structures that other module functions
 /// have created and embedded in the Lisp runtime (through `user-ptr` objects).
 ///
+/// - A shared reference to a [`Transfer`] value embedded directly in a `user-ptr` object, e.g.
+/// `&Arc<Mutex<T>>` (by returning an `Arc` from a function). The parameter must be listed in the
+/// option `direct`, e.g. `#[defun(direct(counter))]`. Unlike other references, it does not borrow
+/// through a `RefCell`. The `Arc` can be cloned to share the data with background Rust threads. An
+/// owned `Arc` parameter receives such a clone.
+///
+/// - A shared reference to a `Handle`, e.g. `&Handle<T>`, embedded directly in a `user-ptr` object
+/// (by returning a `Handle` from a function declared with `#[defun(user_ptr(direct))]`). Like
+/// `&Arc<T>`, it must be listed in the option `direct`. Lisp code can release its resource
+/// explicitly, through a function calling `Handle::close`.
+///
 /// - A Lisp [`Value`]. This allows holding off the conversion to Rust data structures until
 /// necessary, or working with values that don't have a meaningful representation in Rust, like Lisp
 /// lambdas.
@@ -58,6 +73,30 @@ pub fn module(attr_ts: TokenStream, item_ts: TokenStream) -> TokenStream {
 /// function's Lisp signature. This is unnecessary if there is already another parameter with type
 /// [`Value`], which allows accessing the runtime through `Value.env`.
 ///
+/// By default, failing to convert an owned argument propagates the conversion error as-is, e.g.
+/// `(wrong-type-argument integerp "1")`. With the option `strict_args`, the error is instead
+/// `rust-wrong-type-argument` (a sub-type of `wrong-type-argument`), whose data starts with the
+/// parameter's name, e.g. `(rust-wrong-type-argument y integerp "1")`.
+///
+/// With the option `optional`, trailing owned parameters of type `Option<T>` are optional in Lisp,
+/// i.e. they come after `&optional` in the function's Lisp signature. An omitted argument is
+/// converted into `None`, the same as `nil`. For example, with `#[defun(optional)]`, `fn greet(name:
+/// String, punct: Option<String>)` can be called as both `(greet "a")` and `(greet "a" "!")`.
+/// Without it, every parameter is required, and `nil` must be passed explicitly.
+///
+/// Trailing owned parameters can also be given default values with the option `defaults`, which
+/// makes them optional in Lisp. An omitted argument, or `nil`, is replaced by the default value,
+/// which can be any Rust expression, e.g. `#[defun(defaults(host = "localhost".to_owned(), port =
+/// 8080))]` on `fn connect(host: String, port: i64)`. The defaults are given to `#[defun]`, instead
+/// of as attributes on the parameters themselves (e.g. `#[default = 8080] port: i64`), because the
+/// version of `syn` used by this crate cannot parse attributes on function parameters.
+///
+/// With the option `rest`, the last parameter collects the remaining arguments, like `&rest` in
+/// Lisp. It must be either a `Vec<T>`, where `T` implements [`FromLisp`], or a `&[T]`, e.g.
+/// `&[Value]`. Failing to convert one of the remaining arguments signals
+/// `rust-wrong-type-argument`, whose data starts with the parameter's name and the argument's
+/// index among the remaining arguments, e.g. `(rust-wrong-type-argument numbers 2 integerp "3")`.
+///
 /// # Return Value
 ///
 /// The return type must be [`Result<T>`], where `T` is one of the following:
@@ -75,6 +114,33 @@ pub fn module(attr_ts: TokenStream, item_ts: TokenStream) -> TokenStream {
 ///
 /// - [`Value`]. This is mostly useful for returning an input parameter unchanged.
 ///
+/// With the option `raw_return`, the return type must instead be `Result<emacs_value>`, for a raw
+/// value that was already constructed through the raw module API. It is passed to Lisp as-is,
+/// skipping [`IntoLisp`]. The raw value must come from the `Env` of the current call.
+///
+/// # Interactive Commands
+///
+/// With the option `interactive_form`, the function becomes a command, whose arguments are computed
+/// by the given Lisp code when it is called interactively, e.g.
+/// `#[defun(interactive_form = "(list (read-string \"Name: \"))")]`. The code must evaluate to a
+/// list of arguments, or be a string literal using the codes of [`interactive`], e.g. `"\"p\""`.
+/// Since module functions cannot be interactive before Emacs 28, the function is wrapped in a Lisp
+/// lambda with this `interactive` form.
+///
+/// The option `interactive` is a shorthand for the common case of a spec string, e.g.
+/// `#[defun(interactive = "p")]`, or `#[defun(interactive = "sEnter name: ")]`. The string is used
+/// as-is, without Lisp escaping. A bare `#[defun(interactive)]` makes a command that takes no
+/// arguments when called interactively.
+///
+/// [`interactive`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Using-Interactive.html
+///
+/// # Tracing
+///
+/// With the option `trace`, calls to the function are counted and timed. The statistics can be
+/// queried through a generated Lisp function, whose name is the function's Lisp name followed by
+/// `-stats`. It returns a plist of the form `(:calls N :total-time SECONDS)`. This is intended for
+/// finding hot functions during development.
+///
 /// # Naming
 ///
 /// By default, the function's Lisp name has the form `<feature-prefix>[mod-prefix]<base-name>`.
@@ -87,6 +153,10 @@ pub fn module(attr_ts: TokenStream, item_ts: TokenStream) -> TokenStream {
 /// - `base-name` is the function's Rust name (with `_` replaced by `-`). This can be overridden
 /// with the option `name`, e.g. `#[defun(name = "foo:bar")]`.
 ///
+/// The whole name can instead be specified with the option `full_name`, which is used verbatim,
+/// without any prefix, e.g. `#[defun(full_name = "my-pkg-valid-p")]`. It cannot be combined with
+/// `name` or `mod_in_name`.
+///
 /// [`#[module]`]: attr.module.html
 /// [`Result<T>`]: /emacs/*/emacs/type.Result.html
 /// [`FromLisp`]: /emacs/*/emacs/trait.FromLisp.html
@@ -96,10 +166,171 @@ pub fn module(attr_ts: TokenStream, item_ts: TokenStream) -> TokenStream {
 /// [`Value`]: /emacs/*/emacs/struct.Value.html
 #[proc_macro_attribute]
 pub fn defun(attr_ts: TokenStream, item_ts: TokenStream) -> TokenStream {
+    let (attr_ts, defaults) = match func::Defaults::split(attr_ts) {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error().into(),
+    };
     let attr_args: AttributeArgs = parse_macro_input!(attr_ts);
     let fn_item: ItemFn = parse_macro_input!(item_ts);
-    match func::LispFunc::parse(attr_args, fn_item) {
+    match func::LispFunc::parse(attr_args, defaults, fn_item) {
         Ok(func) => func.render().into(),
         Err(e) => e.into(),
     }
 }
+
+/// Implements [`IntoLisp`] for a struct with named fields, converting it into a plist. Each key is
+/// the field's name (with `_` replaced by `-`), prefixed with `:`. Each value is converted using the
+/// field's own [`IntoLisp`] implementation, so fields whose types also derive it become nested
+/// plists.
+///
+/// A newtype (tuple struct with a single field), e.g. `struct UserId(i64)`, is instead converted
+/// transparently, the same way as its field.
+///
+/// Recursive types (e.g. a struct containing a `Box` of itself) are not supported.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use emacs::{FromLisp, IntoLisp};
+/// #[derive(IntoLisp, FromLisp)]
+/// struct Server {
+///     host: String,
+///     port: i64,
+/// }
+///
+/// #[derive(IntoLisp, FromLisp)]
+/// struct Config {
+///     server: Server,
+///     max_connections: i64,
+/// }
+/// ```
+///
+/// ```emacs-lisp
+/// (:server (:host "localhost" :port 8080) :max-connections 10)
+/// ```
+///
+/// [`IntoLisp`]: /emacs/*/emacs/trait.IntoLisp.html
+#[proc_macro_derive(IntoLisp)]
+pub fn derive_into_lisp(ts: TokenStream) -> TokenStream {
+    let input: DeriveInput = parse_macro_input!(ts);
+    match lisp_data::LispData::parse(input) {
+        Ok(data) => data.render_into_lisp().into(),
+        Err(e) => e.into(),
+    }
+}
+
+/// Implements [`FromLisp`] for a struct with named fields, converting it from a plist of the
+/// format described in [`IntoLisp`]. Each value is looked up with `plist-get`, so a missing key is
+/// treated as `nil`, which is only valid for fields of type `Option<T>`. A newtype is converted
+/// from the same Lisp value as its field.
+///
+/// [`FromLisp`]: /emacs/*/emacs/trait.FromLisp.html
+/// [`IntoLisp`]: derive.IntoLisp.html
+#[proc_macro_derive(FromLisp)]
+pub fn derive_from_lisp(ts: TokenStream) -> TokenStream {
+    let input: DeriveInput = parse_macro_input!(ts);
+    match lisp_data::LispData::parse(input) {
+        Ok(data) => data.render_from_lisp().into(),
+        Err(e) => e.into(),
+    }
+}
+
+/// Implements [`IntoLisp`] and [`FromLisp`] for a struct with named fields, mapping it to an
+/// existing `cl-defstruct` type.
+///
+/// Conversion into Lisp calls the type's keyword constructor, with each field's name (with `_`
+/// replaced by `-`) as the keyword. Conversion from Lisp checks the type with `cl-typep`, then
+/// reads the slots by index, so the fields must be declared in the same order as the slots.
+///
+/// # Options
+///
+/// - `name`: Name of the `cl-defstruct` type. This is required.
+/// - `constructor`: Name of the keyword constructor. Defaults to `make-NAME`.
+/// - `define`: Define the type when the module is loaded, with a slot for each field, instead of
+/// requiring Lisp code to define it. This makes the type's accessors, e.g. `NAME-FIELD`, available
+/// to Lisp code that uses the module.
+///
+/// # Examples
+///
+/// ```emacs-lisp
+/// (cl-defstruct point x y)
+/// ```
+///
+/// ```no_run
+/// # use emacs::LispRecord;
+/// #[derive(LispRecord)]
+/// #[lisp_record(name = "point")]
+/// struct Point {
+///     x: i64,
+///     y: i64,
+/// }
+/// ```
+///
+/// Letting the module define the type:
+///
+/// ```no_run
+/// # use emacs::LispRecord;
+/// #[derive(LispRecord)]
+/// #[lisp_record(name = "my-match", define)]
+/// struct Match {
+///     start: i64,
+///     end: i64,
+/// }
+/// ```
+///
+/// ```emacs-lisp
+/// (my-match-start (my-find "x")) ; Accessors are defined by the module.
+/// ```
+///
+/// [`IntoLisp`]: /emacs/*/emacs/trait.IntoLisp.html
+/// [`FromLisp`]: /emacs/*/emacs/trait.FromLisp.html
+#[proc_macro_derive(LispRecord, attributes(lisp_record))]
+pub fn derive_lisp_record(ts: TokenStream) -> TokenStream {
+    let input: DeriveInput = parse_macro_input!(ts);
+    match lisp_data::LispRecord::parse(input) {
+        Ok(record) => record.render().into(),
+        Err(e) => e.into(),
+    }
+}
+
+/// Implements [`IntoLisp`] and [`FromLisp`] for a struct with named fields, like the derives of the
+/// same names, and defines a Lisp constructor `make-NAME` for it, taking the fields as keyword
+/// arguments.
+///
+/// The constructor is exported like a [`#[defun]`], with the module's prefix. It converts its
+/// arguments into the struct, checking their types, then returns the struct converted back into a
+/// plist, with all of its keys. Missing keywords are treated as `nil`, like in [`FromLisp`].
+///
+/// # Options
+///
+/// - `name`: Name of the type in Lisp. Defaults to the struct's name, converted to kebab-case,
+/// e.g. `server-config` for `ServerConfig`.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use emacs::LispType;
+/// #[derive(LispType)]
+/// struct ServerConfig {
+///     host: String,
+///     port: i64,
+///     timeout: Option<i64>,
+/// }
+/// ```
+///
+/// ```emacs-lisp
+/// (my-make-server-config :host "localhost" :port 8080)
+/// ;; => (:host "localhost" :port 8080 :timeout nil)
+/// ```
+///
+/// [`IntoLisp`]: /emacs/*/emacs/trait.IntoLisp.html
+/// [`FromLisp`]: /emacs/*/emacs/trait.FromLisp.html
+/// [`#[defun]`]: attr.defun.html
+#[proc_macro_derive(LispType, attributes(lisp_type))]
+pub fn derive_lisp_type(ts: TokenStream) -> TokenStream {
+    let input: DeriveInput = parse_macro_input!(ts);
+    match lisp_data::LispType::parse(input) {
+        Ok(lisp_type) => lisp_type.render().into(),
+        Err(e) => e.into(),
+    }
+}
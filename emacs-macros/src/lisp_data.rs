@@ -0,0 +1,354 @@
+use darling::FromMeta;
+use quote::{quote, quote_spanned};
+use syn::{
+    export::{Span, TokenStream2},
+    spanned::Spanned,
+    Data, DeriveInput, Fields, FieldsNamed, FieldsUnnamed, Ident,
+};
+
+use crate::util::{self, report};
+
+/// A struct with named fields, represented in Lisp either as a plist, whose keys are the fields'
+/// names (with `_` replaced by `-`), prefixed with `:`, or as an instance of a `cl-defstruct` type.
+/// A newtype (tuple struct with a single field) is instead represented the same way as its field.
+#[derive(Debug)]
+pub struct LispData {
+    ident: Ident,
+    fields: Vec<Field>,
+    /// Span of the single field, if this is a newtype.
+    newtype: Option<Span>,
+}
+
+#[derive(Debug)]
+struct Field {
+    ident: Ident,
+    key: String,
+    span: Span,
+}
+
+#[derive(Debug, FromMeta)]
+struct RecordOpts {
+    /// Name of the `cl-defstruct` type.
+    name: String,
+    /// Name of the constructor taking keyword arguments. `None` means `make-NAME`.
+    #[darling(default)]
+    constructor: Option<String>,
+    /// Whether the type should be defined by the module when it is loaded, instead of by Lisp code.
+    #[darling(default)]
+    define: bool,
+}
+
+#[derive(Debug)]
+pub struct LispRecord {
+    data: LispData,
+    opts: RecordOpts,
+}
+
+#[derive(Debug, Default, FromMeta)]
+struct TypeOpts {
+    /// Name of the type in Lisp, used in the constructor's name. `None` means the struct's name,
+    /// converted to kebab-case.
+    #[darling(default)]
+    name: Option<String>,
+}
+
+/// A struct with named fields, represented in Lisp as a plist, like [`LispData`], with a Lisp
+/// constructor taking the fields as keyword arguments.
+#[derive(Debug)]
+pub struct LispType {
+    data: LispData,
+    opts: TypeOpts,
+}
+
+impl LispData {
+    pub fn parse(input: DeriveInput) -> Result<Self, TokenStream2> {
+        let mut errors = TokenStream2::new();
+        if !input.generics.params.is_empty() {
+            report(&mut errors, &input.generics, "Generic types are not supported");
+        }
+        let mut newtype = None;
+        let fields = match &input.data {
+            Data::Struct(data) => match &data.fields {
+                Fields::Unnamed(FieldsUnnamed { unnamed, .. }) if unnamed.len() == 1 => {
+                    newtype = Some(unnamed[0].span());
+                    vec![]
+                }
+                Fields::Named(FieldsNamed { named, .. }) => named
+                    .iter()
+                    .filter_map(|f| f.ident.as_ref().map(|ident| (ident, f.span())))
+                    .map(|(ident, span)| Field {
+                        ident: ident.clone(),
+                        key: format!(":{}", util::lisp_name(ident)),
+                        span,
+                    })
+                    .collect(),
+                _ => {
+                    report(
+                        &mut errors,
+                        &input.ident,
+                        "Only structs with named fields, or newtypes, are supported",
+                    );
+                    vec![]
+                }
+            },
+            _ => {
+                report(&mut errors, &input.ident, "Only structs are supported");
+                vec![]
+            }
+        };
+        if errors.is_empty() {
+            Ok(Self { ident: input.ident, fields, newtype })
+        } else {
+            Err(errors)
+        }
+    }
+
+    pub fn render_into_lisp(&self) -> TokenStream2 {
+        let ident = &self.ident;
+        if let Some(span) = self.newtype {
+            return quote_spanned! {span=>
+                impl<'e> ::emacs::IntoLisp<'e> for #ident {
+                    fn into_lisp(
+                        self,
+                        env: &'e ::emacs::Env,
+                    ) -> ::emacs::Result<::emacs::Value<'e>> {
+                        ::emacs::IntoLisp::into_lisp(self.0, env)
+                    }
+                }
+            };
+        }
+        let items = self.keyword_args();
+        quote! {
+            impl<'e> ::emacs::IntoLisp<'e> for #ident {
+                fn into_lisp(self, env: &'e ::emacs::Env) -> ::emacs::Result<::emacs::Value<'e>> {
+                    env.list(&[#items])
+                }
+            }
+        }
+    }
+
+    pub fn render_from_lisp(&self) -> TokenStream2 {
+        let ident = &self.ident;
+        if let Some(span) = self.newtype {
+            return quote_spanned! {span=>
+                impl<'e> ::emacs::FromLisp<'e> for #ident {
+                    fn from_lisp(value: ::emacs::Value<'e>) -> ::emacs::Result<Self> {
+                        Ok(#ident(value.into_rust()?))
+                    }
+                }
+            };
+        }
+        let mut fields = TokenStream2::new();
+        for Field { ident, key, span } in &self.fields {
+            fields.extend(quote_spanned! {*span=>
+                #ident: env.call("plist-get", &[value, env.intern(#key)?])?.into_rust()?,
+            });
+        }
+        quote! {
+            impl<'e> ::emacs::FromLisp<'e> for #ident {
+                fn from_lisp(value: ::emacs::Value<'e>) -> ::emacs::Result<Self> {
+                    let env = value.env;
+                    Ok(#ident { #fields })
+                }
+            }
+        }
+    }
+
+    /// Alternating keys and values, to be used as arguments of `list` or of a `cl-defstruct`
+    /// constructor.
+    fn keyword_args(&self) -> TokenStream2 {
+        let mut items = TokenStream2::new();
+        for Field { ident, key, span } in &self.fields {
+            items.extend(quote_spanned! {*span=>
+                env.intern(#key)?,
+                ::emacs::IntoLisp::into_lisp(self.#ident, env)?,
+            });
+        }
+        items
+    }
+}
+
+impl LispRecord {
+    pub fn parse(input: DeriveInput) -> Result<Self, TokenStream2> {
+        let attr = input.attrs.iter().find(|attr| {
+            attr.path.segments.len() == 1 && attr.path.segments[0].ident == "lisp_record"
+        });
+        let opts = match attr {
+            Some(attr) => match attr.parse_meta() {
+                Ok(meta) => RecordOpts::from_meta(&meta).map_err(|e| e.write_errors())?,
+                Err(e) => return Err(e.to_compile_error()),
+            },
+            None => {
+                let mut errors = TokenStream2::new();
+                report(&mut errors, &input.ident, "Missing #[lisp_record(name = \"...\")]");
+                return Err(errors);
+            }
+        };
+        let ident = input.ident.clone();
+        let data = LispData::parse(input)?;
+        if data.newtype.is_some() {
+            let mut errors = TokenStream2::new();
+            report(&mut errors, &ident, "Only structs with named fields are supported");
+            return Err(errors);
+        }
+        Ok(Self { data, opts })
+    }
+
+    pub fn render(&self) -> TokenStream2 {
+        let ident = &self.data.ident;
+        let name = &self.opts.name;
+        let predicate = format!("{}-p", name);
+        let constructor = match &self.opts.constructor {
+            Some(constructor) => constructor.clone(),
+            None => format!("make-{}", name),
+        };
+        let args = self.data.keyword_args();
+        let mut fields = TokenStream2::new();
+        // Slot 0 holds the type tag.
+        for (i, Field { ident, span, .. }) in self.data.fields.iter().enumerate() {
+            let index = i as i64 + 1;
+            fields.extend(quote_spanned! {*span=>
+                #ident: env.call("aref", &[value, ::emacs::IntoLisp::into_lisp(#index, env)?])?
+                    .into_rust()?,
+            });
+        }
+        let define = if self.opts.define { self.gen_definer(&constructor) } else { quote!() };
+        quote! {
+            #define
+
+            impl<'e> ::emacs::IntoLisp<'e> for #ident {
+                fn into_lisp(self, env: &'e ::emacs::Env) -> ::emacs::Result<::emacs::Value<'e>> {
+                    env.call(#constructor, &[#args])
+                }
+            }
+
+            impl<'e> ::emacs::FromLisp<'e> for #ident {
+                fn from_lisp(value: ::emacs::Value<'e>) -> ::emacs::Result<Self> {
+                    let env = value.env;
+                    let ty = env.intern(#name)?;
+                    if !env.is_not_nil(env.call("cl-typep", &[value, ty])?) {
+                        return Err(env.wrong_type(#predicate, value));
+                    }
+                    Ok(#ident { #fields })
+                }
+            }
+        }
+    }
+
+    /// Generates a function that defines the `cl-defstruct` type, and registers it to be called
+    /// when the module is loaded, like the exporters of `#[defun]` functions.
+    fn gen_definer(&self, constructor: &str) -> TokenStream2 {
+        let ident = &self.data.ident;
+        let definer = util::concat("__emrs_D_", ident);
+        let registrator = util::concat("__emrs_R_", ident);
+        let init_fns = util::init_fns_path();
+        let slots: Vec<_> = self.data.fields.iter().map(|f| util::lisp_name(&f.ident)).collect();
+        let source = format!(
+            "(progn (require 'cl-lib) (cl-defstruct ({} (:constructor {})) {}))",
+            self.opts.name,
+            constructor,
+            slots.join(" "),
+        );
+        let key = format!("{}::cl-defstruct", ident);
+        quote! {
+            #[allow(non_snake_case)]
+            fn #definer(env: &::emacs::Env) -> ::emacs::Result<()> {
+                env.eval(#source)?;
+                Ok(())
+            }
+
+            #[allow(non_snake_case)]
+            #[::emacs::deps::ctor::ctor]
+            fn #registrator() {
+                let mut full_path = module_path!().to_owned();
+                full_path.push_str("::");
+                full_path.push_str(#key);
+                let mut funcs = #init_fns.lock()
+                    .expect("Failed to acquire a write lock on map of initializers");
+                funcs.insert(full_path, ::std::boxed::Box::new(#definer));
+            }
+        }
+    }
+}
+
+impl LispType {
+    pub fn parse(input: DeriveInput) -> Result<Self, TokenStream2> {
+        let attr = input.attrs.iter().find(|attr| {
+            attr.path.segments.len() == 1 && attr.path.segments[0].ident == "lisp_type"
+        });
+        let opts = match attr {
+            Some(attr) => match attr.parse_meta() {
+                Ok(meta) => TypeOpts::from_meta(&meta).map_err(|e| e.write_errors())?,
+                Err(e) => return Err(e.to_compile_error()),
+            },
+            None => TypeOpts::default(),
+        };
+        let ident = input.ident.clone();
+        let data = LispData::parse(input)?;
+        if data.newtype.is_some() {
+            let mut errors = TokenStream2::new();
+            report(&mut errors, &ident, "Only structs with named fields are supported");
+            return Err(errors);
+        }
+        Ok(Self { data, opts })
+    }
+
+    pub fn render(&self) -> TokenStream2 {
+        let into_lisp = self.data.render_into_lisp();
+        let from_lisp = self.data.render_from_lisp();
+        let constructor = self.gen_constructor();
+        quote! {
+            #into_lisp
+            #from_lisp
+            #constructor
+        }
+    }
+
+    /// Generates a `#[defun]` that takes the fields as keyword arguments, converts them into the
+    /// struct, checking their types, and converts the struct back into a (complete) plist.
+    fn gen_constructor(&self) -> TokenStream2 {
+        let ident = &self.data.ident;
+        let kebab = kebab_case(&ident.to_string());
+        // Generated items derived from the function's name must also be in snake case.
+        let constructor =
+            Ident::new(&format!("__emrs_make_{}", kebab.replace('-', "_")), Span::call_site());
+        let name = match &self.opts.name {
+            Some(name) => name.clone(),
+            None => kebab,
+        };
+        let lisp_name = format!("make-{}", name);
+        let keys: Vec<_> = self.data.fields.iter().map(|f| f.key.as_str()).collect();
+        let doc = format!(
+            "Make a `{}' from the keyword arguments ARGS.\n\nThe keywords are {}.",
+            name,
+            keys.join(", "),
+        );
+        quote! {
+            #[::emacs::defun(rest, name = #lisp_name)]
+            #[doc = #doc]
+            fn #constructor<'e>(
+                env: &'e ::emacs::Env,
+                args: ::std::vec::Vec<::emacs::Value<'e>>,
+            ) -> ::emacs::Result<::emacs::Value<'e>> {
+                let value: #ident = env.list(&args)?.into_rust()?;
+                ::emacs::IntoLisp::into_lisp(value, env)
+            }
+        }
+    }
+}
+
+/// Converts a Rust type name to kebab-case, e.g. `ServerConfig` to `server-config`.
+fn kebab_case(name: &str) -> String {
+    let mut kebab = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                kebab.push('-');
+            }
+            kebab.extend(c.to_lowercase());
+        } else {
+            kebab.push(c);
+        }
+    }
+    kebab
+}
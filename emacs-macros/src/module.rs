@@ -25,6 +25,10 @@ struct ModuleOpts {
     /// Whether module path should be used to construct functions' full Lisp name.
     #[darling(default = "default::mod_in_name")]
     mod_in_name: bool,
+    /// Condition to signal for panics, defined as a sub-type of `rust-panic`. `None` means
+    /// `rust-panic` itself.
+    #[darling(default)]
+    panic_condition: Option<String>,
 }
 
 #[derive(Debug)]
@@ -139,6 +143,12 @@ impl Module {
         let configure_mod_in_name = quote! {
             #mod_in_name.store(#crate_mod_in_name, ::std::sync::atomic::Ordering::Relaxed);
         };
+        let set_panic_condition = match &self.opts.panic_condition {
+            Some(name) => quote! {
+                ::emacs::globals::set_panic_condition(#env, #name)?;
+            },
+            None => TokenStream2::new(),
+        };
         let export_lisp_funcs = quote! {
             {
                 let funcs = #init_fns.try_lock()
@@ -154,6 +164,7 @@ impl Module {
                 #set_feature
                 #set_prefix
                 #configure_mod_in_name
+                #set_panic_condition
                 #export_lisp_funcs
                 #hook(#env)?;
                 #env.provide(&#feature)
@@ -23,6 +23,9 @@ pub fn report<T: ToTokens, U: Display>(errors: &mut TokenStream2, ts: T, msg: U)
     errors.append_all(syn::Error::new_spanned(ts, msg).to_compile_error());
 }
 
+/// Collects the doc comments of a function into a docstring, one line per `#[doc]` attribute.
+/// Only the space conventionally separating `///` from the text is removed, so indentation, e.g.
+/// of code examples, is preserved.
 // TODO: Report errors.
 // TODO: Use syn::Parse?
 pub fn doc(fn_item: &ItemFn) -> String {
@@ -31,7 +34,8 @@ pub fn doc(fn_item: &ItemFn) -> String {
         if let Ok(syn::Meta::NameValue(mnv)) = attr.parse_meta() {
             if mnv.ident == "doc" {
                 if let syn::Lit::Str(ls) = mnv.lit {
-                    doc.push(ls.value().trim_start_matches(' ').to_owned());
+                    let line = ls.value();
+                    doc.push(if line.starts_with(' ') { line[1..].to_owned() } else { line });
                 }
             }
         }
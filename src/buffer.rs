@@ -0,0 +1,127 @@
+use super::error::Result;
+use super::symbol::CoreSymbol;
+use super::{Env, FromLisp, IntoLisp, Value};
+
+/// A Lisp buffer, following the convention of functions that take "a buffer or the name of a
+/// buffer", like `set-buffer`.
+///
+/// Converting a buffer from Lisp returns it as-is. A string is resolved through `get-buffer`,
+/// signaling `(error "No such buffer NAME")` if there is no buffer with that name. Anything else
+/// signals `(wrong-type-argument stringp VALUE)`.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use emacs::{defun, Buffer, Env, Result};
+/// /// Return the size of BUFFER-OR-NAME.
+/// #[defun]
+/// fn size(env: &Env, buffer: Buffer<'_>) -> Result<i64> {
+///     env.call("buffer-size", &[buffer.value()])?.into_rust()
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Buffer<'e>(Value<'e>);
+
+impl<'e> Buffer<'e> {
+    /// Returns the underlying Lisp buffer.
+    pub fn value(&self) -> Value<'e> {
+        self.0
+    }
+}
+
+impl<'e> FromLisp<'e> for Buffer<'e> {
+    fn from_lisp(value: Value<'e>) -> Result<Self> {
+        let env = value.env;
+        if env.is_not_nil(call_lisp!(env, "bufferp", value)?) {
+            return Ok(Buffer(value));
+        }
+        let buffer = call_lisp!(env, "get-buffer", value)?;
+        if !env.is_not_nil(buffer) {
+            let name: String = value.into_rust()?;
+            return Err(env.error(&format!("No such buffer {}", name)));
+        }
+        Ok(Buffer(buffer))
+    }
+}
+
+impl<'e> IntoLisp<'e> for Buffer<'e> {
+    fn into_lisp(self, _: &'e Env) -> Result<Value<'e>> {
+        Ok(self.0)
+    }
+}
+
+/// Buffer helpers. See [Buffers].
+///
+/// [Buffers]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Buffers.html
+impl Env {
+    /// Calls `f` with a new buffer temporarily made current, by a `with-temp-buffer` form evaluated
+    /// in Lisp. The previous current buffer is restored, and the temporary buffer is killed after
+    /// `f` returns, whether it succeeds, fails, or panics.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, IntoLisp, Result};
+    /// fn render(env: &Env, lines: &[&str]) -> Result<String> {
+    ///     env.with_temp_buffer(|env| {
+    ///         for line in lines {
+    ///             env.call("insert", &[line.into_lisp(env)?, "\n".into_lisp(env)?])?;
+    ///         }
+    ///         env.call("buffer-string", &[])?.into_rust()
+    ///     })
+    /// }
+    /// ```
+    pub fn with_temp_buffer<T, F>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&Env) -> Result<T>,
+    {
+        self.call_within(|body| self.list(&[self.intern("with-temp-buffer")?, body]), || f(self))
+    }
+
+    /// Returns all live buffers, like `buffer-list`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, Result};
+    /// fn modified_buffer_names(env: &Env) -> Result<Vec<String>> {
+    ///     let mut names = vec![];
+    ///     for buffer in env.buffer_list()? {
+    ///         if env.is_not_nil(env.call("buffer-modified-p", &[buffer])?) {
+    ///             names.push(env.call("buffer-name", &[buffer])?.into_rust()?);
+    ///         }
+    ///     }
+    ///     Ok(names)
+    /// }
+    /// ```
+    pub fn buffer_list(&self) -> Result<Vec<Value<'_>>> {
+        call_lisp!(self, "buffer-list")?.into_rust()
+    }
+
+    /// Calls `f` with `buffer` temporarily made current, by a `with-current-buffer` form evaluated
+    /// in Lisp. The previous current buffer is restored after `f` returns, whether it succeeds,
+    /// fails, or panics, unless it has been killed in the meantime.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, Result};
+    /// fn total_size(env: &Env) -> Result<i64> {
+    ///     let mut total = 0;
+    ///     for buffer in env.buffer_list()? {
+    ///         total += env.with_buffer(buffer, |env| env.call("buffer-size", &[])?.into_rust())?;
+    ///     }
+    ///     Ok(total)
+    /// }
+    /// ```
+    pub fn with_buffer<T, F>(&self, buffer: Value<'_>, f: F) -> Result<T>
+    where
+        F: FnOnce(&Env) -> Result<T>,
+    {
+        let buffer = self.list(&[self.core_symbol(CoreSymbol::Quote)?, buffer])?;
+        self.call_within(
+            |body| self.list(&[self.intern("with-current-buffer")?, buffer, body]),
+            || f(self),
+        )
+    }
+}
@@ -0,0 +1,131 @@
+use std::cell::RefCell;
+use std::marker::PhantomData;
+
+use super::error::Result;
+use super::{Closure, Env, IntoLisp, Transfer, Value};
+
+/// Rust state associated with buffers, e.g. the state of a buffer-local minor mode.
+///
+/// The state of each buffer is stored in a buffer-local variable, as a `user-ptr` object. When a
+/// buffer is killed, its state is dropped immediately, through a buffer-local `kill-buffer-hook`.
+/// Otherwise, e.g. after `kill-all-local-variables`, it is dropped when the `user-ptr` object is
+/// garbage-collected.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use emacs::{defun, BufferLocal, Env, Result};
+/// struct ParseState {
+///     dirty_from: i64,
+/// }
+///
+/// static PARSE_STATE: BufferLocal<ParseState> = BufferLocal::new("my-parse--state");
+///
+/// #[defun]
+/// fn mode_enable(env: &Env) -> Result<()> {
+///     PARSE_STATE.set(env, ParseState { dirty_from: 1 })
+/// }
+///
+/// #[defun]
+/// fn mode_disable(env: &Env) -> Result<()> {
+///     PARSE_STATE.take(env)?;
+///     Ok(())
+/// }
+///
+/// #[defun]
+/// fn after_change(env: &Env, start: i64) -> Result<()> {
+///     PARSE_STATE.with(env, |state| {
+///         if let Some(state) = state {
+///             state.dirty_from = state.dirty_from.min(start);
+///         }
+///     })
+/// }
+/// ```
+pub struct BufferLocal<T> {
+    name: &'static str,
+    _type: PhantomData<fn() -> T>,
+}
+
+/// The state of one buffer, embedded in a `user-ptr` object. It is emptied when the buffer is
+/// killed, so that the state doesn't wait for the GC to be dropped.
+struct Slot<T>(RefCell<Option<T>>);
+
+impl<T> Transfer for Slot<T> {
+    fn type_name() -> &'static str {
+        "BufferLocal"
+    }
+}
+
+impl<T> BufferLocal<T> {
+    /// Creates a handle to the state stored in the buffer-local variable `name`.
+    pub const fn new(name: &'static str) -> Self {
+        BufferLocal { name, _type: PhantomData }
+    }
+
+    /// Returns the name of the buffer-local variable holding the state.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl<T: 'static> BufferLocal<T> {
+    /// Sets the state of the current buffer to `value`, dropping the previous state, if any.
+    pub fn set(&self, env: &Env, value: T) -> Result<()> {
+        if let Some(slot) = self.slot(env)? {
+            slot.0.try_borrow_mut()?.replace(value);
+            return Ok(());
+        }
+        let symbol = env.intern(self.name)?;
+        let slot = Box::new(Slot(RefCell::new(Some(value)))).into_lisp(env)?;
+        call_lisp!(env, "make-local-variable", symbol)?;
+        call_lisp!(env, "set", symbol, slot)?;
+        let name = self.name;
+        // The cleanup function is only referenced by the buffer's hook, so it is freed by the GC
+        // after the buffer is killed.
+        let cleanup = Closure::new(0..0, move |env| {
+            BufferLocal::<T>::new(name).take(env)?;
+            env.nil()
+        })
+        .into_lisp(env)?;
+        env.add_hook("kill-buffer-hook", cleanup, true)
+    }
+
+    /// Calls `f` with a mutable reference to the state of the current buffer, or `None` if it has
+    /// none.
+    pub fn with<R, F>(&self, env: &Env, f: F) -> Result<R>
+    where
+        F: FnOnce(Option<&mut T>) -> R,
+    {
+        match self.slot(env)? {
+            Some(slot) => Ok(f(slot.0.try_borrow_mut()?.as_mut())),
+            None => Ok(f(None)),
+        }
+    }
+
+    /// Removes the state of the current buffer, returning it.
+    pub fn take(&self, env: &Env) -> Result<Option<T>> {
+        match self.slot(env)? {
+            Some(slot) => Ok(slot.0.try_borrow_mut()?.take()),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns whether the current buffer has a state.
+    pub fn is_set(&self, env: &Env) -> Result<bool> {
+        self.with(env, |state| state.is_some())
+    }
+
+    fn slot<'e>(&self, env: &'e Env) -> Result<Option<&'e Slot<T>>> {
+        let symbol = env.intern(self.name)?;
+        if !env.is_not_nil(call_lisp!(env, "local-variable-p", symbol)?) {
+            return Ok(None);
+        }
+        let value: Value<'e> = call_lisp!(env, "symbol-value", symbol)?;
+        if !env.is_not_nil(value) {
+            return Ok(None);
+        }
+        // Safety: The `user-ptr` object, and thus the slot, is kept alive by `value`, which lives
+        // as long as `env`.
+        Ok(Some(unsafe { &*env.get_raw_pointer::<Slot<T>>(value.raw)? }))
+    }
+}
@@ -0,0 +1,91 @@
+use super::error::Result;
+use super::{Env, FromLisp, IntoLisp, Value};
+
+/// Bytes represented in Lisp as a vector of integers between 0 and 255, instead of a unibyte
+/// string. This is convenient for Lisp code that manipulates individual bytes.
+///
+/// Converting from anything other than a vector signals `(wrong-type-argument vectorp VALUE)`. An
+/// integer outside of the valid range signals `(args-out-of-range INTEGER 0 255)`.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use emacs::{defun, ByteVector, Result};
+/// #[defun]
+/// fn checksum(bytes: ByteVector) -> Result<i64> {
+///     Ok(bytes.0.iter().fold(0, |sum, b| (sum + i64::from(*b)) % 256))
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ByteVector(pub Vec<u8>);
+
+impl IntoLisp<'_> for ByteVector {
+    fn into_lisp(self, env: &Env) -> Result<Value<'_>> {
+        let items =
+            self.0.iter().map(|b| i64::from(*b).into_lisp(env)).collect::<Result<Vec<_>>>()?;
+        env.call("vector", &items)
+    }
+}
+
+impl FromLisp<'_> for ByteVector {
+    fn from_lisp(value: Value<'_>) -> Result<Self> {
+        let env = value.env;
+        if !env.is_not_nil(call_lisp!(env, "vectorp", value)?) {
+            return Err(env.wrong_type("vectorp", value));
+        }
+        let size = raw_call!(env, vec_size, value.raw)?;
+        let mut bytes = Vec::with_capacity(size as usize);
+        for i in 0..size {
+            let item = raw_call_value!(env, vec_get, value.raw, i)?;
+            let byte: i64 = item.into_rust()?;
+            if !(0..=255).contains(&byte) {
+                let (min, max) = (0.into_lisp(env)?, 255.into_lisp(env)?);
+                return Err(env.make_signal("args-out-of-range", &[item, min, max])?);
+            }
+            bytes.push(byte as u8);
+        }
+        Ok(ByteVector(bytes))
+    }
+}
+
+impl Env {
+    /// Encodes the given bytes as a base64 string (without line breaks), using
+    /// `base64-encode-string`.
+    pub fn base64_encode(&self, bytes: &[u8]) -> Result<String> {
+        let string = self.make_unibyte_string(bytes)?;
+        let no_line_break = true.into_lisp(self)?;
+        call_lisp!(self, "base64-encode-string", string, no_line_break)?.into_rust()
+    }
+
+    /// Decodes the given base64 string into bytes, using `base64-decode-string`. Invalid input
+    /// signals `error`.
+    pub fn base64_decode(&self, s: &str) -> Result<Vec<u8>> {
+        let string = s.into_lisp(self)?;
+        let decoded = call_lisp!(self, "base64-decode-string", string)?;
+        self.unibyte_string_bytes(decoded)
+    }
+
+    /// Creates a unibyte Lisp string holding the given raw bytes. Unlike `make_string`, this does
+    /// not require the bytes to be valid UTF-8.
+    pub(crate) fn make_unibyte_string(&self, bytes: &[u8]) -> Result<Value<'_>> {
+        let args =
+            bytes.iter().map(|b| i64::from(*b).into_lisp(self)).collect::<Result<Vec<_>>>()?;
+        self.call("unibyte-string", &args)
+    }
+
+    /// Returns the raw bytes of a unibyte Lisp string.
+    pub(crate) fn unibyte_string_bytes(&self, string: Value<'_>) -> Result<Vec<u8>> {
+        // `copy_string_contents` would encode the string as UTF-8, so go through a vector of
+        // integers instead.
+        let vector = call_lisp!(self, "vconcat", string)?;
+        let size = raw_call!(self, vec_size, vector.raw)?;
+        let mut bytes = Vec::with_capacity(size as usize);
+        for i in 0..size {
+            let raw = raw_call!(self, vec_get, vector.raw, i)?;
+            // Safety: Integers are not GC-managed, so there's no need to protect them.
+            let byte: i64 = unsafe { Value::new(raw, self) }.into_rust()?;
+            bytes.push(byte as u8);
+        }
+        Ok(bytes)
+    }
+}
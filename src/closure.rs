@@ -0,0 +1,208 @@
+use std::cell::{Cell, RefCell};
+use std::mem;
+use std::ops::Range;
+use std::ptr;
+
+use emacs_module::{emacs_env, emacs_value, EmacsSubr};
+
+use super::error::Result;
+use super::func::{HandleCall, Manage, VARIADIC};
+use super::{CallEnv, Env, IntoLisp, Transfer, Value};
+
+type Function<'a> = dyn FnMut(&CallEnv) -> Result<Value<'_>> + 'a;
+type SharedFunction = dyn Fn(&CallEnv) -> Result<Value<'_>>;
+
+/// The Rust side of a [`Closure`]. A `FnMut` closure is borrowed mutably while it runs, so it
+/// cannot be called recursively. A `Fn` closure can.
+///
+/// [`Closure`]: struct.Closure.html
+enum Body {
+    Unique(RefCell<Box<Function<'static>>>),
+    Shared(Box<SharedFunction>),
+}
+
+/// A Rust closure that can be returned to Lisp as a function, e.g. from a [`#[defun]`].
+///
+/// Unlike functions exported by [`#[defun]`], a closure can capture state computed at runtime. The
+/// state is owned by the GC, and is dropped when the Lisp function is garbage-collected.
+///
+/// The closure must not call itself recursively (through Lisp). Doing so signals an error instead.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use emacs::{defun, Closure, IntoLisp, Result};
+/// #[defun]
+/// fn make_adder(x: i64) -> Result<Closure> {
+///     Ok(Closure::new(1..1, move |env| {
+///         let y: i64 = env.parse_arg(0)?;
+///         (x + y).into_lisp(env)
+///     }))
+/// }
+/// ```
+///
+/// ```emacs-lisp
+/// (funcall (make-adder 3) 4) ; 7
+/// ```
+///
+/// [`#[defun]`]: /emacs-macros/*/emacs_macros/attr.defun.html
+pub struct Closure {
+    arities: Range<usize>,
+    body: Body,
+}
+
+/// The state of a [`Closure`] after it is converted into Lisp, embedded in a `user-ptr` object.
+///
+/// [`Closure`]: struct.Closure.html
+struct State(Body);
+
+impl Transfer for State {
+    fn type_name() -> &'static str {
+        "Closure"
+    }
+}
+
+/// A Rust closure lent to Lisp by [`with_borrowed_function`], embedded in a `user-ptr` object. It
+/// is taken out while being called, so that it is not called recursively, and cleared when
+/// `with_borrowed_function` returns, so that calling the Lisp function afterwards signals an error,
+/// instead of using a dangling pointer.
+///
+/// [`with_borrowed_function`]: struct.Env.html#method.with_borrowed_function
+struct Loan(Cell<Option<*mut Function<'static>>>);
+
+impl Transfer for Loan {
+    fn type_name() -> &'static str {
+        "BorrowedFunction"
+    }
+}
+
+impl Closure {
+    /// Creates a closure that accepts the given number of arguments, which can be accessed through
+    /// the [`CallEnv`] passed to `f`. An upper bound of `usize::MAX` means any number of arguments,
+    /// like `&rest` in Lisp.
+    ///
+    /// [`CallEnv`]: struct.CallEnv.html
+    pub fn new<F>(arities: Range<usize>, f: F) -> Self
+    where
+        F: FnMut(&CallEnv) -> Result<Value<'_>> + 'static,
+    {
+        Closure { arities, body: Body::Unique(RefCell::new(Box::new(f))) }
+    }
+
+    /// Like [`new`], but for a closure that doesn't need mutable access to its state, which can
+    /// therefore be called recursively.
+    ///
+    /// [`new`]: #method.new
+    pub(crate) fn shared<F>(arities: Range<usize>, f: F) -> Self
+    where
+        F: Fn(&CallEnv) -> Result<Value<'_>> + 'static,
+    {
+        Closure { arities, body: Body::Shared(Box::new(f)) }
+    }
+}
+
+impl IntoLisp<'_> for Closure {
+    /// Converts this closure into a Lisp function, by partially applying a module function to a
+    /// `user-ptr` object holding the closure's state. Emacs provides no way to finalize module
+    /// functions, but it does finalize `user-ptr` objects.
+    fn into_lisp(self, env: &Env) -> Result<Value<'_>> {
+        let Closure { arities, body } = self;
+        let state = Box::new(State(body)).into_lisp(env)?;
+        env.partially_apply(closure_subr, arities, state)
+    }
+}
+
+impl Env {
+    /// Calls `f` with a Lisp function that calls the Rust closure `function`, which can be
+    /// borrowing, unlike a [`Closure`]. The Lisp function only works until `f` returns. Calling it
+    /// afterwards, or recursively, signals an error instead.
+    ///
+    /// [`Closure`]: struct.Closure.html
+    pub(crate) fn with_borrowed_function<'e, T, G, F>(
+        &'e self,
+        arities: Range<usize>,
+        mut function: G,
+        f: F,
+    ) -> Result<T>
+    where
+        G: FnMut(&CallEnv) -> Result<Value<'_>>,
+        F: FnOnce(Value<'e>) -> Result<T>,
+    {
+        let function: &mut Function<'_> = &mut function;
+        // Safety: The closure is only used while the loan holds it, which is until this returns.
+        #[allow(clippy::transmute_ptr_to_ptr)]
+        let function: *mut Function<'static> =
+            unsafe { mem::transmute(function as *mut Function<'_>) };
+        let loan = Box::new(Loan(Cell::new(Some(function)))).into_lisp(self)?;
+        let slot: &Loan = loan.into_rust()?;
+        let result = self.partially_apply(loan_subr, arities, loan).and_then(f);
+        slot.0.set(None);
+        result
+    }
+
+    /// Returns a Lisp function that calls `subr` with `state` as the first argument, followed by
+    /// its own arguments, whose number is within `arities`.
+    fn partially_apply<'e>(
+        &'e self,
+        subr: EmacsSubr,
+        arities: Range<usize>,
+        state: Value<'e>,
+    ) -> Result<Value<'e>> {
+        // The state is an extra argument, but there is no maximum to increase if the function is
+        // variadic.
+        let end = match arities.end {
+            VARIADIC => VARIADIC,
+            end => end + 1,
+        };
+        let arities = arities.start + 1..end;
+        // Safety: `subr` does not use `data`.
+        let subr = unsafe { self.make_function(subr, arities, "", ptr::null_mut())? };
+        call_lisp!(self, "apply-partially", subr, state)
+    }
+}
+
+unsafe extern "C" fn closure_subr(
+    env: *mut emacs_env,
+    nargs: libc::ptrdiff_t,
+    args: *mut emacs_value,
+    _data: *mut libc::c_void,
+) -> emacs_value {
+    // The first argument is the closure's state, bound by `apply-partially`. The rest are passed
+    // on to the closure.
+    let state = *args;
+    let env = CallEnv::new(Env::new(env), nargs - 1, args.offset(1));
+    env.handle_call(|env| {
+        let state = &*env.get_raw_pointer::<State>(state)?;
+        match &state.0 {
+            Body::Unique(function) => {
+                let mut function = function.try_borrow_mut()?;
+                function(env)
+            }
+            Body::Shared(function) => function(env),
+        }
+    })
+}
+
+unsafe extern "C" fn loan_subr(
+    env: *mut emacs_env,
+    nargs: libc::ptrdiff_t,
+    args: *mut emacs_value,
+    _data: *mut libc::c_void,
+) -> emacs_value {
+    // The first argument is the loan, bound by `apply-partially`. The rest are passed on to the
+    // borrowed closure.
+    let loan = *args;
+    let env = CallEnv::new(Env::new(env), nargs - 1, args.offset(1));
+    env.handle_call(|env| {
+        let loan = &*env.get_raw_pointer::<Loan>(loan)?;
+        let function = match loan.0.take() {
+            Some(function) => function,
+            None => {
+                return Err(env.error("Borrowed Rust closure called recursively, or too late"));
+            }
+        };
+        let result = (*function)(env);
+        loan.0.set(Some(function));
+        result
+    })
+}
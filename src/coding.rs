@@ -0,0 +1,77 @@
+use super::error::Result;
+use super::{Env, IntoLisp, Value};
+
+const FOR_READ: &str = "coding-system-for-read";
+const FOR_WRITE: &str = "coding-system-for-write";
+
+/// Accessors for the coding systems used by I/O primitives (file access, processes...). See
+/// [Specifying Coding Systems for One Operation].
+///
+/// [Specifying Coding Systems for One Operation]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Specifying-Coding-Systems.html
+impl Env {
+    /// Returns the value of `coding-system-for-read`: a coding system symbol, or `nil`.
+    pub fn coding_system_for_read(&self) -> Result<Value<'_>> {
+        self.symbol_value(FOR_READ)
+    }
+
+    /// Returns the value of `coding-system-for-write`: a coding system symbol, or `nil`.
+    pub fn coding_system_for_write(&self) -> Result<Value<'_>> {
+        self.symbol_value(FOR_WRITE)
+    }
+
+    /// Sets `coding-system-for-read` to the named coding system, or to `nil` if `coding` is
+    /// `None`.
+    pub fn set_coding_system_for_read(&self, coding: Option<&str>) -> Result<()> {
+        let coding = self.coding_system(coding)?;
+        self.set(FOR_READ, coding)?;
+        Ok(())
+    }
+
+    /// Sets `coding-system-for-write` to the named coding system, or to `nil` if `coding` is
+    /// `None`.
+    pub fn set_coding_system_for_write(&self, coding: Option<&str>) -> Result<()> {
+        let coding = self.coding_system(coding)?;
+        self.set(FOR_WRITE, coding)?;
+        Ok(())
+    }
+
+    /// Calls `f` with `coding-system-for-read` and `coding-system-for-write` temporarily set to the
+    /// given coding systems (`None` meaning `nil`), like a `let` binding of these variables in Lisp.
+    ///
+    /// The previous values are restored after `f` returns, whether it succeeds, fails, or panics.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, IntoLisp, Result};
+    /// fn git_log(env: &Env) -> Result<String> {
+    ///     env.with_coding_system(Some("utf-8"), None, || {
+    ///         let command = "git log -1".into_lisp(env)?;
+    ///         env.call("shell-command-to-string", &[command])?.into_rust()
+    ///     })
+    /// }
+    /// ```
+    pub fn with_coding_system<T, F>(
+        &self,
+        read: Option<&str>,
+        write: Option<&str>,
+        f: F,
+    ) -> Result<T>
+    where
+        F: FnOnce() -> Result<T>,
+    {
+        let read = self.coding_system(read)?;
+        let write = self.coding_system(write)?;
+        self.with_bindings(&[(FOR_READ, read), (FOR_WRITE, write)], f)
+    }
+
+    fn coding_system(&self, coding: Option<&str>) -> Result<Value<'_>> {
+        match coding {
+            Some(name) => {
+                let symbol = self.intern(name)?;
+                call_lisp!(self, "check-coding-system", symbol)
+            }
+            None => ().into_lisp(self),
+        }
+    }
+}
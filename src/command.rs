@@ -0,0 +1,136 @@
+use super::error::Result;
+use super::symbol::CoreSymbol;
+use super::{Closure, Env, IntoLisp, Value};
+
+/// Kinds of interactive calls to check for, with [`called_interactively_p`].
+///
+/// [`called_interactively_p`]: struct.Env.html#method.called_interactively_p
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InteractiveKind {
+    /// `interactive`: the call was made directly by the user, e.g. through a key binding, and not
+    /// by a keyboard macro. This is never the case when Emacs is running in batch mode.
+    Interactive,
+
+    /// `any`: the call was made through `call-interactively` or `funcall-interactively`, in any
+    /// way.
+    Any,
+}
+
+/// Accessors for information about the command loop. See [Command Loop Info].
+///
+/// [Command Loop Info]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Command-Loop-Info.html
+impl Env {
+    /// Returns the value of `this-command`: the command currently being executed by the editor
+    /// command loop, or `None` if there is none.
+    pub fn this_command(&self) -> Result<Option<Value<'_>>> {
+        self.symbol_value("this-command")?.into_rust()
+    }
+
+    /// Returns the value of `last-command`: the previous command executed by the editor command
+    /// loop, or `None` if there is none.
+    ///
+    /// # Examples
+    ///
+    /// Detecting repeated invocations of a command:
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, Result};
+    /// fn is_repeated(env: &Env) -> Result<bool> {
+    ///     match (env.this_command()?, env.last_command()?) {
+    ///         (Some(this), Some(last)) => Ok(env.eq(this, last)),
+    ///         _ => Ok(false),
+    ///     }
+    /// }
+    /// ```
+    pub fn last_command(&self) -> Result<Option<Value<'_>>> {
+        self.symbol_value("last-command")?.into_rust()
+    }
+
+    /// Returns the description of the key sequence that invoked the current command, e.g. `"C-c a"`,
+    /// through `this-command-keys-vector` and `key-description`.
+    ///
+    /// # Examples
+    ///
+    /// A command bound to several keys, which behaves differently depending on the triggering one:
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, Result};
+    /// fn step(env: &Env) -> Result<i64> {
+    ///     match env.this_command_keys()?.as_str() {
+    ///         "C-c n" => Ok(1),
+    ///         "C-c p" => Ok(-1),
+    ///         _ => Ok(0),
+    ///     }
+    /// }
+    /// ```
+    pub fn this_command_keys(&self) -> Result<String> {
+        let keys = call_lisp!(self, "this-command-keys-vector")?;
+        call_lisp!(self, "key-description", keys)?.into_rust()
+    }
+
+    /// Calls `func` with the given arguments, as if it were called interactively, through
+    /// [`funcall-interactively`]. In particular, `(called-interactively-p 'any)` returns `t` inside
+    /// `func`.
+    ///
+    /// The arguments are passed as-is: `func`'s interactive form is not used to compute them. Use
+    /// `call-interactively` for that.
+    ///
+    /// [`funcall-interactively`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Interactive-Call.html
+    pub fn funcall_interactively(&self, func: Value<'_>, args: &[Value<'_>]) -> Result<Value<'_>> {
+        let mut all = Vec::with_capacity(args.len() + 1);
+        all.push(func);
+        all.extend_from_slice(args);
+        self.call("funcall-interactively", &all)
+    }
+
+    /// Returns whether the module function calling this was called interactively, through
+    /// [`called-interactively-p`].
+    ///
+    /// # Examples
+    ///
+    /// Only reporting to the user when called as a command:
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, InteractiveKind, Result};
+    /// fn notify(env: &Env, text: &str) -> Result<()> {
+    ///     if env.called_interactively_p(InteractiveKind::Interactive)? {
+    ///         env.message(text)?;
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [`called-interactively-p`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Distinguish-Interactive.html
+    pub fn called_interactively_p(&self, kind: InteractiveKind) -> Result<bool> {
+        let kind = self.intern(match kind {
+            InteractiveKind::Interactive => "interactive",
+            InteractiveKind::Any => "any",
+        })?;
+        // The check is made by evaluating the form below, which binds the hook with `let`. The
+        // `eval` frame must be skipped to get to the module function's own frame (the `let` frame
+        // is skipped by `called-interactively-p` itself). Before Emacs 27, a module function is a
+        // lambda that applies `internal--module-call`, which must be skipped as well.
+        let skip = Closure::new(3..3, |env| {
+            let frame = env.get_arg(1);
+            let function = env.call("car-safe", &[env.call("cdr-safe", &[frame])?])?;
+            if env.eq(function, env.intern("eval")?) {
+                0.into_lisp(env)
+            } else if env.eq(function, env.intern("internal--module-call")?) {
+                1.into_lisp(env)
+            } else {
+                env.nil()
+            }
+        });
+        // (let ((called-interactively-p-functions
+        //        (cons 'SKIP called-interactively-p-functions)))
+        //   (called-interactively-p 'KIND))
+        let quote = self.core_symbol(CoreSymbol::Quote)?;
+        let hooks = self.intern("called-interactively-p-functions")?;
+        let skip = self.list(&[quote, skip.into_lisp(self)?])?;
+        let binding = self.list(&[hooks, self.list(&[self.intern("cons")?, skip, hooks])?])?;
+        let kind = self.list(&[quote, kind])?;
+        let check = self.list(&[self.intern("called-interactively-p")?, kind])?;
+        let form = self.list(&[self.intern("let")?, self.list(&[binding])?, check])?;
+        Ok(self.is_not_nil(call_lisp!(self, "eval", form)?))
+    }
+}
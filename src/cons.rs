@@ -0,0 +1,114 @@
+use std::convert::TryFrom;
+
+use super::error::{Error, Result};
+use super::{Env, FromLisp, IntoLisp, Value};
+
+/// A Lisp cons cell, e.g. an element of an alist, or a non-empty list.
+///
+/// It can be obtained from a [`Value`] through `TryFrom`, which checks that the value is a cons
+/// cell. It also implements [`FromLisp`], so it can be used as a parameter of a [`#[defun]`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use emacs::{defun, Cons, Result, Value};
+/// /// Return the key of the alist entry PAIR.
+/// #[defun]
+/// fn key(pair: Cons<'_>) -> Result<Value<'_>> {
+///     pair.car()
+/// }
+/// ```
+///
+/// [`Value`]: struct.Value.html
+/// [`FromLisp`]: trait.FromLisp.html
+/// [`#[defun]`]: /emacs-macros/*/emacs_macros/attr.defun.html
+#[derive(Debug, Clone, Copy)]
+pub struct Cons<'e>(Value<'e>);
+
+impl<'e> Cons<'e> {
+    /// Returns the `car` of this cons cell.
+    pub fn car(&self) -> Result<Value<'e>> {
+        let Cons(value) = *self;
+        value.env.car(value)
+    }
+
+    /// Returns the `cdr` of this cons cell.
+    pub fn cdr(&self) -> Result<Value<'e>> {
+        let Cons(value) = *self;
+        value.env.cdr(value)
+    }
+
+    /// Returns the underlying Lisp cons cell.
+    pub fn value(&self) -> Value<'e> {
+        self.0
+    }
+}
+
+impl<'e> TryFrom<Value<'e>> for Cons<'e> {
+    type Error = Error;
+
+    /// Checks that the value is a cons cell, signaling `(wrong-type-argument consp VALUE)` if not.
+    fn try_from(value: Value<'e>) -> Result<Self> {
+        let env = value.env;
+        if !env.is_not_nil(call_lisp!(env, "consp", value)?) {
+            return Err(env.wrong_type("consp", value));
+        }
+        Ok(Cons(value))
+    }
+}
+
+impl<'e> FromLisp<'e> for Cons<'e> {
+    fn from_lisp(value: Value<'e>) -> Result<Self> {
+        Cons::try_from(value)
+    }
+}
+
+impl<'e> IntoLisp<'e> for Cons<'e> {
+    fn into_lisp(self, _: &'e Env) -> Result<Value<'e>> {
+        Ok(self.0)
+    }
+}
+
+/// Helpers for cons cells. See [Cons Cells].
+///
+/// [Cons Cells]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Cons-Cells.html
+impl Env {
+    /// Creates a cons cell, like `cons`. Unlike [`list`], this can build dotted pairs, e.g. the
+    /// entries of an alist.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, Result, Value};
+    /// fn to_alist<'e>(env: &'e Env, entries: &[(&str, i64)]) -> Result<Value<'e>> {
+    ///     let mut alist = env.nil()?;
+    ///     for (key, value) in entries.iter().rev() {
+    ///         alist = env.cons(env.cons(env.intern(key)?, *value)?, alist)?;
+    ///     }
+    ///     Ok(alist)
+    /// }
+    /// ```
+    ///
+    /// [`list`]: #method.list
+    pub fn cons<'e, A, D>(&'e self, car: A, cdr: D) -> Result<Value<'e>>
+    where
+        A: IntoLisp<'e>,
+        D: IntoLisp<'e>,
+    {
+        let car = car.into_lisp(self)?;
+        let cdr = cdr.into_lisp(self)?;
+        call_lisp!(self, "cons", car, cdr)
+    }
+
+    /// Returns the `car` of a cons cell, like `car`. The `car` of `nil` is `nil`. Anything else
+    /// signals `(wrong-type-argument listp VALUE)`.
+    pub fn car<'e>(&'e self, value: Value<'e>) -> Result<Value<'e>> {
+        call_lisp!(self, "car", value)
+    }
+
+    /// Returns the `cdr` of a cons cell, like `cdr`. The `cdr` of `nil` is `nil`. Anything else
+    /// signals `(wrong-type-argument listp VALUE)`.
+    pub fn cdr<'e>(&'e self, value: Value<'e>) -> Result<Value<'e>> {
+        call_lisp!(self, "cdr", value)
+    }
+}
@@ -0,0 +1,38 @@
+use super::error::Result;
+use super::symbol::CoreSymbol;
+use super::{Env, IntoLisp, Value};
+
+/// Definitions that the byte compiler can rely on.
+impl Env {
+    /// Defines `name` as a constant with the given value and documentation, like `defconst` in Lisp.
+    /// The variable is made special, and marked as `risky-local-variable`. Returns the symbol.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, Result};
+    /// fn init_constants(env: &Env) -> Result<()> {
+    ///     env.defconst("my-mod-max-batch-size", 1024, "Largest batch size accepted by my-mod.")?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn defconst<'e, T>(&'e self, name: &str, value: T, doc: &str) -> Result<Value<'e>>
+    where
+        T: IntoLisp<'e>,
+    {
+        let symbol = self.intern(name)?;
+        let value = value.into_lisp(self)?;
+        let quote = self.core_symbol(CoreSymbol::Quote)?;
+        let value = self.list(&[quote, value])?;
+        let form = self.list(&[self.intern("defconst")?, symbol, value, doc.into_lisp(self)?])?;
+        call_lisp!(self, "eval", form)
+    }
+
+    /// Declares that the named function is pure, i.e. it has no side effects, and its result only
+    /// depends on its arguments, by setting its `pure` and `side-effect-free` properties. This
+    /// allows the byte compiler to evaluate calls with constant arguments at compile time.
+    pub fn declare_pure(&self, name: &str) -> Result<()> {
+        self.put(name, "pure", true)?;
+        self.put(name, "side-effect-free", true)
+    }
+}
@@ -1,9 +1,16 @@
 use std::cell::RefCell;
+use std::convert::TryFrom;
 use std::ffi::CString;
+use std::num::{
+    NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU16, NonZeroU32,
+    NonZeroU64, NonZeroU8, NonZeroUsize,
+};
+use std::path::PathBuf;
 use std::ptr;
-use std::sync::{Mutex, RwLock};
+use std::result;
+use std::sync::{Arc, Mutex, RwLock};
 
-use super::error::{ErrorKind, Result};
+use super::error::{Error, ErrorKind, Result};
 use super::{Env, Value};
 use super::{FromLisp, IntoLisp, Transfer};
 use emacs_module::emacs_value;
@@ -39,6 +46,13 @@ impl FromLisp<'_> for String {
     }
 }
 
+impl FromLisp<'_> for PathBuf {
+    fn from_lisp(value: Value<'_>) -> Result<Self> {
+        Ok(value.into_rust::<String>()?.into())
+    }
+}
+
+/// Converts `nil` into `None`, and anything else into `Some`.
 impl<'e, T: FromLisp<'e>> FromLisp<'e> for Option<T> {
     fn from_lisp(value: Value<'e>) -> Result<Self> {
         if value.env.is_not_nil(value) {
@@ -49,6 +63,41 @@ impl<'e, T: FromLisp<'e>> FromLisp<'e> for Option<T> {
     }
 }
 
+/// Converts a proper list or a vector into a `Vec`, converting all the elements eagerly. Anything
+/// else, including an improper list, signals `(wrong-type-argument listp TAIL)`. A circular list
+/// signals `(circular-list LIST)`, instead of looping forever.
+impl<'e, T: FromLisp<'e>> FromLisp<'e> for Vec<T> {
+    fn from_lisp(value: Value<'e>) -> Result<Self> {
+        let env = value.env;
+        if env.is_not_nil(call_lisp!(env, "vectorp", value)?) {
+            let size = raw_call!(env, vec_size, value.raw)?;
+            let mut items = Vec::with_capacity(size as usize);
+            for i in 0..size {
+                items.push(raw_call_value!(env, vec_get, value.raw, i)?.into_rust()?);
+            }
+            return Ok(items);
+        }
+        let mut items = vec![];
+        let mut tail = value;
+        // Detect cycles by moving `slow` at half the speed of `tail`. They meet iff there is one.
+        let mut slow = value;
+        while env.is_not_nil(tail) {
+            if !env.is_not_nil(call_lisp!(env, "consp", tail)?) {
+                return Err(env.wrong_type("listp", tail));
+            }
+            items.push(call_lisp!(env, "car", tail)?.into_rust()?);
+            tail = call_lisp!(env, "cdr", tail)?;
+            if items.len() % 2 == 0 {
+                slow = call_lisp!(env, "cdr", slow)?;
+            }
+            if env.eq(slow, tail) {
+                return Err(env.circular_list(value));
+            }
+        }
+        Ok(items)
+    }
+}
+
 impl<'a, 'e: 'a, T: Transfer> FromLisp<'e> for &'a T {
     fn from_lisp(value: Value<'e>) -> Result<Self> {
         value.env.get_raw_pointer(value.raw).map(|r| unsafe { &*r })
@@ -64,16 +113,16 @@ impl<'e> IntoLisp<'e> for Value<'e> {
 
 impl IntoLisp<'_> for () {
     fn into_lisp(self, env: &Env) -> Result<Value<'_>> {
-        env.intern("nil")
+        env.nil()
     }
 }
 
 impl IntoLisp<'_> for bool {
     fn into_lisp(self, env: &Env) -> Result<Value<'_>> {
         if self {
-            env.intern("t")
+            env.t()
         } else {
-            env.intern("nil")
+            env.nil()
         }
     }
 }
@@ -104,15 +153,41 @@ impl IntoLisp<'_> for String {
     }
 }
 
+/// Converts `None` into `nil`.
 impl<'e, T: IntoLisp<'e>> IntoLisp<'e> for Option<T> {
     fn into_lisp(self, env: &'e Env) -> Result<Value<'_>> {
         match self {
             Some(t) => t.into_lisp(env),
-            None => env.intern("nil"),
+            None => env.nil(),
+        }
+    }
+}
+
+/// Converts a `Vec` into a proper list, converting the elements from left to right. An empty `Vec`
+/// is converted into `nil`.
+impl<'e, T: IntoLisp<'e>> IntoLisp<'e> for Vec<T> {
+    fn into_lisp(self, env: &'e Env) -> Result<Value<'_>> {
+        let items = self.into_iter().map(|t| t.into_lisp(env)).collect::<Result<Vec<_>>>()?;
+        env.list(&items)
+    }
+}
+
+/// Converts `Ok` values into Lisp, and propagates `Err` values as errors. Combined with the
+/// conversion for `Option<T>`, this maps `Option<Result<T>>` to `nil`, the value, or a non-local
+/// exit.
+impl<'e, T: IntoLisp<'e>, E: Into<Error>> IntoLisp<'e> for result::Result<T, E> {
+    fn into_lisp(self, env: &'e Env) -> Result<Value<'_>> {
+        match self {
+            Ok(t) => t.into_lisp(env),
+            Err(error) => Err(error.into()),
         }
     }
 }
 
+/// Embeds the value in a `user-ptr` object, transferring its ownership to the GC. When the object
+/// is garbage-collected, the value is dropped by [`Transfer::finalizer`], running its `Drop` impl.
+///
+/// [`Transfer::finalizer`]: trait.Transfer.html#method.finalizer
 impl<T: Transfer> IntoLisp<'_> for Box<T> {
     fn into_lisp(self, env: &Env) -> Result<Value<'_>> {
         let raw = Box::into_raw(self);
@@ -121,10 +196,109 @@ impl<T: Transfer> IntoLisp<'_> for Box<T> {
     }
 }
 
+/// Conversions for non-zero integer types. Converting zero, or an integer out of the type's range,
+/// from Lisp signals `(wrong-type-argument nonzero-<type>-p VALUE)`. Converting an integer that
+/// doesn't fit in a Lisp integer into Lisp signals `overflow-error`.
+macro_rules! impl_nonzero {
+    ($($nonzero:ident($int:ident);)*) => {$(
+        impl FromLisp<'_> for $nonzero {
+            fn from_lisp(value: Value<'_>) -> Result<Self> {
+                let i: i64 = value.into_rust()?;
+                match $int::try_from(i).ok().and_then($nonzero::new) {
+                    Some(n) => Ok(n),
+                    None => {
+                        let predicate = concat!("nonzero-", stringify!($int), "-p");
+                        Err(value.env.wrong_type(predicate, value))
+                    }
+                }
+            }
+        }
+
+        impl IntoLisp<'_> for $nonzero {
+            fn into_lisp(self, env: &Env) -> Result<Value<'_>> {
+                match i64::try_from(self.get()) {
+                    Ok(i) => i.into_lisp(env),
+                    Err(_) => {
+                        let data = self.to_string().into_lisp(env)?;
+                        Err(env.make_signal("overflow-error", &[data])?)
+                    }
+                }
+            }
+        }
+    )*};
+}
+
+impl_nonzero! {
+    NonZeroI8(i8);
+    NonZeroI16(i16);
+    NonZeroI32(i32);
+    NonZeroI64(i64);
+    NonZeroIsize(isize);
+    NonZeroU8(u8);
+    NonZeroU16(u16);
+    NonZeroU32(u32);
+    NonZeroU64(u64);
+    NonZeroUsize(usize);
+}
+
+/// Conversions for tuples of 2 to 12 elements, represented in Lisp as lists, e.g. `(1 "a")` for
+/// `(1, "a")`, so that they can be destructured with `pcase-let` or `cl-destructuring-bind`. Each
+/// element is converted with its own type's conversion.
+///
+/// Converting from Lisp also accepts a vector. A list or vector whose length is not the tuple's
+/// arity signals `(wrong-length-argument EXPECTED ACTUAL)`.
+macro_rules! impl_tuple {
+    ($($len:expr => ($($n:tt $t:ident),+);)*) => {$(
+        impl<'e, $($t: IntoLisp<'e>),+> IntoLisp<'e> for ($($t,)+) {
+            fn into_lisp(self, env: &'e Env) -> Result<Value<'e>> {
+                env.list(&[$(self.$n.into_lisp(env)?),+])
+            }
+        }
+
+        impl<'e, $($t: FromLisp<'e>),+> FromLisp<'e> for ($($t,)+) {
+            fn from_lisp(value: Value<'e>) -> Result<Self> {
+                let items: Vec<Value<'e>> = value.into_rust()?;
+                if items.len() != $len {
+                    let env = value.env;
+                    let expected = ($len as i64).into_lisp(env)?;
+                    let actual = (items.len() as i64).into_lisp(env)?;
+                    return Err(env.make_signal("wrong-length-argument", &[expected, actual])?);
+                }
+                Ok(($(items[$n].into_rust()?,)+))
+            }
+        }
+    )*};
+}
+
+impl_tuple! {
+    2 => (0 A, 1 B);
+    3 => (0 A, 1 B, 2 C);
+    4 => (0 A, 1 B, 2 C, 3 D);
+    5 => (0 A, 1 B, 2 C, 3 D, 4 E);
+    6 => (0 A, 1 B, 2 C, 3 D, 4 E, 5 F);
+    7 => (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G);
+    8 => (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H);
+    9 => (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I);
+    10 => (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I, 9 J);
+    11 => (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I, 9 J, 10 K);
+    12 => (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I, 9 J, 10 K, 11 L);
+}
+
 enable_transfers! {
     RefCell;
     Mutex;
     RwLock;
+    Arc;
+}
+
+/// Returns a clone of the `Arc` embedded in a `user-ptr` object, sharing its data with the object,
+/// e.g. to hand an `Arc<Mutex<T>>` to a background thread. Signals `rust-wrong-type-user-ptr` if
+/// the object embeds a different type.
+impl<'e, T> FromLisp<'e> for Arc<T> {
+    fn from_lisp(value: Value<'e>) -> Result<Self> {
+        let arc: &Arc<T> = value.into_rust()?;
+        Ok(arc.clone())
+    }
 }
 
 fn strip_trailing_zero_bytes(bytes: &mut Vec<u8>) {
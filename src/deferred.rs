@@ -0,0 +1,134 @@
+use std::mem;
+use std::panic;
+use std::sync::Mutex;
+
+use failure::err_msg;
+
+use super::error::Result;
+use super::Env;
+
+type Call = Box<dyn FnOnce(&Env) -> Result<()> + Send>;
+
+/// A queue of calls that other threads can schedule, to be run later on the Lisp thread.
+///
+/// An [`Env`] can only be used on the thread it was created on, so background threads cannot call
+/// Lisp directly. Instead, they push closures onto a shared `DeferredQueue`, which are run, in
+/// order, when the Lisp thread calls [`drain`], e.g. from a `#[defun]` run by a timer.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::thread;
+/// # use emacs::{defun, DeferredQueue, Env, Result};
+/// # use lazy_static::lazy_static;
+/// lazy_static! {
+///     static ref QUEUE: DeferredQueue = DeferredQueue::new();
+/// }
+///
+/// #[defun]
+/// fn start_indexing() -> Result<()> {
+///     thread::spawn(|| {
+///         // ...
+///         QUEUE.push(|env| {
+///             env.message("Indexing done")?;
+///             Ok(())
+///         });
+///     });
+///     Ok(())
+/// }
+///
+/// /// Run the updates scheduled by indexing threads.
+/// #[defun]
+/// fn drain(env: &Env) -> Result<usize> {
+///     QUEUE.drain(env)
+/// }
+/// ```
+///
+/// ```emacs-lisp
+/// (run-with-idle-timer 0.5 t #'my-module-drain)
+/// ```
+///
+/// [`Env`]: struct.Env.html
+/// [`drain`]: #method.drain
+pub struct DeferredQueue {
+    calls: Mutex<Vec<Call>>,
+}
+
+impl DeferredQueue {
+    /// Creates an empty queue.
+    pub fn new() -> Self {
+        DeferredQueue { calls: Mutex::new(vec![]) }
+    }
+
+    /// Schedules `f` to be run on the Lisp thread by the next [`drain`]. This can be called from
+    /// any thread.
+    ///
+    /// [`drain`]: #method.drain
+    pub fn push<F>(&self, f: F)
+    where
+        F: FnOnce(&Env) -> Result<()> + Send + 'static,
+    {
+        self.lock().push(Box::new(f));
+    }
+
+    /// Returns the number of calls waiting to be run.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns `true` if there are no calls waiting to be run.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Runs the pending calls, in the order they were pushed, returning how many were run. Calls
+    /// pushed while draining, including by the calls themselves, are left for the next drain.
+    ///
+    /// A call that fails or panics does not prevent the remaining calls from running. Panics are
+    /// caught, and turned into errors. If any call failed, the first error is returned after all
+    /// calls have run, and the others are reported with `message`.
+    pub fn drain(&self, env: &Env) -> Result<usize> {
+        let calls = mem::replace(&mut *self.lock(), vec![]);
+        let count = calls.len();
+        let mut first_error = None;
+        for call in calls {
+            let result = match panic::catch_unwind(panic::AssertUnwindSafe(|| call(env))) {
+                Ok(result) => result,
+                Err(error) => {
+                    let message = match error.downcast_ref::<&str>() {
+                        Some(s) => s.to_string(),
+                        None => match error.downcast_ref::<String>() {
+                            Some(s) => s.clone(),
+                            None => format!("{:#?}", error),
+                        },
+                    };
+                    Err(err_msg(format!("Deferred call panicked: {}", message)))
+                }
+            };
+            if let Err(error) = result {
+                if first_error.is_none() {
+                    first_error = Some(error);
+                } else {
+                    // Failing to report an error must not stop the remaining calls from running.
+                    let _ = env.message(&format!("Error in deferred call: {}", error));
+                }
+            }
+        }
+        match first_error {
+            Some(error) => Err(error),
+            None => Ok(count),
+        }
+    }
+
+    // If a thread panicked while holding the lock, the queue itself is still consistent, because
+    // it is only pushed to and swapped out.
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Call>> {
+        self.calls.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Default for DeferredQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,12 +1,18 @@
 #[doc(no_inline)]
 pub use failure::{Error, ResultExt};
 use failure_derive::Fail;
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::io;
 use std::mem;
 use std::result;
+use std::sync::Mutex;
 use std::thread;
 
-use super::IntoLisp;
-use super::{Env, Value};
+use super::{FromLisp, IntoLisp};
+use super::globals;
+use super::symbol::CoreSymbol;
+use super::{Env, GlobalRef, Value};
 use emacs_module::*;
 
 // We assume that the C code in Emacs really treats it as an enum and doesn't return an undeclared
@@ -16,14 +22,13 @@ const RETURN: emacs_funcall_exit = emacs_funcall_exit_emacs_funcall_exit_return;
 const SIGNAL: emacs_funcall_exit = emacs_funcall_exit_emacs_funcall_exit_signal;
 const THROW: emacs_funcall_exit = emacs_funcall_exit_emacs_funcall_exit_throw;
 
-#[derive(Debug)]
-pub struct TempValue {
-    raw: emacs_value,
-}
-
-const WRONG_TYPE_USER_PTR: &str = "rust-wrong-type-user-ptr";
-const ERROR: &str = "rust-error";
-const PANIC: &str = "rust-panic";
+pub(crate) const WRONG_TYPE_USER_PTR: &str = "rust-wrong-type-user-ptr";
+const WRONG_TYPE_ARGUMENT: &str = "wrong-type-argument";
+pub(crate) const RUST_WRONG_TYPE_ARGUMENT: &str = "rust-wrong-type-argument";
+pub(crate) const ERROR: &str = "rust-error";
+pub(crate) const PANIC: &str = "rust-panic";
+pub(crate) const TIMEOUT: &str = "rust-timeout";
+const HANDLE_CLOSED: &str = "rust-handle-closed";
 
 /// Error types generic to all Rust dynamic modules.
 ///
@@ -35,13 +40,13 @@ pub enum ErrorKind {
     ///
     /// [error]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Signaling-Errors.html
     #[fail(display = "Non-local signal: symbol={:?} data={:?}", symbol, data)]
-    Signal { symbol: TempValue, data: TempValue },
+    Signal { symbol: GlobalRef, data: GlobalRef },
 
     /// A [non-local exit] thrown by Lisp code.
     ///
     /// [non-local exit]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Catch-and-Throw.html
     #[fail(display = "Non-local throw: tag={:?} value={:?}", tag, value)]
-    Throw { tag: TempValue, value: TempValue },
+    Throw { tag: GlobalRef, value: GlobalRef },
 
     /// An error indicating that the given value is not a `user-ptr` of the expected type.
     ///
@@ -73,38 +78,512 @@ pub enum ErrorKind {
     /// ```
     #[fail(display = "expected: {}", expected)]
     WrongTypeUserPtr { expected: &'static str },
+
+    /// An error indicating that an argument of a function declared with `#[defun(strict_args)]`
+    /// could not be converted to the parameter's type. It is signaled to Lisp as
+    /// `rust-wrong-type-argument`, a sub-type of `wrong-type-argument`, with the parameter's name
+    /// prepended to the data of the original error.
+    ///
+    /// # Examples:
+    ///
+    /// ```no_run
+    /// # use emacs::*;
+    /// #[defun(strict_args)]
+    /// fn add(x: i64, y: i64) -> Result<i64> {
+    ///     Ok(x + y)
+    /// }
+    /// ```
+    ///
+    /// ```emacs-lisp
+    /// (add 1 "2") ; *** Eval error ***  Wrong type argument: y, integerp, "2"
+    /// ```
+    #[fail(display = "argument {}: {}", name, cause)]
+    WrongTypeArgument { name: &'static str, cause: Error },
+
+    /// An error indicating that one of the arguments collected by the `&rest` parameter of a
+    /// function declared with `#[defun(rest)]` could not be converted to the parameter's element
+    /// type. It is signaled like [`WrongTypeArgument`], with the argument's index among the rest
+    /// arguments following the parameter's name.
+    ///
+    /// # Examples:
+    ///
+    /// ```no_run
+    /// # use emacs::*;
+    /// #[defun(rest)]
+    /// fn sum(numbers: Vec<i64>) -> Result<i64> {
+    ///     Ok(numbers.iter().sum())
+    /// }
+    /// ```
+    ///
+    /// ```emacs-lisp
+    /// (sum 1 2 "3") ; *** Eval error ***  Wrong type argument: numbers, 2, integerp, "3"
+    /// ```
+    ///
+    /// [`WrongTypeArgument`]: #variant.WrongTypeArgument
+    #[fail(display = "argument {}[{}]: {}", name, index, cause)]
+    WrongTypeRestArgument { name: &'static str, index: usize, cause: Error },
+
+    /// An error indicating that an index is out of the bounds of a [`Vector`]. It is signaled to
+    /// Lisp as `rust-error`.
+    ///
+    /// [`Vector`]: struct.Vector.html
+    #[fail(display = "index {} is out of bounds for a vector of length {}", index, len)]
+    IndexOutOfBounds { index: usize, len: usize },
+
+    /// An error indicating that an iterator yielded more items than allowed, e.g. by
+    /// [`list_from_bounded`]. It is signaled to Lisp as `rust-error`.
+    ///
+    /// [`list_from_bounded`]: struct.Env.html#method.list_from_bounded
+    #[fail(display = "more than {} items", max)]
+    TooManyItems { max: usize },
+
+    /// An error signaled by Lisp while reading the source code passed to [`eval`], e.g.
+    /// `invalid-read-syntax` or `end-of-file`. This distinguishes malformed source code from errors
+    /// signaled during evaluation, which are [`Signal`]. It is signaled to Lisp unchanged.
+    ///
+    /// [`eval`]: struct.Env.html#method.eval
+    /// [`Signal`]: #variant.Signal
+    #[fail(display = "Failed to read: symbol={:?} data={:?}", symbol, data)]
+    Read { symbol: GlobalRef, data: GlobalRef },
+
+    /// An error indicating that a [`Handle`] was used after being closed. It is signaled to Lisp as
+    /// `rust-handle-closed`, a sub-type of `rust-error`, without data.
+    ///
+    /// [`Handle`]: struct.Handle.html
+    #[fail(display = "handle closed")]
+    HandleClosed,
+}
+
+/// Standard error conditions, signaled by built-in editing commands. See [Standard Errors].
+///
+/// This list is intended to grow over time and it is not recommended to exhaustively match against
+/// it.
+///
+/// [Standard Errors]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Standard-Errors.html
+#[derive(Debug, Clone)]
+pub enum StandardCondition {
+    /// `beginning-of-buffer`: an attempt to move before the start of the buffer.
+    BeginningOfBuffer,
+
+    /// `end-of-buffer`: an attempt to move past the end of the buffer.
+    EndOfBuffer,
+
+    /// `buffer-read-only`: an attempt to modify the current buffer, which is read-only.
+    BufferReadOnly,
+
+    /// `mark-inactive`: an attempt to use the region while the mark is inactive.
+    MarkInactive,
+
+    /// `quit`: the user typed `C-g`. This is not an `error`, so it is not caught by
+    /// `condition-case` handlers for `error`.
+    Quit,
+
+    /// `scan-error`: a parsing error while scanning balanced expressions, between the positions
+    /// `start` and `end`.
+    ScanError { message: String, start: i64, end: i64 },
 }
 
+/// The expected shape of an error condition's data, registered by [`define_typed_error`], and
+/// checked by [`signal_typed`] in debug builds.
+///
+/// [`define_typed_error`]: struct.Env.html#method.define_typed_error
+/// [`signal_typed`]: struct.Env.html#method.signal_typed
+#[derive(Debug, Clone)]
+pub enum DataSchema {
+    /// Any value.
+    Any,
+
+    /// A value satisfying the named Lisp predicate, e.g. `integerp`.
+    Predicate(&'static str),
+
+    /// A cons cell whose car and cdr match the given schemas.
+    Cons(Box<DataSchema>, Box<DataSchema>),
+
+    /// A proper list whose elements match the given schemas, one for one.
+    List(Vec<DataSchema>),
+}
+
+lazy_static! {
+    /// Schemas of error conditions defined by `Env::define_typed_error`, keyed by their names.
+    static ref SCHEMAS: Mutex<HashMap<String, DataSchema>> = Mutex::new(HashMap::new());
+
+    /// Mappers added by `Env::add_error_mapper`, in the order they were added.
+    static ref MAPPERS: Mutex<Vec<ErrorMapper>> = Mutex::new(vec![]);
+}
+
+/// A function that converts a Rust error into a Lisp signal, used by [`add_error_mapper`].
+///
+/// [`add_error_mapper`]: struct.Env.html#method.add_error_mapper
+pub type ErrorMapper = fn(&Env, &Error) -> Option<Error>;
+
 /// A specialized [`Result`] type for Emacs's dynamic modules.
 ///
 /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
 pub type Result<T> = result::Result<T, Error>;
 
-// FIX: Make this into RootedValue (or ProtectedValue), and make it safe. XXX: The problem is that
-// the raw value will be leaked when RootedValue is dropped, since `free_global_ref` requires an env
-// (thus cannot be called there). This is likely a mis-design in Emacs (In Erlang,
-// `enif_keep_resource` and `enif_release_resource` don't require an env).
-impl TempValue {
-    unsafe fn new(raw: emacs_value) -> Self {
-        Self { raw }
+impl DataSchema {
+    #[cfg(debug_assertions)]
+    fn matches(&self, value: Value<'_>) -> Result<bool> {
+        let env = value.env;
+        let is_cons = |value| -> Result<bool> { Ok(env.is_not_nil(env.call("consp", &[value])?)) };
+        match self {
+            DataSchema::Any => Ok(true),
+            DataSchema::Predicate(predicate) => Ok(env.is_not_nil(env.call(predicate, &[value])?)),
+            DataSchema::Cons(car, cdr) => Ok(is_cons(value)?
+                && car.matches(env.call("car", &[value])?)?
+                && cdr.matches(env.call("cdr", &[value])?)?),
+            DataSchema::List(items) => {
+                let mut list = value;
+                for item in items {
+                    if !is_cons(list)? || !item.matches(env.call("car", &[list])?)? {
+                        return Ok(false);
+                    }
+                    list = env.call("cdr", &[list])?;
+                }
+                Ok(!env.is_not_nil(list))
+            }
+        }
     }
+}
 
-    /// # Safety
+impl Env {
+    /// Returns an error that signals `(wrong-type-argument PREDICATE VALUE)` when it reaches Lisp,
+    /// the same way built-in functions report invalid arguments.
     ///
-    /// This must only be used with the [`Env`] from which the error originated.
+    /// # Examples
     ///
-    /// [`Env`]: struct.Env.html
-    pub unsafe fn value<'e>(&self, env: &'e Env) -> Value<'e> {
-        Value::new_protected(self.raw, env)
+    /// ```no_run
+    /// # use emacs::*;
+    /// #[defun]
+    /// fn parse_version(value: Value<'_>) -> Result<i64> {
+    ///     let s: String = value.into_rust()?;
+    ///     match s.trim_start_matches('v').parse() {
+    ///         Ok(v) => Ok(v),
+    ///         Err(_) => Err(value.env.wrong_type("my-valid-format-p", value)),
+    ///     }
+    /// }
+    /// ```
+    pub fn wrong_type(&self, predicate: &str, value: Value<'_>) -> Error {
+        let predicate = match self.intern(predicate) {
+            Ok(v) => v,
+            Err(error) => return error,
+        };
+        self.make_signal(WRONG_TYPE_ARGUMENT, &[predicate, value]).unwrap_or_else(|error| error)
     }
-}
 
-// XXX: Technically these are unsound, but they are necessary to use the `Fail` trait. We ensure
-// safety by marking TempValue methods as unsafe.
-unsafe impl Send for TempValue {}
-unsafe impl Sync for TempValue {}
+    /// Returns an error that signals `(SYMBOL . DATA)` when it reaches Lisp, like `signal`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::*;
+    /// #[defun]
+    /// fn checked_div(env: &Env, x: i64, y: i64) -> Result<i64> {
+    ///     if y == 0 {
+    ///         return Err(env.signal("arith-error", &[x.into_lisp(env)?]));
+    ///     }
+    ///     Ok(x / y)
+    /// }
+    /// ```
+    pub fn signal<'e>(&'e self, symbol: &str, data: &[Value<'e>]) -> Error {
+        match self.list(data) {
+            Ok(data) => self.signal_typed(symbol, data),
+            Err(error) => error,
+        }
+    }
+
+    /// Returns an error that signals `(error MESSAGE)` when it reaches Lisp, like `error` with a
+    /// plain message.
+    pub fn error(&self, message: &str) -> Error {
+        match message.into_lisp(self) {
+            Ok(message) => self.signal("error", &[message]),
+            Err(error) => error,
+        }
+    }
+
+    /// Returns an error that signals `(circular-list LIST)` when it reaches Lisp, the same way
+    /// built-in list functions report cycles.
+    pub(crate) fn circular_list(&self, list: Value<'_>) -> Error {
+        self.make_signal("circular-list", &[list]).unwrap_or_else(|error| error)
+    }
+
+    /// Returns an error that signals `(file-error OPERATION MESSAGE PATH)` when it reaches Lisp,
+    /// with the same data layout as built-in file operations, e.g. `insert-file-contents`. This
+    /// allows Lisp code to handle failures of the module's file operations like other file errors.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::*;
+    /// #[defun]
+    /// fn read_config(env: &Env, path: String) -> Result<String> {
+    ///     std::fs::read_to_string(&path).map_err(|error| {
+    ///         env.file_error("Reading config file", &path, &error.to_string())
+    ///     })
+    /// }
+    /// ```
+    ///
+    /// ```emacs-lisp
+    /// (read-config "/none") ; *** Eval error ***  Reading config file: No such file ..., /none
+    /// ```
+    pub fn file_error(&self, operation: &str, path: &str, errno_msg: &str) -> Error {
+        let data: Result<Vec<_>> =
+            [operation, errno_msg, path].iter().map(|s| s.into_lisp(self)).collect();
+        data.and_then(|data| self.make_signal("file-error", &data)).unwrap_or_else(|error| error)
+    }
+
+    /// Adds a mapper that converts Rust errors returned to Lisp into more specific signals. When
+    /// an error that is not already a Lisp signal reaches Lisp, the mappers are tried in the order
+    /// they were added, before the built-in mapping of `std::io::Error`. The first one returning
+    /// an error created by e.g. [`signal_typed`] or [`file_error`] determines the signal. If none
+    /// does, `rust-error` is signaled with the error's message.
+    ///
+    /// Mappers are shared by all functions of the module.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, Error, Result};
+    /// # use std::num::ParseIntError;
+    /// fn map_parse_error(env: &Env, error: &Error) -> Option<Error> {
+    ///     let error = error.downcast_ref::<ParseIntError>()?;
+    ///     Some(env.signal_typed("my-parse-error", error.to_string()))
+    /// }
+    ///
+    /// fn init_errors(env: &Env) -> Result<()> {
+    ///     env.define_error("my-parse-error", "Invalid number", "error")?;
+    ///     env.add_error_mapper(map_parse_error);
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [`signal_typed`]: #method.signal_typed
+    /// [`file_error`]: #method.file_error
+    pub fn add_error_mapper(&self, mapper: ErrorMapper) {
+        MAPPERS.lock().expect("Failed to acquire lock of error mappers").push(mapper);
+    }
+
+    /// Returns an error that signals the given standard condition when it reaches Lisp, with the
+    /// same data as built-in commands use.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::*;
+    /// #[defun]
+    /// fn forward_record(env: &Env) -> Result<()> {
+    ///     let point: i64 = env.call("point", &[])?.into_rust()?;
+    ///     let max: i64 = env.call("point-max", &[])?.into_rust()?;
+    ///     if point == max {
+    ///         return Err(env.signal_standard(StandardCondition::EndOfBuffer));
+    ///     }
+    ///     // ...
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn signal_standard(&self, condition: StandardCondition) -> Error {
+        self.make_standard_signal(condition).unwrap_or_else(|error| error)
+    }
+
+    /// Defines a new error condition `name`, whose error message is `message`, as a sub-type of
+    /// `parent`, through [`define-error`].
+    ///
+    /// [`define-error`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Error-Symbols.html
+    pub fn define_error(&self, name: &str, message: &str, parent: &str) -> Result<Value<'_>> {
+        self.call(
+            "define-error",
+            &[self.intern(name)?, message.into_lisp(self)?, self.intern(parent)?],
+        )
+    }
+
+    /// Like [`define_error`], but also records the expected shape of the condition's data, which
+    /// is checked by [`signal_typed`] in debug builds.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{DataSchema, Env, Result};
+    /// fn init_errors(env: &Env) -> Result<()> {
+    ///     // (my-timeout SECONDS . HOST)
+    ///     let schema = DataSchema::Cons(
+    ///         Box::new(DataSchema::Predicate("integerp")),
+    ///         Box::new(DataSchema::Predicate("stringp")),
+    ///     );
+    ///     env.define_typed_error("my-timeout", "Timed out", "error", schema)?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [`define_error`]: #method.define_error
+    /// [`signal_typed`]: #method.signal_typed
+    pub fn define_typed_error(
+        &self,
+        name: &str,
+        message: &str,
+        parent: &str,
+        schema: DataSchema,
+    ) -> Result<Value<'_>> {
+        let symbol = self.define_error(name, message, parent)?;
+        SCHEMAS
+            .lock()
+            .expect("Failed to acquire lock of error schemas")
+            .insert(name.to_owned(), schema);
+        Ok(symbol)
+    }
+
+    /// Returns an error that signals the condition `symbol` with the given data when it reaches
+    /// Lisp, i.e. `(signal SYMBOL DATA)`.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, if the condition was defined by [`define_typed_error`], this panics if the
+    /// data doesn't match the condition's schema.
+    ///
+    /// [`define_typed_error`]: #method.define_typed_error
+    pub fn signal_typed<'e, T: IntoLisp<'e>>(&'e self, symbol: &str, data: T) -> Error {
+        let result = data.into_lisp(self).and_then(|data| {
+            #[cfg(debug_assertions)]
+            self.check_schema(symbol, data)?;
+            let symbol = GlobalRef::new(self.intern(symbol)?);
+            let data = GlobalRef::new(data);
+            Ok(ErrorKind::Signal { symbol, data }.into())
+        });
+        result.unwrap_or_else(|error| error)
+    }
+
+    /// Like [`signal_typed`], but the data is computed by calling `data`. This is useful with
+    /// combinators like `ok_or_else` and `map_err`, so that expensive data, e.g. a diagnostic dump,
+    /// is only computed on the error path. If `data` fails, its error is returned instead.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, IntoLisp, Result};
+    /// fn lookup(env: &Env, table: &[(String, i64)], key: &str) -> Result<i64> {
+    ///     table.iter().find(|(k, _)| k == key).map(|(_, v)| *v).ok_or_else(|| {
+    ///         env.signal_with("my-missing-key", || {
+    ///             let keys: Vec<&str> = table.iter().map(|(k, _)| k.as_str()).collect();
+    ///             format!("{} not in {:?}", key, keys).into_lisp(env)
+    ///         })
+    ///     })
+    /// }
+    /// ```
+    ///
+    /// [`signal_typed`]: #method.signal_typed
+    pub fn signal_with<'e, F>(&'e self, symbol: &str, data: F) -> Error
+    where
+        F: FnOnce() -> Result<Value<'e>>,
+    {
+        match data() {
+            Ok(data) => self.signal_typed(symbol, data),
+            Err(error) => error,
+        }
+    }
+
+    /// Calls `f`, and if it signals the condition `name` (or one of its sub-types), calls `recover`
+    /// with the signal's data instead of propagating the error, like a `condition-case` handler.
+    ///
+    /// Only Lisp signals are matched. Other errors, including throws, are propagated as-is.
+    ///
+    /// # Examples
+    ///
+    /// Skipping unreadable files during a batch operation:
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, Result};
+    /// fn total_length(env: &Env, paths: &[&str]) -> Result<usize> {
+    ///     let mut total = 0;
+    ///     for path in paths {
+    ///         total += env.with_restart(
+    ///             "file-error",
+    ///             || Ok(env.insert_file_contents(path)?.len()),
+    ///             |_| Ok(0),
+    ///         )?;
+    ///     }
+    ///     Ok(total)
+    /// }
+    /// ```
+    pub fn with_restart<'e, T, F, R>(&'e self, name: &str, f: F, recover: R) -> Result<T>
+    where
+        F: FnOnce() -> Result<T>,
+        R: FnOnce(Value<'e>) -> Result<T>,
+    {
+        let error = match f() {
+            Err(error) => error,
+            result => return result,
+        };
+        let data = match error.downcast_ref::<ErrorKind>() {
+            Some(&ErrorKind::Signal { ref symbol, ref data }) => {
+                let (symbol, data) = (symbol.bind(self), data.bind(self));
+                let conditions = self.call("get", &[symbol, self.intern("error-conditions")?])?;
+                if self.is_not_nil(self.call("memq", &[self.intern(name)?, conditions])?) {
+                    Some(data)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        };
+        match data {
+            Some(data) => recover(data),
+            None => Err(error),
+        }
+    }
+
+    /// Runs `body`, catching a Lisp `throw` to `tag`, like `catch`. If `body` returns an
+    /// [`ErrorKind::Throw`] error whose tag is `eq` to `tag`, the thrown value is converted to `T`
+    /// and returned. Other throws, and signals, are propagated unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, Result, Value};
+    /// fn first_match(env: &Env, predicate: Value<'_>, items: Value<'_>) -> Result<Option<i64>> {
+    ///     let tag = env.intern("found")?;
+    ///     env.catch(tag, |env| {
+    ///         // `predicate` may call `(throw 'found ITEM)`.
+    ///         env.call("mapc", &[predicate, items])?;
+    ///         Ok(None)
+    ///     })
+    /// }
+    /// ```
+    ///
+    /// [`ErrorKind::Throw`]: enum.ErrorKind.html#variant.Throw
+    pub fn catch<'e, T, F>(&'e self, tag: Value<'e>, body: F) -> Result<T>
+    where
+        T: FromLisp<'e>,
+        F: FnOnce(&'e Env) -> Result<T>,
+    {
+        let error = match body(self) {
+            Err(error) => error,
+            result => return result,
+        };
+        let value = match error.downcast_ref::<ErrorKind>() {
+            Some(&ErrorKind::Throw { tag: ref thrown, ref value }) => {
+                if !self.eq(thrown.bind(self), tag) {
+                    return Err(error);
+                }
+                value.bind(self)
+            }
+            _ => return Err(error),
+        };
+        value.into_rust()
+    }
+
+    /// Checks whether there is a pending non-local exit, which is the case after a raw module
+    /// function (obtained through [`raw`]) signals an error or throws. The non-local exit, if any,
+    /// is cleared and returned as an [`ErrorKind::Signal`] or [`ErrorKind::Throw`] error.
+    ///
+    /// All methods of `Env` already do this. This is only needed when calling raw module functions
+    /// directly.
+    ///
+    /// [`raw`]: #method.raw
+    /// [`ErrorKind::Signal`]: enum.ErrorKind.html#variant.Signal
+    /// [`ErrorKind::Throw`]: enum.ErrorKind.html#variant.Throw
+    pub fn check_exit(&self) -> Result<()> {
+        self.handle_exit(())
+    }
 
-impl Env {
     /// Handles possible non-local exit after calling Lisp code.
     #[inline]
     pub(crate) fn handle_exit<T>(&self, result: T) -> Result<T> {
@@ -116,16 +595,16 @@ impl Env {
             (SIGNAL, symbol, data) => {
                 self.non_local_exit_clear();
                 Err(ErrorKind::Signal {
-                    symbol: unsafe { TempValue::new(symbol) },
-                    data: unsafe { TempValue::new(data) },
+                    symbol: GlobalRef::new(unsafe { Value::new(symbol, self) }),
+                    data: GlobalRef::new(unsafe { Value::new(data, self) }),
                 }
                 .into())
             }
             (THROW, tag, value) => {
                 self.non_local_exit_clear();
                 Err(ErrorKind::Throw {
-                    tag: unsafe { TempValue::new(tag) },
-                    value: unsafe { TempValue::new(value) },
+                    tag: GlobalRef::new(unsafe { Value::new(tag, self) }),
+                    value: GlobalRef::new(unsafe { Value::new(value, self) }),
                 }
                 .into())
             }
@@ -139,55 +618,175 @@ impl Env {
         match result {
             Ok(v) => v.raw,
             Err(error) => match error.downcast_ref::<ErrorKind>() {
-                Some(&ErrorKind::Signal { ref symbol, ref data }) => {
-                    self.signal(symbol.raw, data.raw)
+                Some(&ErrorKind::Signal { ref symbol, ref data })
+                | Some(&ErrorKind::Read { ref symbol, ref data }) => {
+                    self.non_local_exit_signal(symbol.raw, data.raw)
+                }
+                Some(&ErrorKind::Throw { ref tag, ref value }) => {
+                    self.non_local_exit_throw(tag.raw, value.raw)
                 }
-                Some(&ErrorKind::Throw { ref tag, ref value }) => self.throw(tag.raw, value.raw),
                 Some(&ErrorKind::WrongTypeUserPtr { .. }) => self
-                    .signal_str(WRONG_TYPE_USER_PTR, &format!("{}", error))
+                    .signal_str(CoreSymbol::RustWrongTypeUserPtr, &format!("{}", error))
                     .unwrap_or_else(|_| panic!("Failed to signal {}", error)),
-                _ => self
-                    .signal_str(ERROR, &format!("{}", error))
+                Some(&ErrorKind::HandleClosed) => self
+                    .intern(HANDLE_CLOSED)
+                    .and_then(|symbol| Ok(self.non_local_exit_signal(symbol.raw, self.nil()?.raw)))
                     .unwrap_or_else(|_| panic!("Failed to signal {}", error)),
+                Some(&ErrorKind::WrongTypeArgument { name, ref cause }) => self
+                    .signal_wrong_type_argument(name, None, cause)
+                    .unwrap_or_else(|_| panic!("Failed to signal {}", error)),
+                Some(&ErrorKind::WrongTypeRestArgument { name, index, ref cause }) => self
+                    .signal_wrong_type_argument(name, Some(index), cause)
+                    .unwrap_or_else(|_| panic!("Failed to signal {}", error)),
+                _ => match self.map_error(&error) {
+                    Some((symbol, data)) => self.non_local_exit_signal(symbol.raw, data.raw),
+                    None => self
+                        .signal_str(CoreSymbol::RustError, &format!("{}", error))
+                        .unwrap_or_else(|_| panic!("Failed to signal {}", error)),
+                },
             },
         }
     }
 
+    /// Converts a Rust error into a Lisp signal, using the mappers added by `add_error_mapper`,
+    /// then the built-in ones. Mapped errors that are not signals are ignored.
+    fn map_error(&self, error: &Error) -> Option<(GlobalRef, GlobalRef)> {
+        let mappers = match MAPPERS.lock() {
+            Ok(mappers) => mappers.clone(),
+            Err(_) => vec![],
+        };
+        let builtins: [ErrorMapper; 1] = [map_io_error];
+        for mapper in mappers.iter().chain(builtins.iter()) {
+            if let Some(mapped) = mapper(self, error) {
+                if let Ok(ErrorKind::Signal { symbol, data }) = mapped.downcast::<ErrorKind>() {
+                    return Some((symbol, data));
+                }
+            }
+        }
+        None
+    }
+
     #[inline]
     pub(crate) fn handle_panic(&self, result: thread::Result<emacs_value>) -> emacs_value {
         match result {
             Ok(v) => v,
             Err(error) => {
                 // TODO: Try to check for some common types to display?
-                self.signal_str(PANIC, &format!("{:#?}", error))
-                    .unwrap_or_else(|_| panic!("Fail to signal panic {:#?}", error))
+                let message = format!("{:#?}", error);
+                let signaled = match globals::panic_condition() {
+                    Some(condition) => self
+                        .intern(&condition)
+                        .and_then(|symbol| self.signal_message(symbol, &message)),
+                    None => self.signal_str(CoreSymbol::RustPanic, &message),
+                };
+                signaled.unwrap_or_else(|_| panic!("Fail to signal panic {:#?}", error))
             }
         }
     }
 
     pub(crate) fn define_errors(&self) -> Result<()> {
+        let error = self.core_symbol(CoreSymbol::Error)?;
+        let rust_error = self.core_symbol(CoreSymbol::RustError)?;
+        let wrong_type_argument = self.intern(WRONG_TYPE_ARGUMENT)?;
         // FIX: Make panics louder than errors, by somehow make sure that 'rust-panic is
         // not a sub-type of 'error.
-        self.define_error(PANIC, "Rust panic", "error")?;
-        self.define_error(ERROR, "Rust error", "error")?;
+        self.define_core_error(CoreSymbol::RustPanic, "Rust panic", error)?;
+        self.define_core_error(CoreSymbol::RustError, "Rust error", error)?;
         // TODO: This should also be a sub-types of 'wrong-type-argument?
-        self.define_error(WRONG_TYPE_USER_PTR, "Wrong type user-ptr", ERROR)?;
+        let user_ptr = CoreSymbol::RustWrongTypeUserPtr;
+        self.define_core_error(user_ptr, "Wrong type user-ptr", rust_error)?;
+        self.define_core_error(
+            CoreSymbol::RustWrongTypeArgument,
+            "Wrong type argument",
+            wrong_type_argument,
+        )?;
+        let timeout = self.intern(TIMEOUT)?;
+        let message = "Timed out".into_lisp(self)?;
+        call_lisp!(self, "define-error", timeout, message, rust_error)?;
+        let handle_closed = self.intern(HANDLE_CLOSED)?;
+        let message = "Handle closed".into_lisp(self)?;
+        call_lisp!(self, "define-error", handle_closed, message, rust_error)?;
         Ok(())
     }
 
-    // TODO: Prepare static values for the symbols.
-    fn signal_str(&self, symbol: &str, message: &str) -> Result<emacs_value> {
+    fn define_core_error(&self, name: CoreSymbol, message: &str, parent: Value<'_>) -> Result<()> {
+        let name = self.core_symbol(name)?;
+        let message = message.into_lisp(self)?;
+        call_lisp!(self, "define-error", name, message, parent)?;
+        Ok(())
+    }
+
+    fn signal_str(&self, symbol: CoreSymbol, message: &str) -> Result<emacs_value> {
+        let symbol = self.core_symbol(symbol)?;
+        self.signal_message(symbol, message)
+    }
+
+    fn signal_message(&self, symbol: Value<'_>, message: &str) -> Result<emacs_value> {
         let message = message.into_lisp(&self)?;
         let data = self.list(&[message])?;
+        unsafe { Ok(self.non_local_exit_signal(symbol.raw, data.raw)) }
+    }
+
+    fn signal_wrong_type_argument(
+        &self,
+        name: &str,
+        index: Option<usize>,
+        cause: &Error,
+    ) -> Result<emacs_value> {
+        let mut prefix = vec![self.intern(name)?];
+        if let Some(index) = index {
+            prefix.push((index as i64).into_lisp(self)?);
+        }
+        let data = match cause.downcast_ref::<ErrorKind>() {
+            Some(&ErrorKind::Signal { ref data, .. }) => {
+                let data = data.bind(self);
+                let prefix = self.list(&prefix)?;
+                call_lisp!(self, "append", prefix, data)?
+            }
+            _ => {
+                prefix.push(format!("{}", cause).into_lisp(self)?);
+                self.list(&prefix)?
+            }
+        };
+        let symbol = self.core_symbol(CoreSymbol::RustWrongTypeArgument)?;
+        unsafe { Ok(self.non_local_exit_signal(symbol.raw, data.raw)) }
+    }
+
+    /// Creates an error that signals the given symbol with the given data, once it reaches Lisp.
+    pub(crate) fn make_signal(&self, symbol: &str, data: &[Value<'_>]) -> Result<Error> {
         let symbol = self.intern(symbol)?;
-        unsafe { Ok(self.signal(symbol.raw, data.raw)) }
+        let data = self.list(data)?;
+        Ok(ErrorKind::Signal { symbol: GlobalRef::new(symbol), data: GlobalRef::new(data) }.into())
     }
 
-    fn define_error(&self, name: &str, message: &str, parent: &str) -> Result<Value<'_>> {
-        self.call(
-            "define-error",
-            &[self.intern(name)?, message.into_lisp(self)?, self.intern(parent)?],
-        )
+    fn make_standard_signal(&self, condition: StandardCondition) -> Result<Error> {
+        match condition {
+            StandardCondition::BeginningOfBuffer => self.make_signal("beginning-of-buffer", &[]),
+            StandardCondition::EndOfBuffer => self.make_signal("end-of-buffer", &[]),
+            StandardCondition::BufferReadOnly => {
+                let buffer = self.call("current-buffer", &[])?;
+                self.make_signal("buffer-read-only", &[buffer])
+            }
+            StandardCondition::MarkInactive => self.make_signal("mark-inactive", &[]),
+            StandardCondition::Quit => self.make_signal("quit", &[]),
+            StandardCondition::ScanError { message, start, end } => {
+                let data = [message.into_lisp(self)?, start.into_lisp(self)?, end.into_lisp(self)?];
+                self.make_signal("scan-error", &data)
+            }
+        }
+    }
+
+    #[cfg(debug_assertions)]
+    fn check_schema(&self, symbol: &str, data: Value<'_>) -> Result<()> {
+        let schema =
+            SCHEMAS.lock().expect("Failed to acquire lock of error schemas").get(symbol).cloned();
+        if let Some(schema) = schema {
+            if !schema.matches(data)? {
+                let data: String = self.call("prin1-to-string", &[data])?.into_rust()?;
+                panic!("Data {} does not match the schema of {}: {:?}", data, symbol, schema);
+            }
+        }
+        Ok(())
     }
 
     fn non_local_exit_get(
@@ -211,7 +810,7 @@ impl Env {
     ///
     /// The given raw values must still live.
     #[allow(unused_unsafe)]
-    unsafe fn throw(&self, tag: emacs_value, value: emacs_value) -> emacs_value {
+    unsafe fn non_local_exit_throw(&self, tag: emacs_value, value: emacs_value) -> emacs_value {
         raw_call_no_exit!(self, non_local_exit_throw, tag, value);
         tag
     }
@@ -220,8 +819,22 @@ impl Env {
     ///
     /// The given raw values must still live.
     #[allow(unused_unsafe)]
-    unsafe fn signal(&self, symbol: emacs_value, data: emacs_value) -> emacs_value {
+    unsafe fn non_local_exit_signal(&self, symbol: emacs_value, data: emacs_value) -> emacs_value {
         raw_call_no_exit!(self, non_local_exit_signal, symbol, data);
         symbol
     }
 }
+
+/// Maps `std::io::Error` to `(file-missing "Rust I/O error" MESSAGE)` if the file was not found,
+/// and `(file-error "Rust I/O error" MESSAGE)` otherwise.
+fn map_io_error(env: &Env, error: &Error) -> Option<Error> {
+    let error = error.downcast_ref::<io::Error>()?;
+    let symbol = match error.kind() {
+        io::ErrorKind::NotFound => "file-missing",
+        _ => "file-error",
+    };
+    let message = error.to_string();
+    let data: Result<Vec<_>> =
+        vec!["Rust I/O error".into_lisp(env), message.into_lisp(env)].into_iter().collect();
+    Some(data.and_then(|data| env.make_signal(symbol, &data)).unwrap_or_else(|error| error))
+}
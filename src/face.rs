@@ -0,0 +1,45 @@
+use std::{cell::RefCell, collections::HashMap};
+
+use emacs_module::emacs_value;
+
+use super::error::Result;
+use super::{Env, Value};
+
+thread_local! {
+    /// Global references to the face symbols already validated by `Env::face`. They are never
+    /// freed, since faces cannot be undefined.
+    static FACES: RefCell<HashMap<String, emacs_value>> = RefCell::new(HashMap::new());
+}
+
+impl Env {
+    /// Returns the symbol naming the given [face], signaling `(wrong-type-argument facep NAME)` if
+    /// it is not defined.
+    ///
+    /// The result is cached, so subsequent calls with the same name are cheap, and return values
+    /// that are `eq` to each other.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, IntoLisp, Result, Value};
+    /// fn highlight(env: &Env, start: i64, end: i64) -> Result<Value<'_>> {
+    ///     let face = env.face("font-lock-warning-face")?;
+    ///     let args = [start.into_lisp(env)?, end.into_lisp(env)?, env.intern("face")?, face];
+    ///     env.call("put-text-property", &args)
+    /// }
+    /// ```
+    ///
+    /// [face]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Faces.html
+    pub fn face(&self, name: &str) -> Result<Value<'_>> {
+        if let Some(raw) = FACES.with(|faces| faces.borrow().get(name).cloned()) {
+            return Ok(Value { raw, env: self });
+        }
+        let symbol = self.intern(name)?;
+        if !self.is_not_nil(call_lisp!(self, "facep", symbol)?) {
+            return Err(self.wrong_type("facep", symbol));
+        }
+        let raw = raw_call_no_exit!(self, make_global_ref, symbol.raw);
+        FACES.with(|faces| faces.borrow_mut().insert(name.to_owned(), raw));
+        Ok(Value { raw, env: self })
+    }
+}
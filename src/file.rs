@@ -0,0 +1,93 @@
+use std::path::PathBuf;
+
+use super::error::Result;
+use super::{Env, IntoLisp};
+
+/// Accessors for files, going through Emacs's file handlers (e.g. TRAMP for remote files) and
+/// coding systems, instead of `std::fs`. See [Files].
+///
+/// [Files]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Files.html
+impl Env {
+    /// Returns the contents of the given file, decoded by [`insert-file-contents`].
+    ///
+    /// The coding system is detected automatically, unless `coding-system-for-read` is set, e.g.
+    /// through [`with_coding_system`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, Result};
+    /// fn read_config(env: &Env) -> Result<String> {
+    ///     env.insert_file_contents("/ssh:host:~/.config/app.toml")
+    /// }
+    /// ```
+    ///
+    /// [`insert-file-contents`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Reading-from-Files.html
+    /// [`with_coding_system`]: #method.with_coding_system
+    pub fn insert_file_contents(&self, path: &str) -> Result<String> {
+        let path = path.into_lisp(self)?;
+        self.with_temp_buffer(|_| {
+            call_lisp!(self, "insert-file-contents", path)?;
+            call_lisp!(self, "buffer-string")?.into_rust()
+        })
+    }
+
+    /// Writes `contents` to the given file, encoded by [`write-region`], replacing the file's
+    /// previous contents. Like `with-temp-file`, this doesn't display a message in the echo area.
+    ///
+    /// The coding system is chosen automatically, unless `coding-system-for-write` is set, e.g.
+    /// through [`with_coding_system`].
+    ///
+    /// [`write-region`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Writing-to-Files.html
+    /// [`with_coding_system`]: #method.with_coding_system
+    pub fn write_file(&self, path: &str, contents: &str) -> Result<()> {
+        let path = path.into_lisp(self)?;
+        let contents = contents.into_lisp(self)?;
+        self.with_temp_buffer(|_| {
+            call_lisp!(self, "insert", contents)?;
+            let nil = self.nil()?;
+            let quiet = 0.into_lisp(self)?;
+            call_lisp!(self, "write-region", nil, nil, path, nil, quiet)?;
+            Ok(())
+        })
+    }
+
+    /// Returns the names of the files in the given directory, through [`directory-files`]. If
+    /// `full` is true, the names are absolute, otherwise they are relative to `dir`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::path::PathBuf;
+    /// # use emacs::{Env, Result};
+    /// fn project_files(env: &Env, root: &str) -> Result<Vec<PathBuf>> {
+    ///     env.directory_files(root, true)
+    /// }
+    /// ```
+    ///
+    /// [`directory-files`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Contents-of-Directories.html
+    pub fn directory_files(&self, dir: &str, full: bool) -> Result<Vec<PathBuf>> {
+        self.directory_files_matching(dir, full, None)
+    }
+
+    /// Like [`directory_files`], but if `regexp` is given, only returns the names that match it.
+    ///
+    /// [`directory_files`]: #method.directory_files
+    pub fn directory_files_matching(
+        &self,
+        dir: &str,
+        full: bool,
+        regexp: Option<&str>,
+    ) -> Result<Vec<PathBuf>> {
+        let dir = dir.into_lisp(self)?;
+        let full = full.into_lisp(self)?;
+        let regexp = regexp.into_lisp(self)?;
+        let mut list = call_lisp!(self, "directory-files", dir, full, regexp)?;
+        let mut files = vec![];
+        while self.is_not_nil(list) {
+            files.push(call_lisp!(self, "car", list)?.into_rust()?);
+            list = call_lisp!(self, "cdr", list)?;
+        }
+        Ok(files)
+    }
+}
@@ -3,17 +3,28 @@
 //!
 //! [`Env`]: struct.Env.html
 
+use std::collections::HashMap;
 use std::ffi::CString;
 use std::ops::{Deref, Range};
 use std::panic;
 use std::slice;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 use emacs_module::{emacs_value, EmacsSubr};
+use lazy_static::lazy_static;
 
-use super::error::Result;
-use super::{CallEnv, Env, Value};
+use super::error::{ErrorKind, Result};
+use super::symbol::CoreSymbol;
+use super::{CallEnv, Closure, Env, Plist, Value};
 use super::{FromLisp, IntoLisp};
 
+/// The maximum arity of a function with a `&rest` parameter. Used by `#[defun(rest)]`.
+pub const VARIADIC: usize = usize::MAX;
+
+/// `emacs_variadic_function` in `emacs-module.h`.
+const EMACS_VARIADIC_FUNCTION: isize = -2;
+
 pub trait Manage {
     unsafe fn make_function<T: Into<Vec<u8>>>(
         &self,
@@ -51,11 +62,15 @@ impl Manage for Env {
         doc: T,
         data: *mut libc::c_void,
     ) -> Result<Value<'_>> {
+        let max = match arities.end {
+            VARIADIC => EMACS_VARIADIC_FUNCTION,
+            end => end as isize,
+        };
         raw_call_value!(
             self,
             make_function,
             arities.start as isize,
-            arities.end as isize,
+            max,
             Some(function),
             CString::new(doc)?.as_ptr(),
             data
@@ -93,6 +108,143 @@ impl HandleInit for Env {
     }
 }
 
+/// A Rust function whose parameters and return value can all be converted from/to Lisp, without
+/// depending on any particular `Env`. This is implemented for functions of up to 8 parameters of
+/// owned types like `i64`, `f64`, `String`. It is used by [`defun_for_types!`].
+///
+/// [`defun_for_types!`]: ../macro.defun_for_types.html
+pub trait Exportable<Args> {
+    const ARITY: usize;
+
+    fn call_with<'e>(&self, env: &'e CallEnv) -> Result<Value<'e>>;
+}
+
+macro_rules! impl_exportable {
+    ($arity:expr; $($arg:ident $nth:expr),*) => {
+        impl<F, R, $($arg),*> Exportable<($($arg,)*)> for F
+        where
+            F: Fn($($arg),*) -> Result<R>,
+            R: for<'e> IntoLisp<'e>,
+            $($arg: for<'e> FromLisp<'e>,)*
+        {
+            const ARITY: usize = $arity;
+
+            #[allow(unused_variables)]
+            fn call_with<'e>(&self, env: &'e CallEnv) -> Result<Value<'e>> {
+                self($(env.parse_arg::<$arg>($nth)?),*)?.into_lisp(env)
+            }
+        }
+    };
+}
+
+impl_exportable!(0;);
+impl_exportable!(1; A 0);
+impl_exportable!(2; A 0, B 1);
+impl_exportable!(3; A 0, B 1, C 2);
+impl_exportable!(4; A 0, B 1, C 2, D 3);
+impl_exportable!(5; A 0, B 1, C 2, D 3, E 4);
+impl_exportable!(6; A 0, B 1, C 2, D 3, E 4, G 5);
+impl_exportable!(7; A 0, B 1, C 2, D 3, E 4, G 5, H 6);
+impl_exportable!(8; A 0, B 1, C 2, D 3, E 4, G 5, H 6, I 7);
+
+/// Binds `name` to a Lisp function that calls `f`. Used by [`defun_for_types!`].
+///
+/// [`defun_for_types!`]: ../macro.defun_for_types.html
+pub fn export<F, A>(env: &Env, name: &str, f: F) -> Result<()>
+where
+    F: Exportable<A> + 'static,
+    A: 'static,
+{
+    let func = Closure::shared(F::ARITY..F::ARITY, move |env| f.call_with(env)).into_lisp(env)?;
+    env.fset(name, func)?;
+    Ok(())
+}
+
+/// Converts a Rust type's name into a suffix for a Lisp function name, e.g. `Vec<i64>` into
+/// `vec-i64`. Used by [`defun_for_types!`].
+///
+/// [`defun_for_types!`]: ../macro.defun_for_types.html
+pub fn lisp_type_name(ty: &str) -> String {
+    let name: String = ty
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| if c.is_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect();
+    name.trim_matches('-').to_owned()
+}
+
+/// Attaches the name of an argument to its conversion error. Used by `#[defun(strict_args)]`.
+#[inline]
+pub fn check_arg<T>(result: Result<T>, name: &'static str) -> Result<T> {
+    result.map_err(|cause| ErrorKind::WrongTypeArgument { name, cause }.into())
+}
+
+/// Attaches the name of a `&rest` parameter, and the index among the rest arguments, to the
+/// conversion error of one of them. Used by `#[defun(rest)]`.
+#[inline]
+pub fn check_rest_arg<T>(result: Result<T>, name: &'static str, index: usize) -> Result<T> {
+    result.map_err(|cause| ErrorKind::WrongTypeRestArgument { name, index, cause }.into())
+}
+
+/// Replaces the function bound to `name` with an interactive Lisp wrapper, whose interactive form
+/// is read from `form`, and which applies the original function to its arguments. Used by
+/// `#[defun(interactive_form = "...")]`, since module functions cannot be made interactive
+/// directly before Emacs 28.
+pub fn make_interactive(env: &Env, name: &str, doc: &str, form: &str) -> Result<()> {
+    let symbol = env.intern(name)?;
+    let func = call_lisp!(env, "symbol-function", symbol)?;
+    let args = env.intern("args")?;
+    let interactive = env.list(&[env.intern("interactive")?, env.read(form)?])?;
+    let quoted = env.list(&[env.core_symbol(CoreSymbol::Quote)?, func])?;
+    let body = env.list(&[env.intern("apply")?, quoted, args])?;
+    let lambda = env.list(&[
+        env.core_symbol(CoreSymbol::Lambda)?,
+        env.list(&[env.intern("&rest")?, args])?,
+        doc.into_lisp(env)?,
+        interactive,
+        body,
+    ])?;
+    env.fset(name, lambda)?;
+    Ok(())
+}
+
+/// Number of calls and cumulative time of a function declared with `#[defun(trace)]`.
+#[derive(Debug, Default, Clone, Copy)]
+struct CallStats {
+    calls: i64,
+    total: Duration,
+}
+
+lazy_static! {
+    /// Call statistics of functions declared with `#[defun(trace)]`, keyed by their Rust paths.
+    static ref CALL_STATS: Mutex<HashMap<&'static str, CallStats>> = Mutex::new(HashMap::new());
+}
+
+/// Calls `f`, recording the call and its duration under `key`. Used by `#[defun(trace)]`.
+pub fn trace<T, F: FnOnce() -> T>(key: &'static str, f: F) -> T {
+    let start = Instant::now();
+    let result = f();
+    let elapsed = start.elapsed();
+    let mut all = CALL_STATS.lock().expect("Failed to acquire lock of call stats");
+    let stats = all.entry(key).or_default();
+    stats.calls += 1;
+    stats.total += elapsed;
+    result
+}
+
+/// Returns the call statistics recorded under `key`, as a plist `(:calls N :total-time SECONDS)`.
+/// Used by `#[defun(trace)]`.
+pub fn trace_stats<'e>(env: &'e Env, key: &str) -> Result<Value<'e>> {
+    let stats = CALL_STATS
+        .lock()
+        .expect("Failed to acquire lock of call stats")
+        .get(key)
+        .cloned()
+        .unwrap_or_default();
+    let seconds = stats.total.as_secs() as f64 + f64::from(stats.total.subsec_nanos()) * 1e-9;
+    Plist::new(env).put("calls", stats.calls)?.put("total-time", seconds)?.build()
+}
+
 // TODO: Iterator and Index
 impl CallEnv {
     #[doc(hidden)]
@@ -0,0 +1,79 @@
+use super::error::Result;
+use super::{Env, FromLisp, Value};
+
+/// Memory usage statistics returned by [`garbage-collect`], parsed by [`FromLisp`].
+///
+/// [`garbage-collect`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Garbage-Collection.html
+/// [`FromLisp`]: trait.FromLisp.html
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GcStats {
+    /// One entry per kind of object, in the order returned by Emacs. This is empty if garbage
+    /// collection was inhibited.
+    pub entries: Vec<GcEntry>,
+}
+
+/// Statistics about one kind of object, e.g. `(conses 16 USED FREE)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GcEntry {
+    /// The kind of object, e.g. `conses`, `strings`, `vector-slots`.
+    pub name: String,
+    /// The size of each object, in bytes.
+    pub size: i64,
+    /// The number of objects in use.
+    pub used: i64,
+    /// The number of objects that are free but not returned to the OS, if reported.
+    pub free: Option<i64>,
+}
+
+impl Env {
+    /// Runs a garbage collection, through [`garbage-collect`], returning the memory usage
+    /// statistics. This can be converted into [`GcStats`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, GcStats, Result};
+    /// fn live_conses(env: &Env) -> Result<i64> {
+    ///     let stats: GcStats = env.garbage_collect()?.into_rust()?;
+    ///     Ok(stats.get("conses").map_or(0, |entry| entry.used))
+    /// }
+    /// ```
+    ///
+    /// [`garbage-collect`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Garbage-Collection.html
+    /// [`GcStats`]: struct.GcStats.html
+    pub fn garbage_collect(&self) -> Result<Value<'_>> {
+        call_lisp!(self, "garbage-collect")
+    }
+}
+
+impl GcStats {
+    /// Returns the entry for the given kind of object.
+    pub fn get(&self, name: &str) -> Option<&GcEntry> {
+        self.entries.iter().find(|entry| entry.name == name)
+    }
+}
+
+impl FromLisp<'_> for GcStats {
+    fn from_lisp(value: Value<'_>) -> Result<Self> {
+        let env = value.env;
+        let mut entries = vec![];
+        let mut list = value;
+        while env.is_not_nil(list) {
+            let entry = call_lisp!(env, "car", list)?;
+            let name = call_lisp!(env, "car", entry)?;
+            let mut numbers = call_lisp!(env, "cdr", entry)?;
+            let mut next = || -> Result<Option<i64>> {
+                let number = call_lisp!(env, "car", numbers)?.into_rust()?;
+                numbers = call_lisp!(env, "cdr", numbers)?;
+                Ok(number)
+            };
+            let size = next()?.unwrap_or(0);
+            let used = next()?.unwrap_or(0);
+            let free = next()?;
+            let name = call_lisp!(env, "symbol-name", name)?.into_rust()?;
+            entries.push(GcEntry { name, size, used, free });
+            list = call_lisp!(env, "cdr", list)?;
+        }
+        Ok(GcStats { entries })
+    }
+}
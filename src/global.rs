@@ -0,0 +1,99 @@
+use std::mem;
+use std::sync::Mutex;
+
+use emacs_module::emacs_value;
+use lazy_static::lazy_static;
+
+use super::{Env, Value};
+
+/// A global reference to a Lisp value, which keeps the value alive until the reference is dropped.
+/// Unlike a [`Value`], it is not bound to the [`Env`] it comes from, so it can be stored across
+/// module calls, e.g. to cache symbols or keymaps in a `lazy_static`.
+///
+/// Freeing a global reference requires an [`Env`], which is not available in `Drop`. Dropped
+/// references are therefore queued, and freed when the next [`Env`] is dropped, i.e. at the end of
+/// the next call into the module.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use emacs::{defun, Env, GlobalRef, Result, Value};
+/// # use lazy_static::lazy_static;
+/// # use std::sync::Mutex;
+/// lazy_static! {
+///     static ref KEYMAP: Mutex<Option<GlobalRef>> = Mutex::new(None);
+/// }
+///
+/// #[defun]
+/// fn keymap(env: &Env) -> Result<Value<'_>> {
+///     let mut keymap = KEYMAP.lock().expect("Failed to lock keymap cache");
+///     if keymap.is_none() {
+///         *keymap = Some(GlobalRef::new(env.make_sparse_keymap(None)?));
+///     }
+///     Ok(keymap.as_ref().unwrap().bind(env))
+/// }
+/// ```
+///
+/// [`Value`]: struct.Value.html
+/// [`Env`]: struct.Env.html
+#[derive(Debug)]
+pub struct GlobalRef {
+    pub(crate) raw: emacs_value,
+}
+
+/// Raw global references whose [`GlobalRef`] was dropped, waiting to be freed.
+///
+/// [`GlobalRef`]: struct.GlobalRef.html
+struct Pending(Vec<emacs_value>);
+
+// Safety: The raw values are only used (freed) on the Lisp thread, through an `Env`.
+unsafe impl Send for Pending {}
+
+lazy_static! {
+    static ref PENDING: Mutex<Pending> = Mutex::new(Pending(vec![]));
+}
+
+// Safety: The raw value can only be used through an `Env`, which checks that it is on the Lisp
+// thread. Dropping on other threads only queues the raw value.
+unsafe impl Send for GlobalRef {}
+unsafe impl Sync for GlobalRef {}
+
+impl GlobalRef {
+    /// Creates a global reference to the given value.
+    pub fn new(value: Value<'_>) -> Self {
+        let env = value.env;
+        let raw = raw_call_no_exit!(env, make_global_ref, value.raw);
+        GlobalRef { raw }
+    }
+
+    /// Returns the referenced value, bound to the given [`Env`]. The value stays alive during the
+    /// lifetime of `env`, even if this reference is dropped before that.
+    ///
+    /// [`Env`]: struct.Env.html
+    pub fn bind<'e>(&self, env: &'e Env) -> Value<'e> {
+        // Safety: The raw value is kept alive by this reference.
+        unsafe { Value::new_protected(self.raw, env) }
+    }
+}
+
+impl Drop for GlobalRef {
+    fn drop(&mut self) {
+        // If the lock is poisoned, the raw value is leaked, which is safe.
+        if let Ok(mut pending) = PENDING.lock() {
+            pending.0.push(self.raw);
+        }
+    }
+}
+
+impl Env {
+    /// Frees the global references that were dropped since the last time this was called.
+    pub(crate) fn free_dropped_global_refs(&self) {
+        let raws = match PENDING.lock() {
+            Ok(mut pending) => mem::replace(&mut pending.0, vec![]),
+            Err(_) => return,
+        };
+        for raw in raws {
+            raw_call_no_exit!(self, free_global_ref, raw);
+        }
+    }
+}
@@ -27,6 +27,25 @@ lazy_static! {
     pub static ref __PREFIX__: Mutex<[String; 2]> = Mutex::new(["".to_owned(), "-".to_owned()]);
 
     pub static ref __MOD_IN_NAME__: AtomicBool = AtomicBool::new(true);
+
+    /// Condition signaled for panics, set by the `panic_condition` option of [`#[module]`]. `None`
+    /// means `rust-panic`.
+    ///
+    /// [`#[module]`]: /emacs-macros/*/emacs_macros/attr.module.html
+    static ref PANIC_CONDITION: Mutex<Option<String>> = Mutex::new(None);
+}
+
+/// Defines `name` as a sub-type of `rust-panic`, and makes it the condition signaled for panics.
+pub fn set_panic_condition(env: &Env, name: &str) -> Result<()> {
+    env.define_error(name, "Rust panic", crate::error::PANIC)?;
+    *PANIC_CONDITION.lock().expect("Failed to acquire lock of panic condition") =
+        Some(name.to_owned());
+    Ok(())
+}
+
+/// Returns the condition to signal for panics, if it is not `rust-panic`.
+pub(crate) fn panic_condition() -> Option<String> {
+    PANIC_CONDITION.lock().ok().and_then(|condition| condition.clone())
 }
 
 fn lisp_name(s: &str) -> String {
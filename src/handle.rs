@@ -0,0 +1,100 @@
+use std::cell::{Ref, RefCell, RefMut};
+
+use super::error::{ErrorKind, Result};
+use super::Transfer;
+
+/// A resource shared with Lisp, e.g. a database connection, that Lisp code can release explicitly,
+/// instead of waiting for the GC.
+///
+/// A `Handle` is embedded in a `user-ptr` object. Once it is [`close`]d, the resource is taken out
+/// of it, and any further access fails with [`ErrorKind::HandleClosed`], which is signaled to Lisp
+/// as `rust-handle-closed`. The `user-ptr` object itself stays valid until it is garbage-collected.
+/// Module functions take it as `&Handle<T>`, listed in the `#[defun]` option `direct`.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use emacs::{defun, Handle, Result};
+/// # struct Connection;
+/// # impl Connection {
+/// #     fn open(path: String) -> Result<Self> { Ok(Connection) }
+/// #     fn query(&mut self, sql: String) -> Result<i64> { Ok(0) }
+/// # }
+/// #[defun(user_ptr(direct))]
+/// fn open(path: String) -> Result<Handle<Connection>> {
+///     Ok(Handle::new(Connection::open(path)?))
+/// }
+///
+/// #[defun(direct(db))]
+/// fn query(db: &Handle<Connection>, sql: String) -> Result<i64> {
+///     db.borrow_mut()?.query(sql)
+/// }
+///
+/// /// Close DB, dropping its connection. Return t if it was open.
+/// #[defun(direct(db))]
+/// fn close(db: &Handle<Connection>) -> Result<bool> {
+///     Ok(db.close()?.is_some())
+/// }
+/// ```
+///
+/// ```emacs-lisp
+/// (let ((db (my-db-open "/tmp/test.db")))
+///   (unwind-protect (my-db-query db "SELECT 1")
+///     (my-db-close db)))
+/// ```
+///
+/// [`close`]: #method.close
+/// [`ErrorKind::HandleClosed`]: enum.ErrorKind.html#variant.HandleClosed
+#[derive(Debug)]
+pub struct Handle<T> {
+    value: RefCell<Option<T>>,
+}
+
+impl<T> Transfer for Handle<T> {
+    fn type_name() -> &'static str {
+        "Handle"
+    }
+}
+
+impl<T> Handle<T> {
+    /// Creates an open handle to `value`.
+    pub fn new(value: T) -> Self {
+        Handle { value: RefCell::new(Some(value)) }
+    }
+
+    /// Immutably borrows the resource. Fails if the handle is closed, or if the resource is
+    /// mutably borrowed.
+    pub fn borrow(&self) -> Result<Ref<'_, T>> {
+        let value = self.value.try_borrow()?;
+        if value.is_none() {
+            return Err(ErrorKind::HandleClosed.into());
+        }
+        Ok(Ref::map(value, |value| value.as_ref().unwrap()))
+    }
+
+    /// Mutably borrows the resource. Fails if the handle is closed, or if the resource is already
+    /// borrowed.
+    pub fn borrow_mut(&self) -> Result<RefMut<'_, T>> {
+        let value = self.value.try_borrow_mut()?;
+        if value.is_none() {
+            return Err(ErrorKind::HandleClosed.into());
+        }
+        Ok(RefMut::map(value, |value| value.as_mut().unwrap()))
+    }
+
+    /// Closes the handle, returning the resource, or `None` if the handle was already closed. The
+    /// resource is cleaned up when the returned value is dropped. Fails if the resource is
+    /// borrowed.
+    pub fn close(&self) -> Result<Option<T>> {
+        Ok(self.value.try_borrow_mut()?.take())
+    }
+
+    /// Returns `true` if the handle is closed. A handle whose resource is mutably borrowed is
+    /// considered open.
+    pub fn is_closed(&self) -> bool {
+        match self.value.try_borrow() {
+            Ok(value) => value.is_none(),
+            Err(_) => false,
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::rc::Rc;
+
+use super::error::Result;
+use super::{Closure, Env, FromLisp, GlobalRef, IntoLisp, Value};
+
+/// Tests for comparing keys of a Lisp hash table, used by [`make_hash_table`]. See [Creating Hash].
+///
+/// This and [`HashTableWeakness`] are exported at the crate root, so their names carry the
+/// `HashTable` prefix, instead of being just `HashTest` and `Weakness`, which would not say what
+/// they apply to.
+///
+/// [`HashTableWeakness`]: enum.HashTableWeakness.html
+/// [`make_hash_table`]: struct.Env.html#method.make_hash_table
+/// [Creating Hash]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Creating-Hash.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashTableTest {
+    /// `eq`: keys are the same Lisp object.
+    Eq,
+
+    /// `eql`: like `eq`, but numbers of the same type and value are also considered the same. This
+    /// is the default of `make-hash-table`.
+    Eql,
+
+    /// `equal`: keys have the same structure and contents, e.g. strings with the same characters.
+    Equal,
+}
+
+impl HashTableTest {
+    fn symbol_name(self) -> &'static str {
+        match self {
+            HashTableTest::Eq => "eq",
+            HashTableTest::Eql => "eql",
+            HashTableTest::Equal => "equal",
+        }
+    }
+}
+
+/// Weakness of a Lisp hash table, used by [`make_hash_table`]. An entry of a weak table can be
+/// removed by the GC if its key and/or value is only referenced by weak references.
+///
+/// [`make_hash_table`]: struct.Env.html#method.make_hash_table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashTableWeakness {
+    /// `key`: an entry is kept as long as its key is referenced elsewhere.
+    Key,
+
+    /// `value`: an entry is kept as long as its value is referenced elsewhere.
+    Value,
+
+    /// `key-or-value`: an entry is kept as long as its key or its value is referenced elsewhere.
+    KeyOrValue,
+
+    /// `key-and-value`: an entry is kept as long as both its key and its value are referenced
+    /// elsewhere.
+    KeyAndValue,
+}
+
+impl HashTableWeakness {
+    fn symbol_name(self) -> &'static str {
+        match self {
+            HashTableWeakness::Key => "key",
+            HashTableWeakness::Value => "value",
+            HashTableWeakness::KeyOrValue => "key-or-value",
+            HashTableWeakness::KeyAndValue => "key-and-value",
+        }
+    }
+}
+
+/// Helpers for Lisp hash tables. See [Hash Tables].
+///
+/// [Hash Tables]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Hash-Tables.html
+impl Env {
+    /// Creates an empty hash table that compares keys with `test`, with room for `size` entries
+    /// before it needs to grow, like `make-hash-table`. The table is weak if `weakness` is given.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::collections::HashMap;
+    /// # use emacs::{Env, HashTableTest, Result, Value};
+    /// fn to_table<'e>(env: &'e Env, map: &HashMap<String, i64>) -> Result<Value<'e>> {
+    ///     let table = env.make_hash_table(HashTableTest::Equal, map.len(), None)?;
+    ///     for (key, value) in map {
+    ///         table.puthash(key.as_str(), *value)?;
+    ///     }
+    ///     Ok(table)
+    /// }
+    /// ```
+    pub fn make_hash_table(
+        &self,
+        test: HashTableTest,
+        size: usize,
+        weakness: Option<HashTableWeakness>,
+    ) -> Result<Value<'_>> {
+        let test_key = self.intern(":test")?;
+        let test = self.intern(test.symbol_name())?;
+        let size_key = self.intern(":size")?;
+        let size = (size as i64).into_lisp(self)?;
+        match weakness {
+            None => call_lisp!(self, "make-hash-table", test_key, test, size_key, size),
+            Some(weakness) => {
+                let weakness_key = self.intern(":weakness")?;
+                let weakness = self.intern(weakness.symbol_name())?;
+                call_lisp!(
+                    self,
+                    "make-hash-table",
+                    test_key,
+                    test,
+                    size_key,
+                    size,
+                    weakness_key,
+                    weakness
+                )
+            }
+        }
+    }
+
+    /// Associates `key` with `value` in `table`, replacing any existing association, like
+    /// `puthash`.
+    pub fn puthash<'e, K, V>(&'e self, table: Value<'e>, key: K, value: V) -> Result<()>
+    where
+        K: IntoLisp<'e>,
+        V: IntoLisp<'e>,
+    {
+        let key = key.into_lisp(self)?;
+        let value = value.into_lisp(self)?;
+        call_lisp!(self, "puthash", key, value, table)?;
+        Ok(())
+    }
+
+    /// Returns the value associated with `key` in `table`, or `default` if there is none, like
+    /// `gethash`.
+    pub fn gethash<'e, K, D>(&'e self, table: Value<'e>, key: K, default: D) -> Result<Value<'e>>
+    where
+        K: IntoLisp<'e>,
+        D: IntoLisp<'e>,
+    {
+        let key = key.into_lisp(self)?;
+        let default = default.into_lisp(self)?;
+        call_lisp!(self, "gethash", key, table, default)
+    }
+
+    /// Removes the association of `key` from `table`, if there is one, like `remhash`.
+    pub fn remhash<'e, K: IntoLisp<'e>>(&'e self, table: Value<'e>, key: K) -> Result<()> {
+        let key = key.into_lisp(self)?;
+        call_lisp!(self, "remhash", key, table)?;
+        Ok(())
+    }
+}
+
+/// Methods for hash table values. See [`make_hash_table`].
+///
+/// [`make_hash_table`]: struct.Env.html#method.make_hash_table
+impl<'e> Value<'e> {
+    /// Associates `key` with `value` in this hash table, replacing any existing association, like
+    /// `puthash`.
+    pub fn puthash<K: IntoLisp<'e>, V: IntoLisp<'e>>(self, key: K, value: V) -> Result<()> {
+        self.env.puthash(self, key, value)
+    }
+
+    /// Returns the value associated with `key` in this hash table, converted to a Rust value, or
+    /// `None` if there is none. Unlike `gethash`, this distinguishes a missing key from a key
+    /// associated with `nil`.
+    pub fn gethash<T: FromLisp<'e>, K: IntoLisp<'e>>(self, key: K) -> Result<Option<T>> {
+        let env = self.env;
+        // A fresh uninterned symbol cannot be in the table, so it is only returned if the key is
+        // missing.
+        let name = "missing".into_lisp(env)?;
+        let missing = call_lisp!(env, "make-symbol", name)?;
+        let value = env.gethash(self, key, missing)?;
+        if value.eq(missing) {
+            return Ok(None);
+        }
+        Ok(Some(value.into_rust()?))
+    }
+
+    /// Removes the association of `key` from this hash table, if there is one, like `remhash`.
+    pub fn remhash<K: IntoLisp<'e>>(self, key: K) -> Result<()> {
+        self.env.remhash(self, key)
+    }
+}
+
+/// Converts a `HashMap` into a fresh hash table that compares keys with `equal`.
+impl<'e, K: IntoLisp<'e>, V: IntoLisp<'e>> IntoLisp<'e> for HashMap<K, V> {
+    fn into_lisp(self, env: &'e Env) -> Result<Value<'_>> {
+        let table = env.make_hash_table(HashTableTest::Equal, self.len(), None)?;
+        for (key, value) in self {
+            env.puthash(table, key, value)?;
+        }
+        Ok(table)
+    }
+}
+
+/// Converts a hash table into a `HashMap`, converting each key and value. Signals
+/// `(wrong-type-argument hash-table-p VALUE)` if the value is not a hash table.
+///
+/// Distinct Lisp keys may convert to the same Rust key, e.g. two different strings with the same
+/// contents in an `eq` hash table, converted into `String`. In that case, the last entry converted
+/// wins, which depends on the iteration order of the hash table.
+impl<'e, K: FromLisp<'e> + Eq + Hash, V: FromLisp<'e>> FromLisp<'e> for HashMap<K, V> {
+    fn from_lisp(value: Value<'e>) -> Result<Self> {
+        let env = value.env;
+        if !env.is_not_nil(call_lisp!(env, "hash-table-p", value)?) {
+            return Err(env.wrong_type("hash-table-p", value));
+        }
+        // The entries are only valid during the calls to the closure, so they are kept alive
+        // with global references, to be converted afterwards.
+        let entries = Rc::new(RefCell::new(vec![]));
+        let collect = Closure::new(2..2, {
+            let entries = entries.clone();
+            move |env| {
+                let entry = (GlobalRef::new(env.get_arg(0)), GlobalRef::new(env.get_arg(1)));
+                entries.borrow_mut().push(entry);
+                env.nil()
+            }
+        });
+        let collect = collect.into_lisp(env)?;
+        call_lisp!(env, "maphash", collect, value)?;
+        let entries = entries.borrow();
+        let mut map = HashMap::with_capacity(entries.len());
+        for (key, item) in entries.iter() {
+            map.insert(key.bind(env).into_rust()?, item.bind(env).into_rust()?);
+        }
+        Ok(map)
+    }
+}
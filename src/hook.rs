@@ -0,0 +1,164 @@
+use super::error::Result;
+use super::{Closure, Env, IntoLisp, Value};
+
+impl Env {
+    /// Adds a Rust closure to `kill-emacs-hook`, to be called when Emacs is about to exit, e.g. to
+    /// flush caches or clean up temporary files. Panics inside the closure are caught and signaled
+    /// to Lisp as `rust-panic`.
+    ///
+    /// Returns the Lisp function that was added to the hook.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, Result};
+    /// fn init(env: &Env) -> Result<()> {
+    ///     env.add_kill_emacs_hook(|_| {
+    ///         // Flush the write-behind cache...
+    ///         Ok(())
+    ///     })?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn add_kill_emacs_hook<F>(&self, f: F) -> Result<Value<'_>>
+    where
+        F: Fn(&Env) -> Result<()> + 'static,
+    {
+        let function = Closure::new(0..0, move |env| {
+            f(env)?;
+            env.nil()
+        })
+        .into_lisp(self)?;
+        self.add_hook("kill-emacs-hook", function, false)?;
+        Ok(function)
+    }
+
+    /// Adds a Rust closure to `pre-command-hook`, to be called before each command is executed.
+    /// Panics inside the closure are caught and signaled to Lisp as `rust-panic`.
+    ///
+    /// Since the closure runs before every command, it should be cheap. Errors signaled by it cause
+    /// Emacs to remove it from the hook. Once it is no longer in the hook, e.g. after being removed,
+    /// or after its buffer is killed (for a buffer-local hook), it is dropped by the GC.
+    ///
+    /// Returns the Lisp function that was added to the hook, which can be passed to
+    /// [`remove_hook`].
+    ///
+    /// [`remove_hook`]: #method.remove_hook
+    pub fn add_pre_command_hook<F>(&self, f: F) -> Result<Value<'_>>
+    where
+        F: Fn(&Env) -> Result<()> + 'static,
+    {
+        self.add_command_hook("pre-command-hook", f, false)
+    }
+
+    /// Adds a Rust closure to `post-command-hook`, to be called after each command is executed.
+    /// See [`add_pre_command_hook`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, Result};
+    /// fn init(env: &Env) -> Result<()> {
+    ///     env.add_post_command_hook(|env| {
+    ///         // Move the highlight overlay to point...
+    ///         Ok(())
+    ///     })?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [`add_pre_command_hook`]: #method.add_pre_command_hook
+    pub fn add_post_command_hook<F>(&self, f: F) -> Result<Value<'_>>
+    where
+        F: Fn(&Env) -> Result<()> + 'static,
+    {
+        self.add_command_hook("post-command-hook", f, false)
+    }
+
+    /// Like [`add_pre_command_hook`], but only for the current buffer.
+    ///
+    /// [`add_pre_command_hook`]: #method.add_pre_command_hook
+    pub fn add_local_pre_command_hook<F>(&self, f: F) -> Result<Value<'_>>
+    where
+        F: Fn(&Env) -> Result<()> + 'static,
+    {
+        self.add_command_hook("pre-command-hook", f, true)
+    }
+
+    /// Like [`add_post_command_hook`], but only for the current buffer.
+    ///
+    /// [`add_post_command_hook`]: #method.add_post_command_hook
+    pub fn add_local_post_command_hook<F>(&self, f: F) -> Result<Value<'_>>
+    where
+        F: Fn(&Env) -> Result<()> + 'static,
+    {
+        self.add_command_hook("post-command-hook", f, true)
+    }
+
+    /// Adds a Rust closure to `completion-at-point-functions` in the current buffer, as a
+    /// completion-at-point function. Panics inside the closure are caught and signaled to Lisp as
+    /// `rust-panic`.
+    ///
+    /// The closure should return `nil` if it doesn't apply at point, or a list of the form
+    /// `(START END COLLECTION . PROPS)`. See [Completion in Buffers].
+    ///
+    /// Returns the Lisp function that was added to the hook, which can be passed to
+    /// [`remove_hook`]. The closure is dropped by the GC once it is no longer in the hook, e.g.
+    /// after its buffer is killed, so this can be called for each buffer, e.g. from a mode hook.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, IntoLisp, Result};
+    /// fn enable_completion(env: &Env) -> Result<()> {
+    ///     env.add_capf(|env| {
+    ///         let end = env.call("point", &[])?;
+    ///         let start = env.call("line-beginning-position", &[])?;
+    ///         let candidates = env.list(&["alpha".into_lisp(env)?, "beta".into_lisp(env)?])?;
+    ///         env.list(&[start, end, candidates])
+    ///     })?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [Completion in Buffers]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Completion-in-Buffers.html
+    /// [`remove_hook`]: #method.remove_hook
+    pub fn add_capf<F>(&self, f: F) -> Result<Value<'_>>
+    where
+        F: Fn(&Env) -> Result<Value<'_>> + 'static,
+    {
+        let function = Closure::new(0..0, move |env| f(env)).into_lisp(self)?;
+        self.add_hook("completion-at-point-functions", function, true)?;
+        Ok(function)
+    }
+
+    /// Removes `function` from the given hook, or from its buffer-local value in the current
+    /// buffer if `local` is true, through `remove-hook`.
+    pub fn remove_hook(&self, hook: &str, function: Value<'_>, local: bool) -> Result<()> {
+        let hook = self.intern(hook)?;
+        let local = local.into_lisp(self)?;
+        call_lisp!(self, "remove-hook", hook, function, local)?;
+        Ok(())
+    }
+
+    fn add_command_hook<F>(&self, hook: &str, f: F, local: bool) -> Result<Value<'_>>
+    where
+        F: Fn(&Env) -> Result<()> + 'static,
+    {
+        let function = Closure::new(0..0, move |env| {
+            f(env)?;
+            env.nil()
+        })
+        .into_lisp(self)?;
+        self.add_hook(hook, function, local)?;
+        Ok(function)
+    }
+
+    pub(crate) fn add_hook(&self, hook: &str, function: Value<'_>, local: bool) -> Result<()> {
+        let hook = self.intern(hook)?;
+        let append = ().into_lisp(self)?;
+        let local = local.into_lisp(self)?;
+        call_lisp!(self, "add-hook", hook, function, append, local)?;
+        Ok(())
+    }
+}
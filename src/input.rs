@@ -0,0 +1,45 @@
+use std::char;
+
+use super::error::Result;
+use super::{Env, IntoLisp};
+
+/// Reading user input from the keyboard. See [Reading Input].
+///
+/// [Reading Input]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Reading-Input.html
+impl Env {
+    /// Reads a character from the command input, displaying `prompt` in the echo area, through
+    /// `read-char`. Non-character input events (e.g. function keys, mouse clicks, characters with
+    /// modifiers that cannot be represented as a `char`) are reported as errors.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, Result};
+    /// fn confirm(env: &Env) -> Result<bool> {
+    ///     loop {
+    ///         match env.read_char("Proceed? (y or n) ")? {
+    ///             'y' => return Ok(true),
+    ///             'n' => return Ok(false),
+    ///             _ => continue,
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn read_char(&self, prompt: &str) -> Result<char> {
+        let prompt = prompt.into_lisp(self)?;
+        let event = call_lisp!(self, "read-char", prompt)?;
+        let code: i64 = event.into_rust()?;
+        match char::from_u32(code as u32) {
+            Some(c) if code >= 0 => Ok(c),
+            _ => Err(self.wrong_type("characterp", event)),
+        }
+    }
+
+    /// Reads a key sequence, displaying `prompt` in the echo area, through `read-key-sequence`.
+    /// Returns its description in the format used by `kbd`, e.g. `"C-x C-f"`.
+    pub fn read_key_sequence(&self, prompt: &str) -> Result<String> {
+        let prompt = prompt.into_lisp(self)?;
+        let keys = call_lisp!(self, "read-key-sequence", prompt)?;
+        call_lisp!(self, "key-description", keys)?.into_rust()
+    }
+}
@@ -0,0 +1,99 @@
+use super::error::Result;
+use super::func::Manage;
+use super::globals::__PREFIX__;
+use super::{Env, IntoLisp, Value};
+
+impl Env {
+    /// Creates a new sparse keymap, optionally with an overall prompt string.
+    pub fn make_sparse_keymap(&self, prompt: Option<&str>) -> Result<Value<'_>> {
+        let prompt = prompt.into_lisp(self)?;
+        call_lisp!(self, "make-sparse-keymap", prompt)
+    }
+
+    /// Binds `key` to `binding` in `keymap`. The key sequence is given in the format used by
+    /// `kbd`, e.g. `"C-c C-a"`. The binding can be a command, or another keymap, in which case `key`
+    /// becomes a prefix key.
+    pub fn define_key(
+        &self,
+        keymap: Value<'_>,
+        key: &str,
+        binding: Value<'_>,
+    ) -> Result<Value<'_>> {
+        let key = self.kbd(key)?;
+        call_lisp!(self, "define-key", keymap, key, binding)
+    }
+
+    /// Like [`define_key`], but also attaches a description to the binding, in the form of a simple
+    /// menu item `(DESC . BINDING)`. Key lookup ignores the description, while tools like
+    /// `which-key` display it instead of the command's name. This is especially useful for prefix
+    /// keymaps, which don't have a name of their own.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, Result, Value};
+    /// fn make_menu<'e>(env: &'e Env, run: Value<'_>) -> Result<Value<'e>> {
+    ///     let menu = env.make_sparse_keymap(None)?;
+    ///     let actions = env.make_sparse_keymap(None)?;
+    ///     env.define_key_with_desc(actions, "r", run, "run")?;
+    ///     env.define_key_with_desc(menu, "a", actions, "actions")?;
+    ///     Ok(menu)
+    /// }
+    /// ```
+    ///
+    /// [`define_key`]: #method.define_key
+    pub fn define_key_with_desc(
+        &self,
+        keymap: Value<'_>,
+        key: &str,
+        binding: Value<'_>,
+        desc: &str,
+    ) -> Result<Value<'_>> {
+        let desc = desc.into_lisp(self)?;
+        let item = call_lisp!(self, "cons", desc, binding)?;
+        self.define_key(keymap, key, item)
+    }
+
+    /// Defines a prefix command, i.e. a symbol whose function definition is a keymap, dispatching to
+    /// sub-commands. Each entry is a tuple `(KEY, COMMAND, DESC)`, bound as if by
+    /// [`define_key_with_desc`]. Returns the symbol, which can then be bound to a key.
+    ///
+    /// Like functions exported by [`#[defun]`], the symbol's name is prefixed with the module's
+    /// prefix. The symbol's value is also set to the keymap, like `define-prefix-command` does.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, Result, Value};
+    /// fn init_dispatch(env: &Env, build: Value<'_>, test: Value<'_>) -> Result<()> {
+    ///     let dispatch = env.define_dispatch("dispatch", &[("b", build, "build"), ("t", test, "test")])?;
+    ///     let global_map = env.call("current-global-map", &[])?;
+    ///     env.define_key(global_map, "C-c m", dispatch)?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [`define_key_with_desc`]: #method.define_key_with_desc
+    /// [`#[defun]`]: /emacs-macros/*/emacs_macros/attr.defun.html
+    pub fn define_dispatch(
+        &self,
+        name: &str,
+        entries: &[(&str, Value<'_>, &str)],
+    ) -> Result<Value<'_>> {
+        let keymap = self.make_sparse_keymap(None)?;
+        for &(key, command, desc) in entries {
+            self.define_key_with_desc(keymap, key, command, desc)?;
+        }
+        let prefix =
+            __PREFIX__.try_lock().expect("Failed to acquire read lock of module prefix").join("");
+        let name = format!("{}{}", prefix, name);
+        self.fset(&name, keymap)?;
+        self.set(&name, keymap)?;
+        self.intern(&name)
+    }
+
+    fn kbd(&self, key: &str) -> Result<Value<'_>> {
+        let key = key.into_lisp(self)?;
+        call_lisp!(self, "kbd", key)
+    }
+}
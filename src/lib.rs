@@ -28,20 +28,109 @@
 
 use std::cell::{RefCell, Ref, RefMut};
 use std::ffi::CString;
+use std::thread;
 
 #[doc(inline)]
-pub use emacs_macros::{defun, module};
+pub use emacs_macros::{defun, module, FromLisp, IntoLisp, LispRecord, LispType};
 use raw::*;
 
 #[doc(no_inline)]
 pub use failure::{Error, ResultExt};
 
 #[doc(inline)]
-pub use self::error::{ErrorKind, Result};
+pub use self::error::{DataSchema, ErrorKind, ErrorMapper, Result, StandardCondition};
+
+#[doc(inline)]
+pub use self::outcome::Outcome;
+
+#[doc(inline)]
+pub use self::progress::ProgressReporter;
+
+#[doc(inline)]
+pub use self::plist::{KeywordArgs, Plist};
+
+#[doc(inline)]
+pub use self::closure::Closure;
+
+#[doc(inline)]
+pub use self::value::EqualValue;
+
+#[doc(inline)]
+pub use self::gc::{GcEntry, GcStats};
+
+#[doc(inline)]
+pub use self::vector::{Vector, VectorIter, VectorMut};
+
+#[doc(inline)]
+pub use self::command::InteractiveKind;
+
+#[doc(inline)]
+pub use self::bytes::ByteVector;
+
+#[doc(inline)]
+pub use self::hash_table::{HashTableTest, HashTableWeakness};
+
+#[doc(inline)]
+pub use self::global::GlobalRef;
+
+#[doc(inline)]
+pub use self::deferred::DeferredQueue;
+
+#[doc(inline)]
+pub use self::buffer_local::BufferLocal;
+
+#[doc(inline)]
+pub use self::handle::Handle;
+
+#[doc(inline)]
+pub use self::matrix::Matrix;
+
+#[doc(inline)]
+pub use self::cons::Cons;
+
+#[doc(inline)]
+pub use self::buffer::Buffer;
 
 #[macro_use]
 mod macros;
 mod convert;
+mod variable;
+mod keymap;
+mod overlay;
+mod outcome;
+mod coding;
+mod read;
+mod progress;
+mod bytes;
+mod obarray;
+mod seq;
+mod hook;
+mod value;
+mod marker;
+mod plist;
+mod command;
+mod face;
+mod closure;
+mod file;
+mod gc;
+mod constant;
+mod input;
+mod symbol;
+mod buffer;
+mod vector;
+mod position;
+mod hash_table;
+mod global;
+mod mode;
+mod lisp_macro;
+mod deferred;
+mod buffer_local;
+mod handle;
+mod matrix;
+mod cons;
+mod time;
+mod timer;
+mod scope;
 
 #[doc(hidden)]
 pub mod error;
@@ -68,11 +157,18 @@ pub mod deps {
 }
 
 /// Main point of interaction with the Lisp runtime.
+///
+/// An `Env` must only be used on the thread it was created on. In debug builds, every call into
+/// the Lisp runtime asserts this, panicking with a clear message instead of crashing Emacs. The
+/// check is compiled out in release builds, so it costs nothing there.
 #[derive(Debug)]
 pub struct Env {
     pub(crate) raw: *mut emacs_env,
     /// Raw values "rooted" during the lifetime of this `Env`.
     pub(crate) protected: RefCell<Vec<emacs_value>>,
+    /// The thread this `Env` was created on.
+    #[cfg(debug_assertions)]
+    thread: thread::ThreadId,
 }
 
 /// Like [`Env`], but is available only in exported functions. This has additional methods to handle
@@ -159,7 +255,12 @@ impl Env {
     #[doc(hidden)]
     pub unsafe fn new(raw: *mut emacs_env) -> Self {
         let protected = RefCell::new(vec![]);
-        Self { raw, protected }
+        Self {
+            raw,
+            protected,
+            #[cfg(debug_assertions)]
+            thread: thread::current().id(),
+        }
     }
 
     #[doc(hidden)]
@@ -169,11 +270,19 @@ impl Env {
         Self::new(raw)
     }
 
-    #[doc(hidden)]
+    /// Returns the raw pointer to the underlying `emacs_env`, for calling module functions that are
+    /// not wrapped by this crate. After such calls, [`check_exit`] should be used to handle
+    /// possible non-local exits.
+    ///
+    /// [`check_exit`]: #method.check_exit
     pub fn raw(&self) -> *mut emacs_env {
         self.raw
     }
 
+    /// Returns the symbol with the given name, creating it if necessary, in the current obarray,
+    /// like `intern` in Lisp. See [`with_obarray`].
+    ///
+    /// [`with_obarray`]: #method.with_obarray
     pub fn intern(&self, name: &str) -> Result<Value<'_>> {
         raw_call_value!(self, intern, CString::new(name)?.as_ptr())
     }
@@ -186,12 +295,70 @@ impl Env {
     // TODO: Add a convenient macro?
     pub fn call(&self, name: &str, args: &[Value<'_>]) -> Result<Value<'_>> {
         let symbol = self.intern(name)?;
-        // XXX Hmm
+        self.funcall(symbol, args)
+    }
+
+    /// Calls the function object `func`, e.g. a lambda passed from Lisp, with the given arguments,
+    /// like `funcall`. Unlike [`call`], this doesn't require the function to be bound to a symbol.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{defun, Result, Value};
+    /// #[defun]
+    /// fn call_twice<'e>(f: Value<'e>, x: Value<'e>) -> Result<Value<'e>> {
+    ///     let env = f.env;
+    ///     let y = env.funcall(f, &[x])?;
+    ///     env.funcall(f, &[y])
+    /// }
+    /// ```
+    ///
+    /// [`call`]: #method.call
+    pub fn funcall(&self, func: Value<'_>, args: &[Value<'_>]) -> Result<Value<'_>> {
         let mut args: Vec<emacs_value> = args.iter().map(|v| v.raw).collect();
-        raw_call_value!(self, funcall, symbol.raw, args.len() as libc::ptrdiff_t, args.as_mut_ptr())
+        raw_call_value!(self, funcall, func.raw, args.len() as libc::ptrdiff_t, args.as_mut_ptr())
+    }
+
+    /// Calls the function object `func`, with the elements of the Lisp list `args` as arguments,
+    /// like `apply`.
+    pub fn apply(&self, func: Value<'_>, args: Value<'_>) -> Result<Value<'_>> {
+        call_lisp!(self, "apply", func, args)
+    }
+
+    /// Like [`call`], but signals `rust-timeout` if the call takes longer than `secs` seconds, by
+    /// wrapping it in `with-timeout`, whose caveats apply: the timeout can only interrupt Lisp
+    /// code that waits, e.g. in `sleep-for` or `accept-process-output`, not busy computation.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, Result, Value};
+    /// fn run_user_hook<'e>(env: &'e Env, hook: Value<'e>) -> Result<Value<'e>> {
+    ///     env.call_with_timeout("funcall", &[hook], 2.0)
+    /// }
+    /// ```
+    ///
+    /// [`call`]: #method.call
+    pub fn call_with_timeout(
+        &self,
+        name: &str,
+        args: &[Value<'_>],
+        secs: f64,
+    ) -> Result<Value<'_>> {
+        // (with-timeout (SECS (signal 'rust-timeout (list SECS))) (apply 'NAME 'ARGS))
+        let quote = self.core_symbol(symbol::CoreSymbol::Quote)?;
+        let secs = secs.into_lisp(self)?;
+        let condition = self.list(&[quote, self.intern(error::TIMEOUT)?])?;
+        let data = self.list(&[self.intern("list")?, secs])?;
+        let on_timeout = self.list(&[self.intern("signal")?, condition, data])?;
+        let function = self.list(&[quote, self.intern(name)?])?;
+        let args = self.list(&[quote, self.list(args)?])?;
+        let body = self.list(&[self.intern("apply")?, function, args])?;
+        let timeout = self.list(&[secs, on_timeout])?;
+        let form = self.list(&[self.intern("with-timeout")?, timeout, body])?;
+        self.call("eval", &[form, self.t()?])
     }
 
-    // TODO: Add a method to Value instead.
     pub fn is_not_nil(&self, value: Value<'_>) -> bool {
         raw_call_no_exit!(self, is_not_nil, value.raw)
     }
@@ -210,9 +377,30 @@ impl Env {
         call_lisp!(self, "provide", name)
     }
 
+    /// Displays `text` in the echo area, and logs it to the `*Messages*` buffer, like `message`.
+    /// The text is displayed as-is: `%` is not treated as a format directive. Returns the text, as
+    /// a Lisp string.
     pub fn message(&self, text: &str) -> Result<Value<'_>> {
+        let format = "%s".into_lisp(self)?;
         let text = text.into_lisp(self)?;
-        call_lisp!(self, "message", text)
+        call_lisp!(self, "message", format, text)
+    }
+
+    /// Formats `args` according to the format string `fmt`, like `format`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, IntoLisp, Result, Value};
+    /// fn describe<'e>(env: &'e Env, name: &str, value: Value<'e>) -> Result<Value<'e>> {
+    ///     env.format("%s = %S", &[name.into_lisp(env)?, value])
+    /// }
+    /// ```
+    pub fn format(&self, fmt: &str, args: &[Value<'_>]) -> Result<Value<'_>> {
+        let mut all = Vec::with_capacity(args.len() + 1);
+        all.push(fmt.into_lisp(self)?);
+        all.extend_from_slice(args);
+        self.call("format", &all)
     }
 }
 
@@ -224,6 +412,26 @@ impl Drop for Env {
         for raw in self.protected.borrow().iter() {
             raw_call_no_exit!(self, free_global_ref, *raw);
         }
+        self.free_dropped_global_refs();
+    }
+}
+
+/// Implementation details.
+impl Env {
+    /// Panics if this `Env` is being used on a thread other than the one it was created on. This
+    /// is a no-op in release builds.
+    #[inline(always)]
+    pub(crate) fn check_thread(&self) {
+        #[cfg(debug_assertions)]
+        {
+            let current = thread::current().id();
+            if current != self.thread {
+                panic!(
+                    "Env must not be shared across threads: created on {:?}, used on {:?}",
+                    self.thread, current
+                );
+            }
+        }
     }
 }
 
@@ -0,0 +1,52 @@
+use super::error::Result;
+use super::symbol::CoreSymbol;
+use super::{Closure, Env, IntoLisp, Value};
+
+/// Macros. See [Macros].
+///
+/// [Macros]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Macros.html
+impl Env {
+    /// Defines `name` as a Lisp macro, whose expansion is computed by `f`, like `defmacro`. `f` is
+    /// called with the macro's arguments, as unevaluated forms, and returns the expansion. Panics
+    /// inside `f` are caught and signaled to Lisp as `rust-panic`. Returns the macro's symbol.
+    ///
+    /// # Examples
+    ///
+    /// A macro that reverses its arguments, so that `(my-mod-reversed 1 2 -)` expands to
+    /// `(- 2 1)`:
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, Result};
+    /// fn init_macros(env: &Env) -> Result<()> {
+    ///     env.define_macro("my-mod-reversed", |env, forms| {
+    ///         let mut forms = forms.to_vec();
+    ///         forms.reverse();
+    ///         env.list(&forms)
+    ///     })?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn define_macro<F>(&self, name: &str, f: F) -> Result<Value<'_>>
+    where
+        F: for<'e> Fn(&'e Env, &[Value<'e>]) -> Result<Value<'e>> + 'static,
+    {
+        let expander = Closure::shared(1..1, move |env| {
+            let forms: Vec<Value<'_>> = env.parse_arg(0)?;
+            f(env, &forms)
+        })
+        .into_lisp(self)?;
+        // (macro lambda (&rest forms) (funcall 'EXPANDER forms))
+        let forms = self.intern("forms")?;
+        let expander = self.list(&[self.core_symbol(CoreSymbol::Quote)?, expander])?;
+        let body = self.list(&[self.intern("funcall")?, expander, forms])?;
+        let lambda = self.list(&[
+            self.core_symbol(CoreSymbol::Lambda)?,
+            self.list(&[self.intern("&rest")?, forms])?,
+            body,
+        ])?;
+        let macro_symbol = self.intern("macro")?;
+        let definition = call_lisp!(self, "cons", macro_symbol, lambda)?;
+        self.fset(name, definition)?;
+        self.intern(name)
+    }
+}
@@ -1,5 +1,6 @@
 macro_rules! raw_fn {
     ($env:ident, $name:ident) => { {
+        $env.check_thread();
         (*$env.raw).$name.expect(stringify!(Required module function does not exist: $name))
     }};
 }
@@ -187,6 +188,72 @@ macro_rules! export_functions {
     };
 }
 
+/// Exports a generic function to the Lisp runtime once for each of the given types, like what
+/// [`#[defun]`] does for a non-generic function. The Lisp name of each instance has the type's name
+/// appended, e.g. `my-fn-i64`, `my-fn-string`.
+///
+/// The function must take at most 8 parameters, and return `Result<T>`. The parameter and return
+/// types must be owned types that implement [`FromLisp`]/[`IntoLisp`] for any lifetime, e.g.
+/// `i64`, `f64`, `String`, `bool`.
+///
+/// This defines a hidden module with the same name as the function, so the function's name must
+/// not be used for another module.
+///
+/// # Examples
+///
+/// ```no_run
+/// use emacs::Result;
+///
+/// fn clamp<T: PartialOrd>(x: T, min: T, max: T) -> Result<T> {
+///     Ok(if x < min { min } else if x > max { max } else { x })
+/// }
+///
+/// // Exports `clamp-i64`, `clamp-f64`, and `clamp-string`.
+/// emacs::defun_for_types!(clamp, [i64, f64, String]);
+/// ```
+///
+/// [`#[defun]`]: /emacs-macros/*/emacs_macros/attr.defun.html
+/// [`FromLisp`]: trait.FromLisp.html
+/// [`IntoLisp`]: trait.IntoLisp.html
+#[macro_export]
+macro_rules! defun_for_types {
+    ($name:ident, [$($ty:ty),+ $(,)*]) => {
+        #[doc(hidden)]
+        #[allow(non_snake_case)]
+        mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            fn export(env: &$crate::Env) -> $crate::Result<()> {
+                let path = module_path!();
+                // Strip the segment of this hidden module.
+                let path = &path[..path.len() - stringify!($name).len() - 2];
+                let in_name = $crate::globals::__MOD_IN_NAME__
+                    .load(::std::sync::atomic::Ordering::Relaxed);
+                let prefix = $crate::globals::lisp_path(if in_name { path } else { "" });
+                let base = stringify!($name).replace("_", "-");
+                $(
+                    let name = format!(
+                        "{}{}-{}",
+                        prefix,
+                        base,
+                        $crate::func::lisp_type_name(stringify!($ty)),
+                    );
+                    $crate::func::export(env, &name, super::$name::<$ty>)?;
+                )+
+                Ok(())
+            }
+
+            #[$crate::deps::ctor::ctor]
+            fn register() {
+                let mut funcs = $crate::globals::__INIT_FNS__.lock()
+                    .expect("Failed to acquire a write lock on map of initializers");
+                funcs.insert(module_path!().to_owned(), ::std::boxed::Box::new(export));
+            }
+        }
+    };
+}
+
 #[doc(hidden)]
 #[macro_export]
 macro_rules! _emacs_format {
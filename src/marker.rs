@@ -0,0 +1,48 @@
+use super::error::Result;
+use super::{Env, IntoLisp, Value};
+
+impl Env {
+    /// Creates a new marker that points nowhere.
+    pub fn make_marker(&self) -> Result<Value<'_>> {
+        call_lisp!(self, "make-marker")
+    }
+
+    /// Makes `marker` point at `position` in the given buffer (the current buffer if `None`). If
+    /// `position` is `None`, the marker is made to point nowhere. Returns the marker.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, Result, Value};
+    /// fn mark_location(env: &Env, position: i64) -> Result<Value<'_>> {
+    ///     let marker = env.make_marker()?;
+    ///     env.set_marker(marker, Some(position), None)
+    /// }
+    /// ```
+    pub fn set_marker<'e>(
+        &'e self,
+        marker: Value<'_>,
+        position: Option<i64>,
+        buffer: Option<Value<'_>>,
+    ) -> Result<Value<'e>> {
+        let position = position.into_lisp(self)?;
+        let buffer = buffer.into_lisp(self)?;
+        call_lisp!(self, "set-marker", marker, position, buffer)
+    }
+}
+
+/// Methods for marker values.
+impl<'e> Value<'e> {
+    /// Returns the position this marker points at, or `None` if it points nowhere.
+    pub fn marker_position(self) -> Result<Option<i64>> {
+        let env = self.env;
+        call_lisp!(env, "marker-position", self)?.into_rust()
+    }
+
+    /// Returns the buffer this marker points into, or `None` if it points nowhere, or into a killed
+    /// buffer.
+    pub fn marker_buffer(self) -> Result<Option<Value<'e>>> {
+        let env = self.env;
+        call_lisp!(env, "marker-buffer", self)?.into_rust()
+    }
+}
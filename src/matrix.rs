@@ -0,0 +1,133 @@
+use std::convert::TryFrom;
+
+use super::error::{Error, Result};
+use super::{Env, FromLisp, IntoLisp, Value, Vector};
+
+/// A rectangular grid of values, e.g. a numerical matrix, represented in Lisp as a vector of row
+/// vectors, like `[[1 2] [3 4]]`.
+///
+/// Converting from anything other than a vector of vectors signals
+/// `(wrong-type-argument vectorp VALUE)`. In both directions, a row whose length differs from the
+/// first row's signals `(wrong-length-argument LENGTH ROW)`, where `LENGTH` is the expected length.
+///
+/// Ragged data can be read into a `Vec<Vec<T>>` instead, which does not check the lengths of the
+/// rows. Note that a `Vec<Vec<T>>` is converted into a list of lists, not a vector of vectors.
+///
+/// Fixed-size arrays of up to 8 by 8 elements, e.g. `[[f64; 3]; 3]`, are converted to and from
+/// vectors of row vectors as well. Converting from Lisp additionally checks the number of rows,
+/// and requires `T: Default`.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use emacs::{defun, Matrix, Result};
+/// #[defun]
+/// fn transpose(m: Matrix<f64>) -> Result<Matrix<f64>> {
+///     let (rows, cols) = m.dims();
+///     Ok(Matrix((0..cols).map(|j| (0..rows).map(|i| m.0[i][j]).collect()).collect()))
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Matrix<T>(pub Vec<Vec<T>>);
+
+impl<T> Matrix<T> {
+    /// Creates a matrix by copying the given rows.
+    pub fn from_rows<R: AsRef<[T]>>(rows: &[R]) -> Self
+    where
+        T: Clone,
+    {
+        Matrix(rows.iter().map(|row| row.as_ref().to_vec()).collect())
+    }
+
+    /// Returns the number of rows and the number of columns, i.e. the length of the first row.
+    pub fn dims(&self) -> (usize, usize) {
+        (self.0.len(), self.0.first().map_or(0, Vec::len))
+    }
+}
+
+impl<'e, T: IntoLisp<'e>> IntoLisp<'e> for Matrix<T> {
+    fn into_lisp(self, env: &'e Env) -> Result<Value<'e>> {
+        let (_, cols) = self.dims();
+        let matrix = env.make_vector(self.0.len(), ())?.value();
+        for (i, row) in self.0.into_iter().enumerate() {
+            let len = row.len();
+            let vector = env.make_vector(len, ())?;
+            for (j, item) in row.into_iter().enumerate() {
+                let item = item.into_lisp(env)?;
+                raw_call!(env, vec_set, vector.value().raw, j as isize, item.raw)?;
+            }
+            if len != cols {
+                return Err(wrong_length(vector, cols)?);
+            }
+            raw_call!(env, vec_set, matrix.raw, i as isize, vector.value().raw)?;
+        }
+        Ok(matrix)
+    }
+}
+
+impl<'e, T: FromLisp<'e>> FromLisp<'e> for Matrix<T> {
+    fn from_lisp(value: Value<'e>) -> Result<Self> {
+        let matrix = Vector::try_from(value)?;
+        let mut rows = Vec::with_capacity(matrix.len()?);
+        let mut cols = None;
+        for row in matrix.iter()? {
+            let row = Vector::try_from(row)?;
+            let len = row.len()?;
+            match cols {
+                Some(cols) if cols != len => return Err(wrong_length(row, cols)?),
+                Some(_) => (),
+                None => cols = Some(len),
+            }
+            rows.push(row.iter()?.map(|item| item.into_rust()).collect::<Result<Vec<T>>>()?);
+        }
+        Ok(Matrix(rows))
+    }
+}
+
+/// Returns the error signaling `(wrong-length-argument EXPECTED VECTOR)`.
+fn wrong_length(vector: Vector<'_>, expected: usize) -> Result<Error> {
+    let env = vector.value().env;
+    let expected = (expected as i64).into_lisp(env)?;
+    env.make_signal("wrong-length-argument", &[expected, vector.value()])
+}
+
+macro_rules! impl_array {
+    (@rows $rows:literal; [$($cols:literal)*]) => {$(
+        impl<'e, T: IntoLisp<'e>> IntoLisp<'e> for [[T; $cols]; $rows] {
+            fn into_lisp(self, env: &'e Env) -> Result<Value<'e>> {
+                // Boxed slices allow moving the items out of the arrays.
+                let rows: Box<[[T; $cols]]> = Box::new(self);
+                let rows = rows.into_vec().into_iter().map(|row| {
+                    let row: Box<[T]> = Box::new(row);
+                    row.into_vec()
+                });
+                Matrix(rows.collect()).into_lisp(env)
+            }
+        }
+
+        impl<'e, T: FromLisp<'e> + Default> FromLisp<'e> for [[T; $cols]; $rows] {
+            fn from_lisp(value: Value<'e>) -> Result<Self> {
+                let matrix = Vector::try_from(value)?;
+                if matrix.len()? != $rows {
+                    return Err(wrong_length(matrix, $rows)?);
+                }
+                let mut grid: Self = Default::default();
+                for (grid_row, row) in grid.iter_mut().zip(matrix.iter()?) {
+                    let row = Vector::try_from(row)?;
+                    if row.len()? != $cols {
+                        return Err(wrong_length(row, $cols)?);
+                    }
+                    for (grid_item, item) in grid_row.iter_mut().zip(row.iter()?) {
+                        *grid_item = item.into_rust()?;
+                    }
+                }
+                Ok(grid)
+            }
+        }
+    )*};
+    ($($rows:literal)*; $cols:tt) => {$(
+        impl_array!(@rows $rows; $cols);
+    )*};
+}
+
+impl_array!(1 2 3 4 5 6 7 8; [1 2 3 4 5 6 7 8]);
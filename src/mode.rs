@@ -0,0 +1,59 @@
+use super::error::Result;
+use super::symbol::CoreSymbol;
+use super::{Closure, Env, IntoLisp, Value};
+
+/// Major modes. See [Major Modes].
+///
+/// [Major Modes]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Major-Modes.html
+impl Env {
+    /// Defines a major mode named `name`, deriving from the mode `parent`, like
+    /// `define-derived-mode` in Lisp. The mode's body calls `setup`, after the parent's body has
+    /// run, and before the mode's hook runs. Panics inside `setup` are caught and signaled to Lisp
+    /// as `rust-panic`.
+    ///
+    /// The mode line name is `name` without the `-mode` suffix. Returns the mode's symbol.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, IntoLisp, Result};
+    /// fn init_mode(env: &Env) -> Result<()> {
+    ///     env.define_derived_mode("my-lang-mode", "prog-mode", "Major mode for MyLang.", |env| {
+    ///         env.call("set", &[env.intern("comment-start")?, "# ".into_lisp(env)?])?;
+    ///         Ok(())
+    ///     })?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn define_derived_mode<F>(
+        &self,
+        name: &str,
+        parent: &str,
+        doc: &str,
+        setup: F,
+    ) -> Result<Value<'_>>
+    where
+        F: Fn(&Env) -> Result<()> + 'static,
+    {
+        let setup = Closure::shared(0..0, move |env| {
+            setup(env)?;
+            env.nil()
+        })
+        .into_lisp(self)?;
+        let symbol = self.intern(name)?;
+        let parent = self.intern(parent)?;
+        let lighter = name.trim_end_matches("-mode").into_lisp(self)?;
+        let setup = self.list(&[self.core_symbol(CoreSymbol::Quote)?, setup])?;
+        let body = self.list(&[self.intern("funcall")?, setup])?;
+        let form = self.list(&[
+            self.intern("define-derived-mode")?,
+            symbol,
+            parent,
+            lighter,
+            doc.into_lisp(self)?,
+            body,
+        ])?;
+        call_lisp!(self, "eval", form)?;
+        Ok(symbol)
+    }
+}
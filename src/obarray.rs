@@ -0,0 +1,52 @@
+use super::error::Result;
+use super::{Env, IntoLisp, Value};
+
+const OBARRAY: &str = "obarray";
+
+impl Env {
+    /// Returns the current value of `obarray`, the obarray used by `read` and `intern` in Lisp.
+    pub fn obarray(&self) -> Result<Value<'_>> {
+        self.symbol_value(OBARRAY)
+    }
+
+    /// Returns the symbol with the given name in the given obarray, creating it if necessary.
+    pub fn intern_in(&self, name: &str, obarray: Value<'_>) -> Result<Value<'_>> {
+        let name = name.into_lisp(self)?;
+        call_lisp!(self, "intern", name, obarray)
+    }
+
+    /// Calls `f` with `obarray` bound to the given obarray, by a `let` form evaluated in Lisp, so
+    /// that symbols read or interned by Lisp code are confined to it. The binding is undone after
+    /// `f` returns, whether it succeeds, fails, or panics.
+    ///
+    /// Like `intern` in Lisp, [`intern`] uses the current obarray. While `f` is running, looking up
+    /// a symbol by name, e.g. through [`intern`] or [`call`], therefore finds or creates it in the
+    /// private obarray. Functions to be called inside `f` should be looked up beforehand, and called
+    /// with [`funcall`]. [`intern_in`] can be used to create symbols in a specific obarray.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, IntoLisp, Result, Value};
+    /// fn read_isolated<'e>(env: &'e Env, s: &str) -> Result<Value<'e>> {
+    ///     let obarray = env.call("make-vector", &[1511.into_lisp(env)?, 0.into_lisp(env)?])?;
+    ///     let read = env.intern("read")?;
+    ///     let s = s.into_lisp(env)?;
+    ///     env.with_obarray(obarray, || env.funcall(read, &[s]))
+    /// }
+    /// ```
+    ///
+    /// [`intern`]: #method.intern
+    /// [`call`]: #method.call
+    /// [`funcall`]: #method.funcall
+    /// [`intern_in`]: #method.intern_in
+    pub fn with_obarray<T, F>(&self, obarray: Value<'_>, f: F) -> Result<T>
+    where
+        F: FnOnce() -> Result<T>,
+    {
+        // Symbols used internally, e.g. `nil`, are cached on first use, which must not happen
+        // while the private obarray is current.
+        self.intern_core_symbols()?;
+        self.with_bindings(&[(OBARRAY, obarray)], f)
+    }
+}
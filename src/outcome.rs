@@ -0,0 +1,66 @@
+use std::result;
+
+use super::error::Result;
+use super::{Env, FromLisp, IntoLisp, Value};
+
+const OK: &str = ":ok";
+const ERROR: &str = ":error";
+const PREDICATE: &str = "outcomep";
+
+/// The outcome of an operation that can fail, represented in Lisp as a tagged cons instead of a
+/// signal: `(:ok . VALUE)` on success, `(:error . MESSAGE)` on failure. This allows Lisp code to
+/// pattern-match on the result (e.g. with `pcase`), without setting up `condition-case`.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use emacs::{defun, Outcome, Result};
+/// #[defun]
+/// fn parse(s: String) -> Result<Outcome<i64>> {
+///     Ok(s.parse().map_err(|e| format!("{}", e)).into())
+/// }
+/// ```
+///
+/// ```emacs-lisp
+/// (parse "12")  ; (:ok . 12)
+/// (parse "x")   ; (:error . "invalid digit found in string")
+/// ```
+///
+/// Converting a Lisp value that is not such a tagged cons signals
+/// `(wrong-type-argument outcomep VALUE)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Outcome<T>(pub result::Result<T, String>);
+
+impl<T> From<result::Result<T, String>> for Outcome<T> {
+    fn from(result: result::Result<T, String>) -> Self {
+        Outcome(result)
+    }
+}
+
+impl<'e, T: IntoLisp<'e>> IntoLisp<'e> for Outcome<T> {
+    fn into_lisp(self, env: &'e Env) -> Result<Value<'_>> {
+        let (tag, value) = match self.0 {
+            Ok(t) => (env.intern(OK)?, t.into_lisp(env)?),
+            Err(message) => (env.intern(ERROR)?, message.into_lisp(env)?),
+        };
+        call_lisp!(env, "cons", tag, value)
+    }
+}
+
+impl<'e, T: FromLisp<'e>> FromLisp<'e> for Outcome<T> {
+    fn from_lisp(value: Value<'e>) -> Result<Self> {
+        let env = value.env;
+        if !env.is_not_nil(call_lisp!(env, "consp", value)?) {
+            return Err(env.wrong_type(PREDICATE, value));
+        }
+        let tag = call_lisp!(env, "car", value)?;
+        let data = call_lisp!(env, "cdr", value)?;
+        if env.eq(tag, env.intern(OK)?) {
+            Ok(Outcome(Ok(data.into_rust()?)))
+        } else if env.eq(tag, env.intern(ERROR)?) {
+            Ok(Outcome(Err(data.into_rust()?)))
+        } else {
+            Err(env.wrong_type(PREDICATE, value))
+        }
+    }
+}
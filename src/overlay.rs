@@ -0,0 +1,54 @@
+use super::error::Result;
+use super::{Env, FromLisp, IntoLisp, Value};
+
+impl Env {
+    /// Creates an overlay spanning the positions from `start` to `end` in the given buffer, or in
+    /// the current buffer if `buffer` is `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, Result};
+    /// fn highlight(env: &Env, start: i64, end: i64) -> Result<()> {
+    ///     let overlay = env.make_overlay(start, end, None)?;
+    ///     overlay.overlay_put("face", env.intern("highlight")?)?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn make_overlay(
+        &self,
+        start: i64,
+        end: i64,
+        buffer: Option<Value<'_>>,
+    ) -> Result<Value<'_>> {
+        let start = start.into_lisp(self)?;
+        let end = end.into_lisp(self)?;
+        let buffer = buffer.into_lisp(self)?;
+        call_lisp!(self, "make-overlay", start, end, buffer)
+    }
+}
+
+/// Methods for overlay values.
+impl<'e> Value<'e> {
+    /// Sets the property `prop` of this overlay to `value`.
+    pub fn overlay_put<T: IntoLisp<'e>>(self, prop: &str, value: T) -> Result<Value<'e>> {
+        let env = self.env;
+        let prop = env.intern(prop)?;
+        let value = value.into_lisp(env)?;
+        call_lisp!(env, "overlay-put", self, prop, value)
+    }
+
+    /// Returns the property `prop` of this overlay, converted to a Rust value.
+    pub fn overlay_get<T: FromLisp<'e>>(self, prop: &str) -> Result<T> {
+        let env = self.env;
+        let prop = env.intern(prop)?;
+        call_lisp!(env, "overlay-get", self, prop)?.into_rust()
+    }
+
+    /// Deletes this overlay from its buffer. It can be re-attached later with `move-overlay`.
+    pub fn delete_overlay(self) -> Result<()> {
+        let env = self.env;
+        call_lisp!(env, "delete-overlay", self)?;
+        Ok(())
+    }
+}
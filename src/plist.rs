@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
+
+use super::error::Result;
+use super::{Env, FromLisp, IntoLisp, Value};
+
+/// A builder for Lisp [property lists] with keyword keys, such as the arguments of `make-process`.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use emacs::{Env, Plist, Result, Value};
+/// fn spawn<'e>(env: &'e Env, command: Value<'e>) -> Result<Value<'e>> {
+///     let args = Plist::new(env).put("name", "my-proc")?.put(":command", command)?.build()?;
+///     env.call("apply", &[env.intern("make-process")?, args])
+/// }
+/// ```
+///
+/// [property lists]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Property-Lists.html
+#[derive(Debug, Clone)]
+pub struct Plist<'e> {
+    env: &'e Env,
+    items: Vec<Value<'e>>,
+}
+
+impl<'e> Plist<'e> {
+    /// Creates an empty builder.
+    pub fn new(env: &'e Env) -> Self {
+        Plist { env, items: vec![] }
+    }
+
+    /// Appends a key-value pair. If `key` doesn't already start with `:`, it is prefixed with one.
+    pub fn put<T: IntoLisp<'e>>(mut self, key: &str, value: T) -> Result<Self> {
+        let key = if key.starts_with(':') {
+            self.env.intern(key)?
+        } else {
+            self.env.intern(&format!(":{}", key))?
+        };
+        let value = value.into_lisp(self.env)?;
+        self.items.push(key);
+        self.items.push(value);
+        Ok(self)
+    }
+
+    /// Builds the plist, with keys in the order they were added.
+    pub fn build(self) -> Result<Value<'e>> {
+        self.env.list(&self.items)
+    }
+}
+
+/// Keyword arguments parsed from a plist such as `(:a 1 :b 2)`, without a predefined schema. Keys
+/// are stored without the leading `:`, e.g. `"a"`.
+///
+/// Converting from a plist of odd length signals `(wrong-type-argument plistp VALUE)`, and a key
+/// that is not a keyword signals `(wrong-type-argument keywordp KEY)`. Converting back into Lisp
+/// produces a plist, whose keys are in arbitrary order.
+///
+/// # Examples
+///
+/// Forwarding options to a Lisp function:
+///
+/// ```no_run
+/// # use emacs::{defun, Env, IntoLisp, KeywordArgs, Result, Value};
+/// #[defun]
+/// fn start<'e>(env: &'e Env, mut options: KeywordArgs<'e>) -> Result<Value<'e>> {
+///     options.entry("name".to_owned()).or_insert("my-proc".into_lisp(env)?);
+///     env.call("apply", &[env.intern("make-process")?, options.into_lisp(env)?])
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct KeywordArgs<'e> {
+    args: HashMap<String, Value<'e>>,
+}
+
+impl<'e> KeywordArgs<'e> {
+    /// Returns the underlying map.
+    pub fn into_inner(self) -> HashMap<String, Value<'e>> {
+        self.args
+    }
+}
+
+impl<'e> Deref for KeywordArgs<'e> {
+    type Target = HashMap<String, Value<'e>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.args
+    }
+}
+
+impl<'e> DerefMut for KeywordArgs<'e> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.args
+    }
+}
+
+impl<'e> FromLisp<'e> for KeywordArgs<'e> {
+    fn from_lisp(value: Value<'e>) -> Result<Self> {
+        let env = value.env;
+        let mut args = HashMap::new();
+        let mut list = value;
+        let mut slow = value;
+        while env.is_not_nil(list) {
+            let key = env.call("car", &[list])?;
+            let rest = env.call("cdr", &[list])?;
+            if !env.is_not_nil(rest) {
+                return Err(env.wrong_type("plistp", value));
+            }
+            if !env.is_not_nil(env.call("keywordp", &[key])?) {
+                return Err(env.wrong_type("keywordp", key));
+            }
+            let name: String = env.call("symbol-name", &[key])?.into_rust()?;
+            args.insert(name[1..].to_owned(), env.call("car", &[rest])?);
+            list = env.call("cdr", &[rest])?;
+            slow = env.call("cdr", &[slow])?;
+            if env.eq(slow, list) {
+                return Err(env.circular_list(value));
+            }
+        }
+        Ok(KeywordArgs { args })
+    }
+}
+
+impl<'e> IntoLisp<'e> for KeywordArgs<'e> {
+    fn into_lisp(self, env: &'e Env) -> Result<Value<'e>> {
+        let mut plist = Plist::new(env);
+        for (key, value) in self.args {
+            plist = plist.put(&key, value)?;
+        }
+        plist.build()
+    }
+}
@@ -0,0 +1,41 @@
+use super::error::Result;
+use super::{Env, IntoLisp};
+
+/// Accessors for the line and column of positions in the current buffer. See [Text Lines] and
+/// [Columns].
+///
+/// [Text Lines]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Text-Lines.html
+/// [Columns]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Columns.html
+impl Env {
+    /// Returns the horizontal position of point, counting from 0 at the beginning of the line.
+    /// This takes into account the width of tabs and wide characters, like `current-column`.
+    pub fn current_column(&self) -> Result<i64> {
+        call_lisp!(self, "current-column")?.into_rust()
+    }
+
+    /// Returns the line number at `pos`, or at point if `pos` is `None`, counting from 1 at the
+    /// beginning of the accessible portion of the buffer, like `line-number-at-pos`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, Result};
+    /// fn status_line(env: &Env) -> Result<String> {
+    ///     let line = env.line_number_at_pos(None)?;
+    ///     let column = env.current_column()?;
+    ///     Ok(format!("L{}:C{}", line, column))
+    /// }
+    /// ```
+    pub fn line_number_at_pos(&self, pos: Option<i64>) -> Result<i64> {
+        let pos = pos.into_lisp(self)?;
+        call_lisp!(self, "line-number-at-pos", pos)?.into_rust()
+    }
+
+    /// Returns the number of lines between the positions `start` and `end`, like `count-lines`. A
+    /// partial line at the end counts as a line.
+    pub fn count_lines(&self, start: i64, end: i64) -> Result<i64> {
+        let start = start.into_lisp(self)?;
+        let end = end.into_lisp(self)?;
+        call_lisp!(self, "count-lines", start, end)?.into_rust()
+    }
+}
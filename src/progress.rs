@@ -0,0 +1,67 @@
+use super::error::Result;
+use super::{Env, IntoLisp, Value};
+
+/// A Lisp [progress reporter], which displays the progress of a long-running operation in the echo
+/// area.
+///
+/// [progress reporter]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Progress.html
+#[derive(Debug, Clone, Copy)]
+pub struct ProgressReporter<'e> {
+    reporter: Value<'e>,
+}
+
+impl Env {
+    /// Creates a progress reporter that displays `message`, followed by the percentage of progress
+    /// between `min` and `max`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, Result};
+    /// fn index_files(env: &Env, files: &[String]) -> Result<()> {
+    ///     let reporter = env.make_progress_reporter("Indexing...", 0, files.len() as i64)?;
+    ///     for (i, _file) in files.iter().enumerate() {
+    ///         // ...
+    ///         reporter.update(i as i64 + 1)?;
+    ///     }
+    ///     reporter.done()
+    /// }
+    /// ```
+    pub fn make_progress_reporter(
+        &self,
+        message: &str,
+        min: i64,
+        max: i64,
+    ) -> Result<ProgressReporter<'_>> {
+        let message = message.into_lisp(self)?;
+        let min = min.into_lisp(self)?;
+        let max = max.into_lisp(self)?;
+        let reporter = call_lisp!(self, "make-progress-reporter", message, min, max)?;
+        Ok(ProgressReporter { reporter })
+    }
+}
+
+impl<'e> ProgressReporter<'e> {
+    /// Reports that the operation has reached `value`. The echo area is only updated if enough
+    /// progress or time has passed since the last update, so this is cheap to call in a loop.
+    pub fn update(&self, value: i64) -> Result<()> {
+        let ProgressReporter { reporter } = *self;
+        let env = reporter.env;
+        let value = value.into_lisp(env)?;
+        call_lisp!(env, "progress-reporter-update", reporter, value)?;
+        Ok(())
+    }
+
+    /// Reports that the operation has finished, by appending "done" to the message.
+    pub fn done(self) -> Result<()> {
+        let ProgressReporter { reporter } = self;
+        let env = reporter.env;
+        call_lisp!(env, "progress-reporter-done", reporter)?;
+        Ok(())
+    }
+
+    /// Returns the underlying Lisp progress reporter.
+    pub fn value(&self) -> Value<'e> {
+        self.reporter
+    }
+}
@@ -0,0 +1,68 @@
+use super::error::{ErrorKind, Result};
+use super::{Env, IntoLisp, Value};
+
+const INVALID_READ_SYNTAX: &str = "invalid-read-syntax";
+
+impl Env {
+    /// Parses the given string as a single Lisp form, without evaluating it. Whitespace may
+    /// surround the form, but anything else following it signals `invalid-read-syntax`. Incomplete
+    /// input signals `end-of-file`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, Result};
+    /// fn parse_entry(env: &Env) -> Result<i64> {
+    ///     let entry = env.read("(width . 80)")?;
+    ///     env.call("cdr", &[entry])?.into_rust()
+    /// }
+    /// ```
+    pub fn read(&self, s: &str) -> Result<Value<'_>> {
+        let (form, end) = self.read_partial(s)?;
+        if s[end..].trim().is_empty() {
+            Ok(form)
+        } else {
+            let message = "Trailing garbage following expression".into_lisp(self)?;
+            let rest = s[end..].into_lisp(self)?;
+            Err(self.make_signal(INVALID_READ_SYNTAX, &[message, rest])?)
+        }
+    }
+
+    /// Parses the first Lisp form in the given string, ignoring what follows. Returns the form,
+    /// together with the byte offset in `s` where reading stopped.
+    pub fn read_partial(&self, s: &str) -> Result<(Value<'_>, usize)> {
+        let string = s.into_lisp(self)?;
+        let result = call_lisp!(self, "read-from-string", string)?;
+        let form = call_lisp!(self, "car", result)?;
+        let end: i64 = call_lisp!(self, "cdr", result)?.into_rust()?;
+        // `read-from-string` returns a character index.
+        let end = s.char_indices().nth(end as usize).map_or(s.len(), |(i, _)| i);
+        Ok((form, end))
+    }
+
+    /// Parses the given string as a single Lisp form, like [`read`], and evaluates it, with
+    /// lexical binding. Errors signaled while reading are returned as [`ErrorKind::Read`], to be
+    /// distinguished from errors signaled during evaluation.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, Result};
+    /// fn setup(env: &Env) -> Result<()> {
+    ///     env.eval("(require 'subr-x)")?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [`read`]: #method.read
+    /// [`ErrorKind::Read`]: enum.ErrorKind.html#variant.Read
+    pub fn eval(&self, source: &str) -> Result<Value<'_>> {
+        let form = self.read(source).map_err(|error| match error.downcast::<ErrorKind>() {
+            Ok(ErrorKind::Signal { symbol, data }) => ErrorKind::Read { symbol, data }.into(),
+            Ok(kind) => kind.into(),
+            Err(error) => error,
+        })?;
+        let lexical = self.t()?;
+        call_lisp!(self, "eval", form, lexical)
+    }
+}
@@ -0,0 +1,52 @@
+use std::panic;
+
+use super::error::Result;
+use super::symbol::CoreSymbol;
+use super::{Env, Value};
+
+impl Env {
+    /// Calls `f` from inside a Lisp form, which sets up a dynamic context for it, e.g. a `let`
+    /// binding, or a current buffer. `wrap` receives a form that calls `f`, and returns the form to
+    /// evaluate, e.g. `(let ((VAR VALUE)) FORM)`.
+    ///
+    /// Since the context is undone by Lisp itself, it is undone on every path: when `f` returns,
+    /// when it fails, and when it panics. A panic is caught before it reaches Lisp, and resumed
+    /// after the form has been evaluated. An error returned by `f` takes precedence over one in
+    /// undoing the context, e.g. killing a buffer.
+    pub(crate) fn call_within<'e, T, W, F>(&'e self, wrap: W, f: F) -> Result<T>
+    where
+        W: FnOnce(Value<'e>) -> Result<Value<'e>>,
+        F: FnOnce() -> Result<T>,
+    {
+        let mut f = Some(f);
+        let mut outcome = None;
+        let evaluated = self.with_borrowed_function(
+            0..0,
+            |env| {
+                match f.take() {
+                    Some(f) => outcome = Some(panic::catch_unwind(panic::AssertUnwindSafe(f))),
+                    None => return Err(env.error("The Rust closure was already called")),
+                }
+                env.nil()
+            },
+            |function| {
+                // (funcall (quote FUNCTION))
+                let quoted = self.list(&[self.core_symbol(CoreSymbol::Quote)?, function])?;
+                let form = wrap(self.list(&[self.intern("funcall")?, quoted])?)?;
+                call_lisp!(self, "eval", form)
+            },
+        );
+        match outcome {
+            Some(Ok(result)) => {
+                let result = result?;
+                evaluated?;
+                Ok(result)
+            }
+            Some(Err(payload)) => panic::resume_unwind(payload),
+            None => {
+                evaluated?;
+                Err(self.error("The form did not call the Rust closure"))
+            }
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use std::cmp::Ordering;
+
+use super::error::{ErrorKind, Result};
+use super::{Env, FromLisp, IntoLisp, Value};
+
+impl Env {
+    /// Converts the items of `iter` into a proper list, returning an [`ErrorKind::TooManyItems`]
+    /// error as soon as there are more than `max` of them. This guards against unbounded (or
+    /// infinite) iterators, e.g. ones derived from user input.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{defun, Env, Result, Value};
+    /// #[defun]
+    /// fn words<'e>(env: &'e Env, text: String) -> Result<Value<'e>> {
+    ///     env.list_from_bounded(text.split_whitespace(), 1000)
+    /// }
+    /// ```
+    ///
+    /// [`ErrorKind::TooManyItems`]: enum.ErrorKind.html#variant.TooManyItems
+    pub fn list_from_bounded<'e, I, T>(&'e self, iter: I, max: usize) -> Result<Value<'e>>
+    where
+        I: IntoIterator<Item = T>,
+        T: IntoLisp<'e>,
+    {
+        let mut items = vec![];
+        for item in iter {
+            if items.len() == max {
+                return Err(ErrorKind::TooManyItems { max }.into());
+            }
+            items.push(item.into_lisp(self)?);
+        }
+        self.list(&items)
+    }
+
+    /// Sorts `seq`, a list or a vector, with the Lisp function `sort`, using the Rust comparator
+    /// `cmp`. This avoids converting the elements into Rust just to sort them. Like `sort`, this is
+    /// stable, and destructive: a list is rearranged in place, so the returned value should be used
+    /// instead of `seq`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{defun, Env, Result, Value};
+    /// /// Sort the list of strings WORDS by length, then alphabetically.
+    /// #[defun]
+    /// fn sort_words<'e>(env: &'e Env, words: Value<'e>) -> Result<Value<'e>> {
+    ///     env.sort(words, |_, a, b| {
+    ///         let (a, b): (String, String) = (a.into_rust()?, b.into_rust()?);
+    ///         Ok(a.len().cmp(&b.len()).then(a.cmp(&b)))
+    ///     })
+    /// }
+    /// ```
+    pub fn sort<'e, F>(&'e self, seq: Value<'e>, cmp: F) -> Result<Value<'e>>
+    where
+        F: Fn(&Env, Value<'_>, Value<'_>) -> Result<Ordering>,
+    {
+        self.with_borrowed_function(
+            2..2,
+            |env| {
+                let less = cmp(env, env.get_arg(0), env.get_arg(1))? == Ordering::Less;
+                less.into_lisp(env)
+            },
+            |predicate| call_lisp!(self, "sort", seq, predicate),
+        )
+    }
+}
+
+/// Methods for sequence values (lists, vectors, strings...).
+impl<'e> Value<'e> {
+    /// Returns the sub-sequence of this sequence from index `start` up to (excluding) `end`, or up
+    /// to the end of the sequence if `end` is `None`. Negative indices count from the end, as in
+    /// `seq-subseq`. The result is a new sequence of the same type.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{Result, Value};
+    /// fn page(results: Value<'_>, n: i64, size: i64) -> Result<Value<'_>> {
+    ///     results.subseq(n * size, Some((n + 1) * size))
+    /// }
+    /// ```
+    pub fn subseq(self, start: i64, end: Option<i64>) -> Result<Value<'e>> {
+        let env = self.env;
+        let seq = env.intern("seq")?;
+        call_lisp!(env, "require", seq)?;
+        let start = start.into_lisp(env)?;
+        let end = end.into_lisp(env)?;
+        call_lisp!(env, "seq-subseq", self, start, end)
+    }
+
+    /// Returns the element at index `i` of this sequence, converted to a Rust value. Signals
+    /// `args-out-of-range` if `i` is out of range (for lists, `nil` is returned instead, as with
+    /// `elt`).
+    pub fn nth<T: FromLisp<'e>>(self, i: i64) -> Result<T> {
+        let env = self.env;
+        let i = i.into_lisp(env)?;
+        call_lisp!(env, "elt", self, i)?.into_rust()
+    }
+
+    /// Returns the part of this string from character index `start` up to (excluding) `end`, or
+    /// up to the end of the string if `end` is `None`, like `substring`. Unlike slicing a Rust
+    /// `str`, the indices count characters, not bytes. Negative indices count from the end.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{Result, Value};
+    /// fn initial(name: Value<'_>) -> Result<String> {
+    ///     name.substring_chars(0, Some(1))
+    /// }
+    /// ```
+    pub fn substring_chars(self, start: i64, end: Option<i64>) -> Result<String> {
+        let env = self.env;
+        let start = start.into_lisp(env)?;
+        let end = end.into_lisp(env)?;
+        call_lisp!(env, "substring", self, start, end)?.into_rust()
+    }
+}
@@ -0,0 +1,119 @@
+use std::cell::Cell;
+
+use emacs_module::emacs_value;
+
+use super::error::{self, Result};
+use super::{Env, FromLisp, IntoLisp, Value};
+
+/// Symbols used frequently by this crate, which are interned only once per thread.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum CoreSymbol {
+    Nil,
+    T,
+    Quote,
+    Lambda,
+    Error,
+    RustError,
+    RustPanic,
+    RustWrongTypeUserPtr,
+    RustWrongTypeArgument,
+}
+
+const CORE_SYMBOL_COUNT: usize = 9;
+
+impl CoreSymbol {
+    const ALL: [CoreSymbol; CORE_SYMBOL_COUNT] = [
+        CoreSymbol::Nil,
+        CoreSymbol::T,
+        CoreSymbol::Quote,
+        CoreSymbol::Lambda,
+        CoreSymbol::Error,
+        CoreSymbol::RustError,
+        CoreSymbol::RustPanic,
+        CoreSymbol::RustWrongTypeUserPtr,
+        CoreSymbol::RustWrongTypeArgument,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            CoreSymbol::Nil => "nil",
+            CoreSymbol::T => "t",
+            CoreSymbol::Quote => "quote",
+            CoreSymbol::Lambda => "lambda",
+            CoreSymbol::Error => "error",
+            CoreSymbol::RustError => error::ERROR,
+            CoreSymbol::RustPanic => error::PANIC,
+            CoreSymbol::RustWrongTypeUserPtr => error::WRONG_TYPE_USER_PTR,
+            CoreSymbol::RustWrongTypeArgument => error::RUST_WRONG_TYPE_ARGUMENT,
+        }
+    }
+}
+
+thread_local! {
+    /// Global references to core symbols, indexed by `CoreSymbol`, created on first use by
+    /// `Env::core_symbol`. They are never freed.
+    static CORE_SYMBOLS: [Cell<Option<emacs_value>>; CORE_SYMBOL_COUNT] = Default::default();
+}
+
+/// Accessors for [symbol properties].
+///
+/// [symbol properties]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Symbol-Properties.html
+impl Env {
+    /// Sets the property `prop` of the symbol named `symbol` to `value`, like `put` in Lisp.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, Result};
+    /// fn tag_capabilities(env: &Env) -> Result<()> {
+    ///     env.put("my-mod-search", "my-mod-async", true)?;
+    ///     env.put("my-mod-search", "my-mod-version", 2)
+    /// }
+    /// ```
+    pub fn put<'e, T: IntoLisp<'e>>(&'e self, symbol: &str, prop: &str, value: T) -> Result<()> {
+        let symbol = self.intern(symbol)?;
+        let prop = self.intern(prop)?;
+        let value = value.into_lisp(self)?;
+        call_lisp!(self, "put", symbol, prop, value)?;
+        Ok(())
+    }
+
+    /// Returns the property `prop` of the symbol named `symbol`, like `get` in Lisp, or `None` if it
+    /// is `nil` (which is also the case if the property was never set).
+    pub fn get<'e, T: FromLisp<'e>>(&'e self, symbol: &str, prop: &str) -> Result<Option<T>> {
+        let symbol = self.intern(symbol)?;
+        let prop = self.intern(prop)?;
+        call_lisp!(self, "get", symbol, prop)?.into_rust()
+    }
+
+    /// Returns `nil`. It is interned only on the first call, and cached afterwards.
+    pub fn nil(&self) -> Result<Value<'_>> {
+        self.core_symbol(CoreSymbol::Nil)
+    }
+
+    /// Returns `t`. It is interned only on the first call, and cached afterwards.
+    pub fn t(&self) -> Result<Value<'_>> {
+        self.core_symbol(CoreSymbol::T)
+    }
+
+    /// Interns all core symbols that have not been interned yet. This must be done before the
+    /// current obarray is replaced, so that they are not interned in the wrong obarray.
+    pub(crate) fn intern_core_symbols(&self) -> Result<()> {
+        for &symbol in CoreSymbol::ALL.iter() {
+            self.core_symbol(symbol)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the given core symbol, interning it only on the first call.
+    pub(crate) fn core_symbol(&self, symbol: CoreSymbol) -> Result<Value<'_>> {
+        let index = symbol as usize;
+        if let Some(raw) = CORE_SYMBOLS.with(|symbols| symbols[index].get()) {
+            return Ok(Value { raw, env: self });
+        }
+        let value = self.intern(symbol.name())?;
+        let raw = raw_call_no_exit!(self, make_global_ref, value.raw);
+        CORE_SYMBOLS.with(|symbols| symbols[index].set(Some(raw)));
+        Ok(Value { raw, env: self })
+    }
+}
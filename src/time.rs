@@ -0,0 +1,141 @@
+use std::convert::TryFrom;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::error::{Error, Result};
+use super::{Env, FromLisp, IntoLisp, Value};
+
+const NANOS_PER_SEC: i128 = 1_000_000_000;
+
+/// Converts a `SystemTime` into a Lisp time value `(HIGH LOW USEC PSEC)`, like the ones returned by
+/// `current-time`, counting from the epoch. Times before the epoch are represented with a negative
+/// `HIGH`, and non-negative sub-second fields, as in Lisp.
+///
+/// Since `PSEC` holds the nanoseconds (multiplied by 1000), the conversion is lossless, on all
+/// Emacs versions. Converting back into a `SystemTime` yields the same value.
+impl IntoLisp<'_> for SystemTime {
+    fn into_lisp(self, env: &Env) -> Result<Value<'_>> {
+        let nanos = match self.duration_since(UNIX_EPOCH) {
+            Ok(after) => duration_nanos(after),
+            Err(before) => -duration_nanos(before.duration()),
+        };
+        make_time(env, nanos)
+    }
+}
+
+/// Converts a Lisp time value into a `SystemTime`, counting from the epoch. Accepted values are
+/// numbers of seconds, lists `(HIGH LOW USEC PSEC)` where the last 2 (or 3) elements can be
+/// omitted, and, since Emacs 27, the other forms accepted by `time-convert`, e.g. `(TICKS . HZ)`.
+/// Picoseconds are truncated to nanoseconds, so integer forms keep nanosecond precision. Floats
+/// only have about microsecond precision for current times. Anything else signals
+/// `(error "Invalid time specification")`.
+///
+/// Unlike Lisp time functions, `nil` is not accepted as the current time.
+impl FromLisp<'_> for SystemTime {
+    fn from_lisp(value: Value<'_>) -> Result<Self> {
+        let nanos = time_nanos(value)?;
+        let duration = nanos_duration(nanos.abs());
+        if nanos >= 0 {
+            Ok(UNIX_EPOCH + duration)
+        } else {
+            Ok(UNIX_EPOCH - duration)
+        }
+    }
+}
+
+/// Converts a `Duration` into a Lisp time value `(HIGH LOW USEC PSEC)`, which Lisp time functions,
+/// e.g. `time-add`, treat as a relative time.
+impl IntoLisp<'_> for Duration {
+    fn into_lisp(self, env: &Env) -> Result<Value<'_>> {
+        make_time(env, duration_nanos(self))
+    }
+}
+
+/// Converts a Lisp time value into a `Duration`, like the conversion for `SystemTime`. A negative
+/// time signals `(error "Invalid time specification")`.
+impl FromLisp<'_> for Duration {
+    fn from_lisp(value: Value<'_>) -> Result<Self> {
+        let nanos = time_nanos(value)?;
+        if nanos < 0 {
+            return Err(invalid_time(value.env));
+        }
+        Ok(nanos_duration(nanos))
+    }
+}
+
+fn duration_nanos(duration: Duration) -> i128 {
+    i128::from(duration.as_secs()) * NANOS_PER_SEC + i128::from(duration.subsec_nanos())
+}
+
+fn nanos_duration(nanos: i128) -> Duration {
+    Duration::new((nanos / NANOS_PER_SEC) as u64, (nanos % NANOS_PER_SEC) as u32)
+}
+
+fn make_time(env: &Env, nanos: i128) -> Result<Value<'_>> {
+    // Round towards negative infinity, so that the sub-second part is non-negative.
+    let mut secs = nanos / NANOS_PER_SEC;
+    let mut sub = nanos % NANOS_PER_SEC;
+    if sub < 0 {
+        secs -= 1;
+        sub += NANOS_PER_SEC;
+    }
+    let secs = match i64::try_from(secs) {
+        Ok(secs) => secs,
+        Err(_) => {
+            let data = secs.to_string().into_lisp(env)?;
+            return Err(env.make_signal("overflow-error", &[data])?);
+        }
+    };
+    let usec = (sub / 1000) as i64;
+    let psec = (sub % 1000) as i64 * 1000;
+    env.list(&[
+        (secs >> 16).into_lisp(env)?,
+        (secs & 0xffff).into_lisp(env)?,
+        usec.into_lisp(env)?,
+        psec.into_lisp(env)?,
+    ])
+}
+
+/// Returns the number of nanoseconds since the epoch represented by a Lisp time value.
+fn time_nanos(value: Value<'_>) -> Result<i128> {
+    let env = value.env;
+    if env.is_not_nil(call_lisp!(env, "integerp", value)?) {
+        let secs: i64 = value.into_rust()?;
+        return Ok(i128::from(secs) * NANOS_PER_SEC);
+    }
+    if env.is_not_nil(call_lisp!(env, "floatp", value)?) {
+        let secs: f64 = value.into_rust()?;
+        if !secs.is_finite() {
+            return Err(invalid_time(env));
+        }
+        return Ok((secs * 1e9).round() as i128);
+    }
+    if !env.is_not_nil(call_lisp!(env, "consp", value)?) {
+        return Err(invalid_time(env));
+    }
+    // Let Emacs normalize the newer forms, e.g. `(TICKS . HZ)`, when it can.
+    let time_convert = env.intern("time-convert")?;
+    let value = if env.is_not_nil(call_lisp!(env, "fboundp", time_convert)?) {
+        call_lisp!(env, "time-convert", value, env.intern("list")?)?
+    } else {
+        value
+    };
+    let tail = call_lisp!(env, "cdr", value)?;
+    let parts: Vec<i64> = if env.is_not_nil(call_lisp!(env, "integerp", tail)?) {
+        // The obsolete `(HIGH . LOW)` form.
+        vec![call_lisp!(env, "car", value)?.into_rust()?, tail.into_rust()?]
+    } else {
+        value.into_rust()?
+    };
+    let (high, low, usec, psec) = match parts.as_slice() {
+        [high, low] => (*high, *low, 0, 0),
+        [high, low, usec] => (*high, *low, *usec, 0),
+        [high, low, usec, psec] => (*high, *low, *usec, *psec),
+        _ => return Err(invalid_time(env)),
+    };
+    let secs = (i128::from(high) << 16) + i128::from(low);
+    Ok(secs * NANOS_PER_SEC + i128::from(usec) * 1000 + i128::from(psec) / 1000)
+}
+
+fn invalid_time(env: &Env) -> Error {
+    env.error("Invalid time specification")
+}
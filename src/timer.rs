@@ -0,0 +1,44 @@
+use super::error::Result;
+use super::{Closure, Env, IntoLisp, Value};
+
+/// Timers. See [Timers].
+///
+/// [Timers]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Timers.html
+impl Env {
+    /// Sets up a timer that calls `f` the next time Emacs has been idle for `secs` seconds, like
+    /// `run-with-idle-timer`. If `repeat` is `true`, `f` is called again each time Emacs becomes
+    /// idle for that long.
+    ///
+    /// Returns the timer, which can be cancelled with `cancel-timer`. The closure is owned by the
+    /// timer, and is dropped when the timer is garbage-collected, after being cancelled (or after
+    /// running, if it doesn't repeat). Panics inside the closure are caught and signaled to Lisp as
+    /// `rust-panic`, which the timer reports like any other error.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{defun, Env, Result, Value};
+    /// #[defun]
+    /// fn start_indexing(env: &Env) -> Result<Value<'_>> {
+    ///     let mut indexed = 0;
+    ///     env.run_with_idle_timer(0.5, true, move |env| {
+    ///         indexed += 1;
+    ///         env.message(&format!("Indexed {} times", indexed))?;
+    ///         Ok(())
+    ///     })
+    /// }
+    /// ```
+    pub fn run_with_idle_timer<F>(&self, secs: f64, repeat: bool, mut f: F) -> Result<Value<'_>>
+    where
+        F: FnMut(&Env) -> Result<()> + 'static,
+    {
+        let function = Closure::new(0..0, move |env| {
+            f(env)?;
+            env.nil()
+        })
+        .into_lisp(self)?;
+        let secs = secs.into_lisp(self)?;
+        let repeat = repeat.into_lisp(self)?;
+        call_lisp!(self, "run-with-idle-timer", secs, repeat, function)
+    }
+}
@@ -0,0 +1,170 @@
+use std::cell::{Cell, RefCell};
+use std::hash::{Hash, Hasher};
+
+use super::error::Result;
+use super::{Env, GlobalRef, HashTableTest, HashTableWeakness, Value};
+
+/// Type predicates.
+impl<'e> Value<'e> {
+    /// Returns `true` if this value can be called as a function, like `functionp`.
+    pub fn is_function(self) -> Result<bool> {
+        self.predicate("functionp")
+    }
+
+    /// Returns `true` if this value is an interactive command, like `commandp`.
+    pub fn is_command(self) -> Result<bool> {
+        self.predicate("commandp")
+    }
+
+    /// Returns `true` if this value is a macro, or a symbol whose function definition is a macro,
+    /// like `macrop`.
+    pub fn is_macro(self) -> Result<bool> {
+        self.predicate("macrop")
+    }
+
+    fn predicate(self, name: &str) -> Result<bool> {
+        let env = self.env;
+        Ok(env.is_not_nil(call_lisp!(env, name, self)?))
+    }
+}
+
+/// Comparisons.
+impl<'e> Value<'e> {
+    /// Returns `true` if this value is `nil`.
+    pub fn is_nil(self) -> bool {
+        !self.is_not_nil()
+    }
+
+    /// Returns `true` if this value is not `nil`, i.e. if it is true as a condition in Lisp.
+    pub fn is_not_nil(self) -> bool {
+        self.env.is_not_nil(self)
+    }
+
+    /// Returns `true` if this value and `other` are the same Lisp object, like `eq`.
+    pub fn eq(self, other: Value<'_>) -> bool {
+        self.env.eq(self, other)
+    }
+
+    /// Returns `true` if this value and `other` have the same structure and contents, like `equal`.
+    pub fn equal(self, other: Value<'_>) -> Result<bool> {
+        let env = self.env;
+        Ok(env.is_not_nil(call_lisp!(env, "equal", self, other)?))
+    }
+}
+
+/// Hashing.
+impl<'e> Value<'e> {
+    /// Returns a hash code of this value, such that values that are `equal` have the same hash
+    /// code, using `sxhash-equal` (`sxhash` before Emacs 26).
+    pub fn lisp_hash(self) -> Result<i64> {
+        let env = self.env;
+        let sxhash_equal = env.intern("sxhash-equal")?;
+        if env.is_not_nil(call_lisp!(env, "fboundp", sxhash_equal)?) {
+            call_lisp!(env, "sxhash-equal", self)?.into_rust()
+        } else {
+            call_lisp!(env, "sxhash", self)?.into_rust()
+        }
+    }
+}
+
+thread_local! {
+    /// A weak `equal` hash table, mapping each value wrapped by `EqualValue` to `(KEY . ID)`, where
+    /// `KEY` is the first value `equal` to it that was wrapped, and `ID` is shared by all of them.
+    static EQUAL_KEYS: RefCell<Option<GlobalRef>> = RefCell::new(None);
+
+    /// The `ID` of the next value wrapped by `EqualValue` that is not `equal` to any before it.
+    static NEXT_EQUAL_ID: Cell<i64> = Cell::new(0);
+}
+
+/// A global reference to a Lisp value, which implements [`Hash`] and [`Eq`] by Lisp's `equal`,
+/// allowing Lisp values to be used as keys in Rust's `HashMap` and `HashSet`.
+///
+/// Like a [`GlobalRef`], it keeps the value alive, and is not bound to the [`Env`] it comes from,
+/// so it can be stored across module calls, e.g. as the key of a cache.
+///
+/// Values are compared when they are wrapped, by looking them up in a weak `equal` hash table,
+/// which gives all `equal` values the same identity. Hashing and comparing wrappers therefore
+/// does not call into Lisp. As with the keys of a Lisp hash table, a wrapped value must not be
+/// modified.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::cell::RefCell;
+/// # use std::collections::HashMap;
+/// # use emacs::{defun, EqualValue, Result, Value};
+/// thread_local! {
+///     static SIZES: RefCell<HashMap<EqualValue, i64>> = RefCell::new(HashMap::new());
+/// }
+///
+/// #[defun]
+/// fn size(form: Value<'_>) -> Result<i64> {
+///     let key = EqualValue::new(form)?;
+///     if let Some(size) = SIZES.with(|sizes| sizes.borrow().get(&key).cloned()) {
+///         return Ok(size);
+///     }
+///     let size = form.env.call("safe-length", &[form])?.into_rust()?;
+///     SIZES.with(|sizes| sizes.borrow_mut().insert(key, size));
+///     Ok(size)
+/// }
+/// ```
+///
+/// [`GlobalRef`]: struct.GlobalRef.html
+/// [`Env`]: struct.Env.html
+/// [`Hash`]: https://doc.rust-lang.org/std/hash/trait.Hash.html
+/// [`Eq`]: https://doc.rust-lang.org/std/cmp/trait.Eq.html
+#[derive(Debug)]
+pub struct EqualValue {
+    value: GlobalRef,
+    /// The key of the value's entry in `EQUAL_KEYS`, which keeps the entry alive.
+    _key: GlobalRef,
+    id: i64,
+}
+
+impl EqualValue {
+    pub fn new(value: Value<'_>) -> Result<Self> {
+        let env = value.env;
+        let table = EQUAL_KEYS.with(|keys| -> Result<_> {
+            let mut keys = keys.borrow_mut();
+            match keys.as_ref() {
+                Some(table) => Ok(table.bind(env)),
+                None => {
+                    let weakness = Some(HashTableWeakness::Key);
+                    let table = env.make_hash_table(HashTableTest::Equal, 0, weakness)?;
+                    *keys = Some(GlobalRef::new(table));
+                    Ok(table)
+                }
+            }
+        })?;
+        let mut entry = env.gethash(table, value, ())?;
+        if entry.is_nil() {
+            let id = NEXT_EQUAL_ID.with(|next| next.replace(next.get() + 1));
+            entry = env.cons(value, id)?;
+            env.puthash(table, value, entry)?;
+        }
+        let _key = GlobalRef::new(env.car(entry)?);
+        let id = env.cdr(entry)?.into_rust()?;
+        Ok(EqualValue { value: GlobalRef::new(value), _key, id })
+    }
+
+    /// Returns the wrapped value, bound to the given [`Env`].
+    ///
+    /// [`Env`]: struct.Env.html
+    pub fn value<'e>(&self, env: &'e Env) -> Value<'e> {
+        self.value.bind(env)
+    }
+}
+
+impl Hash for EqualValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl PartialEq for EqualValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for EqualValue {}
@@ -0,0 +1,109 @@
+use super::error::Result;
+use super::symbol::CoreSymbol;
+use super::{Closure, Env, IntoLisp, Value};
+
+impl Env {
+    /// Registers a Rust closure to be called when the given variable is about to be changed,
+    /// through [`add-variable-watcher`]. This requires Emacs 26+.
+    ///
+    /// The closure receives the same arguments as a Lisp watch function: the symbol, the new value,
+    /// the kind of operation (`set`, `let`, `unlet`, `makunbound`, or `defvaralias`), and the
+    /// buffer if the change is buffer-local (`nil` otherwise). Panics inside the closure are caught
+    /// and signaled to Lisp as `rust-panic`.
+    ///
+    /// The closure is dropped by the GC after the watcher is removed, e.g. by
+    /// `remove-variable-watcher`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, Result};
+    /// fn init(env: &Env) -> Result<()> {
+    ///     env.add_variable_watcher("my-mod-mode", |env, _symbol, newval, _operation, _where| {
+    ///         env.message(&format!("my-mod-mode is now {}", env.is_not_nil(newval)))?;
+    ///         Ok(())
+    ///     })?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [`add-variable-watcher`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Watching-Variables.html
+    pub fn add_variable_watcher<F>(&self, symbol: &str, f: F) -> Result<Value<'_>>
+    where
+        F: Fn(&Env, Value<'_>, Value<'_>, Value<'_>, Value<'_>) -> Result<()> + 'static,
+    {
+        let watcher = Closure::shared(4..4, move |env| {
+            f(env, env.get_arg(0), env.get_arg(1), env.get_arg(2), env.get_arg(3))?;
+            env.nil()
+        })
+        .into_lisp(self)?;
+        let symbol = self.intern(symbol)?;
+        call_lisp!(self, "add-variable-watcher", symbol, watcher)
+    }
+
+    /// Calls `f` with the given variable bound to `value`, like a `let` binding of a single
+    /// variable in Lisp. The binding is made by a `let` form evaluated in Lisp, so it is undone
+    /// after `f` returns, whether it succeeds, fails, or panics. As with `let`, the variable may be
+    /// void, and if it is buffer-local, only its value in the current buffer is bound.
+    pub fn with_var_bound<'e, T, V, F>(&'e self, name: &str, value: V, f: F) -> Result<T>
+    where
+        V: IntoLisp<'e>,
+        F: FnOnce() -> Result<T>,
+    {
+        let value = value.into_lisp(self)?;
+        self.with_bindings(&[(name, value)], f)
+    }
+
+    /// Calls `f` with `inhibit-read-only` temporarily set to `t`, allowing it to modify read-only
+    /// buffers and text. See [`with_var_bound`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use emacs::{Env, IntoLisp, Result};
+    /// fn append_log(env: &Env, line: &str) -> Result<()> {
+    ///     env.with_inhibit_read_only(|| {
+    ///         env.call("goto-char", &[env.call("point-max", &[])?])?;
+    ///         env.call("insert", &[line.into_lisp(env)?])?;
+    ///         Ok(())
+    ///     })
+    /// }
+    /// ```
+    ///
+    /// [`with_var_bound`]: #method.with_var_bound
+    pub fn with_inhibit_read_only<T, F>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce() -> Result<T>,
+    {
+        self.with_var_bound("inhibit-read-only", true, f)
+    }
+
+    /// Calls `f` with the given variables bound to the given values, by a `let` form evaluated in
+    /// Lisp. Buffer-local and void variables are therefore handled the same way as in Lisp, and
+    /// the bindings are undone even if `f` panics.
+    pub(crate) fn with_bindings<T, F>(&self, bindings: &[(&str, Value<'_>)], f: F) -> Result<T>
+    where
+        F: FnOnce() -> Result<T>,
+    {
+        let quote = self.core_symbol(CoreSymbol::Quote)?;
+        let mut varlist = Vec::with_capacity(bindings.len());
+        for &(name, value) in bindings {
+            let value = self.list(&[quote, value])?;
+            varlist.push(self.list(&[self.intern(name)?, value])?);
+        }
+        let varlist = self.list(&varlist)?;
+        self.call_within(|body| self.list(&[self.intern("let")?, varlist, body]), f)
+    }
+
+    /// Returns the value of the given variable.
+    pub(crate) fn symbol_value(&self, name: &str) -> Result<Value<'_>> {
+        let symbol = self.intern(name)?;
+        call_lisp!(self, "symbol-value", symbol)
+    }
+
+    /// Sets the value of the given variable.
+    pub(crate) fn set(&self, name: &str, value: Value<'_>) -> Result<Value<'_>> {
+        let symbol = self.intern(name)?;
+        call_lisp!(self, "set", symbol, value)
+    }
+}
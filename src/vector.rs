@@ -0,0 +1,224 @@
+use std::convert::TryFrom;
+use std::ops::{Deref, DerefMut};
+
+use super::error::{Error, ErrorKind, Result};
+use super::{Env, FromLisp, IntoLisp, Value};
+
+/// A Lisp vector, with checked access to its elements.
+///
+/// It can be obtained from a [`Value`] through `TryFrom`, which checks that the value is a vector,
+/// or by [`make_vector`]. It also implements [`FromLisp`], so it can be used as a parameter of a
+/// [`#[defun]`].
+///
+/// Unlike `aref` and `aset` in Lisp, accessing an out-of-bounds index returns an
+/// [`ErrorKind::IndexOutOfBounds`] error.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use emacs::{defun, Result, Vector};
+/// #[defun]
+/// fn count_nils(v: Vector<'_>) -> Result<usize> {
+///     Ok(v.iter()?.filter(|item| !item.env.is_not_nil(*item)).count())
+/// }
+/// ```
+///
+/// [`Value`]: struct.Value.html
+/// [`make_vector`]: struct.Env.html#method.make_vector
+/// [`FromLisp`]: trait.FromLisp.html
+/// [`#[defun]`]: /emacs-macros/*/emacs_macros/attr.defun.html
+/// [`ErrorKind::IndexOutOfBounds`]: enum.ErrorKind.html#variant.IndexOutOfBounds
+#[derive(Debug, Clone, Copy)]
+pub struct Vector<'e>(Value<'e>);
+
+impl<'e> Vector<'e> {
+    /// Returns the number of elements.
+    pub fn len(&self) -> Result<usize> {
+        let Vector(Value { raw, env }) = *self;
+        let size = raw_call!(env, vec_size, raw)?;
+        Ok(size as usize)
+    }
+
+    /// Returns whether there are no elements.
+    pub fn is_empty(&self) -> Result<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    /// Returns the element at index `i`.
+    pub fn get(&self, i: usize) -> Result<Value<'e>> {
+        self.check_index(i)?;
+        let Vector(Value { raw, env }) = *self;
+        raw_call_value!(env, vec_get, raw, i as isize)
+    }
+
+    /// Sets the element at index `i` to `value`.
+    pub fn set<T: IntoLisp<'e>>(&self, i: usize, value: T) -> Result<()> {
+        self.check_index(i)?;
+        let Vector(Value { raw, env }) = *self;
+        let value = value.into_lisp(env)?;
+        raw_call!(env, vec_set, raw, i as isize, value.raw)
+    }
+
+    /// Returns an iterator over the elements, from first to last.
+    pub fn iter(&self) -> Result<VectorIter<'e>> {
+        Ok(VectorIter { vector: *self, index: 0, len: self.len()? })
+    }
+
+    /// Returns the underlying Lisp vector.
+    pub fn value(&self) -> Value<'e> {
+        self.0
+    }
+
+    fn check_index(&self, index: usize) -> Result<()> {
+        let len = self.len()?;
+        if index >= len {
+            return Err(ErrorKind::IndexOutOfBounds { index, len }.into());
+        }
+        Ok(())
+    }
+}
+
+impl<'e> TryFrom<Value<'e>> for Vector<'e> {
+    type Error = Error;
+
+    /// Checks that the value is a vector, signaling `(wrong-type-argument vectorp VALUE)` if not.
+    fn try_from(value: Value<'e>) -> Result<Self> {
+        let env = value.env;
+        if !env.is_not_nil(call_lisp!(env, "vectorp", value)?) {
+            return Err(env.wrong_type("vectorp", value));
+        }
+        Ok(Vector(value))
+    }
+}
+
+impl<'e> FromLisp<'e> for Vector<'e> {
+    fn from_lisp(value: Value<'e>) -> Result<Self> {
+        Vector::try_from(value)
+    }
+}
+
+impl<'e> IntoLisp<'e> for Vector<'e> {
+    fn into_lisp(self, _: &'e Env) -> Result<Value<'e>> {
+        Ok(self.0)
+    }
+}
+
+/// An iterator over the elements of a [`Vector`].
+///
+/// [`Vector`]: struct.Vector.html
+#[derive(Debug, Clone)]
+pub struct VectorIter<'e> {
+    vector: Vector<'e>,
+    index: usize,
+    len: usize,
+}
+
+impl<'e> Iterator for VectorIter<'e> {
+    type Item = Value<'e>;
+
+    fn next(&mut self) -> Option<Value<'e>> {
+        if self.index >= self.len {
+            return None;
+        }
+        let Vector(Value { raw, env }) = self.vector;
+        // This cannot fail, since the length of a vector never changes.
+        let item = raw_call_value!(env, vec_get, raw, self.index as isize).ok();
+        self.index += 1;
+        item
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl Env {
+    /// Creates a Lisp vector of the given length, with all elements initialized to `init`, like
+    /// `make-vector`.
+    pub fn make_vector<'e, T: IntoLisp<'e>>(&'e self, len: usize, init: T) -> Result<Vector<'e>> {
+        let len = (len as i64).into_lisp(self)?;
+        let init = init.into_lisp(self)?;
+        Ok(Vector(call_lisp!(self, "make-vector", len, init)?))
+    }
+}
+
+/// A guard that gives mutable access to the elements of a Lisp vector, as a Rust slice.
+///
+/// Since Lisp vectors are not contiguous Rust memory, the elements are copied (converted) into a
+/// `Vec<T>` when the guard is created, and written back into the Lisp vector by [`commit`].
+/// Dropping the guard without committing it discards the changes, e.g. when returning early
+/// because of an error.
+///
+/// This also implements [`FromLisp`], so it can be used as a parameter of a [`#[defun]`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use emacs::{defun, Result, VectorMut};
+/// #[defun]
+/// fn sort_floats(mut v: VectorMut<'_, f64>) -> Result<()> {
+///     v.sort_by(|a, b| a.partial_cmp(b).unwrap());
+///     v.commit()
+/// }
+/// ```
+///
+/// [`commit`]: #method.commit
+/// [`FromLisp`]: trait.FromLisp.html
+/// [`#[defun]`]: /emacs-macros/*/emacs_macros/attr.defun.html
+#[derive(Debug)]
+pub struct VectorMut<'e, T: IntoLisp<'e>> {
+    vector: Value<'e>,
+    items: Vec<T>,
+}
+
+impl<'e, T: FromLisp<'e> + IntoLisp<'e>> VectorMut<'e, T> {
+    /// Copies the elements of the given Lisp vector, converting each of them into `T`.
+    pub fn new(vector: Value<'e>) -> Result<Self> {
+        let env = vector.env;
+        let size = raw_call!(env, vec_size, vector.raw)?;
+        let mut items = Vec::with_capacity(size as usize);
+        for i in 0..size {
+            items.push(raw_call_value!(env, vec_get, vector.raw, i)?.into_rust()?);
+        }
+        Ok(VectorMut { vector, items })
+    }
+}
+
+impl<'e, T: IntoLisp<'e>> VectorMut<'e, T> {
+    /// Writes the elements back into the Lisp vector, returning the first error encountered.
+    pub fn commit(self) -> Result<()> {
+        let VectorMut { vector, items } = self;
+        let env = vector.env;
+        for (i, item) in items.into_iter().enumerate() {
+            let value = item.into_lisp(env)?;
+            raw_call!(env, vec_set, vector.raw, i as isize, value.raw)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the underlying Lisp vector.
+    pub fn value(&self) -> Value<'e> {
+        self.vector
+    }
+}
+
+impl<'e, T: IntoLisp<'e>> Deref for VectorMut<'e, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.items
+    }
+}
+
+impl<'e, T: IntoLisp<'e>> DerefMut for VectorMut<'e, T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        &mut self.items
+    }
+}
+
+impl<'e, T: FromLisp<'e> + IntoLisp<'e>> FromLisp<'e> for VectorMut<'e, T> {
+    fn from_lisp(value: Value<'e>) -> Result<Self> {
+        VectorMut::new(value)
+    }
+}
@@ -0,0 +1,57 @@
+use emacs::{defun, Buffer, Env, IntoLisp, Result, Value};
+
+/// Insert TEXT into a temporary buffer, returning its contents. Signal an error afterwards if FAIL
+/// is non-nil.
+#[defun]
+fn with_temp(env: &Env, text: String, fail: Option<Value<'_>>) -> Result<String> {
+    env.with_temp_buffer(|env| {
+        env.call("insert", &[text.into_lisp(env)?])?;
+        if fail.is_some() {
+            env.call("error", &["Failed in temp buffer".into_lisp(env)?])?;
+        }
+        env.call("buffer-string", &[])?.into_rust()
+    })
+}
+
+/// Panic in a temporary buffer named NAME.
+#[defun]
+fn panic_in_temp(env: &Env, name: String) -> Result<()> {
+    env.with_temp_buffer(|env| {
+        env.call("rename-buffer", &[name.into_lisp(env)?])?;
+        panic!("Oops")
+    })
+}
+
+#[defun]
+fn names(env: &Env) -> Result<Vec<String>> {
+    let mut names = vec![];
+    for buffer in env.buffer_list()? {
+        names.push(env.call("buffer-name", &[buffer])?.into_rust()?);
+    }
+    Ok(names)
+}
+
+/// Return the name of the current buffer, as seen with BUFFER made current. Signal an error
+/// afterwards if FAIL is non-nil.
+#[defun]
+fn name_in(env: &Env, buffer: Value<'_>, fail: Option<Value<'_>>) -> Result<String> {
+    env.with_buffer(buffer, |env| {
+        let name = env.call("buffer-name", &[])?.into_rust()?;
+        if fail.is_some() {
+            env.call("error", &["Failed in buffer".into_lisp(env)?])?;
+        }
+        Ok(name)
+    })
+}
+
+/// Panic with BUFFER made current.
+#[defun]
+fn panic_in(env: &Env, buffer: Value<'_>) -> Result<()> {
+    env.with_buffer(buffer, |_| panic!("Oops"))
+}
+
+/// Return the size of BUFFER, which can be a buffer or the name of one.
+#[defun]
+fn size(env: &Env, buffer: Buffer<'_>) -> Result<i64> {
+    env.call("buffer-size", &[buffer.value()])?.into_rust()
+}
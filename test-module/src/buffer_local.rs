@@ -0,0 +1,44 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use emacs::{defun, BufferLocal, Env, Result};
+
+static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+struct Counter(i64);
+
+impl Drop for Counter {
+    fn drop(&mut self) {
+        DROPPED.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+static COUNTER: BufferLocal<Counter> = BufferLocal::new("t--buffer-local-counter");
+
+/// Start counting in the current buffer, from N.
+#[defun]
+fn start(env: &Env, n: i64) -> Result<()> {
+    COUNTER.set(env, Counter(n))
+}
+
+/// Increment the current buffer's count, returning the new count, or nil if not counting.
+#[defun]
+fn increment(env: &Env) -> Result<Option<i64>> {
+    COUNTER.with(env, |counter| {
+        counter.map(|counter| {
+            counter.0 += 1;
+            counter.0
+        })
+    })
+}
+
+/// Stop counting in the current buffer, returning the final count.
+#[defun]
+fn stop(env: &Env) -> Result<Option<i64>> {
+    Ok(COUNTER.take(env)?.map(|counter| counter.0))
+}
+
+/// Return the number of counters dropped so far.
+#[defun]
+fn dropped() -> Result<i64> {
+    Ok(DROPPED.load(Ordering::SeqCst) as i64)
+}
@@ -0,0 +1,23 @@
+use emacs::{defun, ByteVector, Env, Result};
+
+/// Encode all 256 byte values in base64, check that decoding gives them back, and return the
+/// encoded string.
+#[defun]
+fn base64_roundtrip(env: &Env) -> Result<String> {
+    let bytes: Vec<u8> = (0..=255).collect();
+    let encoded = env.base64_encode(&bytes)?;
+    assert_eq!(env.base64_decode(&encoded)?, bytes);
+    Ok(encoded)
+}
+
+/// Decode S, returning the number of bytes.
+#[defun]
+fn base64_decoded_len(env: &Env, s: String) -> Result<i64> {
+    Ok(env.base64_decode(&s)?.len() as i64)
+}
+
+/// Return BYTES with each byte inverted.
+#[defun]
+fn invert(bytes: ByteVector) -> Result<ByteVector> {
+    Ok(ByteVector(bytes.0.iter().map(|b| !b).collect()))
+}
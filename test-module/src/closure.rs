@@ -0,0 +1,30 @@
+use emacs::{defun, Closure, IntoLisp, Result};
+
+#[defun]
+fn make_adder(x: i64) -> Result<Closure> {
+    Ok(Closure::new(1..1, move |env| {
+        let y: i64 = env.parse_arg(0)?;
+        (x + y).into_lisp(env)
+    }))
+}
+
+#[defun]
+fn make_counter() -> Result<Closure> {
+    let mut count = 0;
+    Ok(Closure::new(0..0, move |env| {
+        count += 1;
+        count.into_lisp(env)
+    }))
+}
+
+/// Return a closure that calls its only argument.
+#[defun]
+fn make_caller() -> Result<Closure> {
+    Ok(Closure::new(1..1, |env| env.call("funcall", &[env.get_arg(0)])))
+}
+
+/// Return a closure that returns the number of its (at least one) arguments.
+#[defun]
+fn make_arg_counter() -> Result<Closure> {
+    Ok(Closure::new(1..usize::MAX, |env| (env.args().len() as i64).into_lisp(env)))
+}
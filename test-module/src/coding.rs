@@ -0,0 +1,13 @@
+use emacs::{defun, Env, Result, Value};
+
+/// Call F with `coding-system-for-read' temporarily set to `utf-8'.
+#[defun]
+fn with_utf_8_read<'e>(env: &'e Env, f: Value<'_>) -> Result<Value<'e>> {
+    env.with_coding_system(Some("utf-8"), None, || env.call("funcall", &[f]))
+}
+
+/// Panic with `coding-system-for-read' temporarily set to `utf-8'.
+#[defun]
+fn panic_with_utf_8_read(env: &Env) -> Result<()> {
+    env.with_coding_system(Some("utf-8"), None, || panic!("Oops"))
+}
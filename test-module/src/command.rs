@@ -0,0 +1,79 @@
+use emacs::{defun, Env, InteractiveKind, IntoLisp, Result, Value};
+
+#[defun]
+fn this(env: &Env) -> Result<Option<Value<'_>>> {
+    env.this_command()
+}
+
+#[defun]
+fn last(env: &Env) -> Result<Option<Value<'_>>> {
+    env.last_command()
+}
+
+/// Return the number of consecutive times the current command has been invoked, counting from 1,
+/// using the symbol property `t--count` of `this-command`.
+#[defun]
+fn repeat_count(env: &Env) -> Result<i64> {
+    let this = match env.this_command()? {
+        Some(this) => this,
+        None => return Ok(0),
+    };
+    let repeated = match env.last_command()? {
+        Some(last) => env.eq(this, last),
+        None => false,
+    };
+    let prop = env.intern("t--count")?;
+    let count = if repeated {
+        env.call("get", &[this, prop])?.into_rust::<Option<i64>>()?.unwrap_or(0) + 1
+    } else {
+        1
+    };
+    env.call("put", &[this, prop, count.into_lisp(env)?])?;
+    Ok(count)
+}
+
+#[defun]
+fn call_interactively_with<'e>(command: Value<'e>, arg: Value<'e>) -> Result<Value<'e>> {
+    command.env.funcall_interactively(command, &[arg])
+}
+
+/// Return the direction of a step, based on the keys that invoked the current command.
+#[defun]
+fn step_direction(env: &Env) -> Result<i64> {
+    match env.this_command_keys()?.as_str() {
+        "C-c n" => Ok(1),
+        "C-c p" => Ok(-1),
+        _ => Ok(0),
+    }
+}
+
+/// Return whether this was called interactively: directly by the user if DIRECT is non-nil, in any
+/// way otherwise.
+#[defun]
+fn interactive_p(env: &Env, direct: Option<Value<'_>>) -> Result<bool> {
+    let kind = match direct {
+        Some(_) => InteractiveKind::Interactive,
+        None => InteractiveKind::Any,
+    };
+    env.called_interactively_p(kind)
+}
+
+#[defun(interactive_form = "(list (read-string \"Name: \"))")]
+fn greet(name: String) -> Result<String> {
+    Ok(format!("Hello, {}!", name))
+}
+
+#[defun(interactive = "p")]
+fn double(n: i64) -> Result<i64> {
+    Ok(n * 2)
+}
+
+#[defun(interactive = "sYour \"name\": ")]
+fn quoted_prompt(name: String) -> Result<String> {
+    Ok(name)
+}
+
+#[defun(interactive)]
+fn answer() -> Result<i64> {
+    Ok(42)
+}
@@ -0,0 +1,34 @@
+use emacs::{defun, Cons, Env, Result, Value};
+
+#[defun]
+fn make<'e>(env: &'e Env, car: Value<'e>, cdr: Value<'e>) -> Result<Value<'e>> {
+    env.cons(car, cdr)
+}
+
+#[defun]
+fn car(v: Value<'_>) -> Result<Value<'_>> {
+    v.env.car(v)
+}
+
+#[defun]
+fn cdr(v: Value<'_>) -> Result<Value<'_>> {
+    v.env.cdr(v)
+}
+
+/// Return a new cons cell with the car and cdr of PAIR swapped.
+#[defun]
+fn swap(pair: Cons<'_>) -> Result<Value<'_>> {
+    let env = pair.value().env;
+    env.cons(pair.cdr()?, pair.car()?)
+}
+
+/// Return an alist mapping each of the strings KEYS to its length.
+#[defun]
+fn lengths(env: &Env, keys: Vec<String>) -> Result<Value<'_>> {
+    let mut alist = env.nil()?;
+    for key in keys.into_iter().rev() {
+        let len = key.len() as i64;
+        alist = env.cons(env.cons(key, len)?, alist)?;
+    }
+    Ok(alist)
+}
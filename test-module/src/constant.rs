@@ -0,0 +1,12 @@
+use emacs::{defun, Env, Result, Value};
+
+#[defun]
+fn define(env: &Env) -> Result<Value<'_>> {
+    env.declare_pure("t/constant-square")?;
+    env.defconst("t--max-batch-size", 1024, "Largest batch size.")
+}
+
+#[defun]
+fn square(x: i64) -> Result<i64> {
+    Ok(x * x)
+}
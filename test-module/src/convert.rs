@@ -0,0 +1,78 @@
+use std::num::{NonZeroI64, NonZeroU8, NonZeroUsize, ParseIntError};
+
+use emacs::{defun, Result, Value};
+
+/// Split TOTAL items into batches of BATCH-SIZE, returning the number of batches.
+#[defun]
+fn batch_count(total: i64, batch_size: NonZeroUsize) -> Result<i64> {
+    let batch_size = batch_size.get() as i64;
+    Ok((total + batch_size - 1) / batch_size)
+}
+
+#[defun]
+fn nonzero_u8_identity(x: NonZeroU8) -> Result<NonZeroU8> {
+    Ok(x)
+}
+
+#[defun]
+fn nonzero_i64_negate(x: NonZeroI64) -> Result<Option<NonZeroI64>> {
+    Ok(NonZeroI64::new(-x.get()))
+}
+
+/// Parse S as an integer, returning nil if S is nil.
+#[defun]
+fn maybe_parse(s: Option<Value<'_>>) -> Result<Option<Result<i64>>> {
+    Ok(s.map(|s| {
+        let string: String = s.into_rust()?;
+        string.parse().map_err(|_| s.env.wrong_type("t--integer-string-p", s))
+    }))
+}
+
+#[defun]
+fn one_two_three() -> Result<Vec<i64>> {
+    Ok(vec![1, 2, 3])
+}
+
+/// Return the words of S, as a list of strings.
+#[defun]
+fn words(s: String) -> Result<Vec<String>> {
+    Ok(s.split_whitespace().map(str::to_owned).collect())
+}
+
+/// Parse the words of S as integers, returning them as a list.
+#[defun]
+fn parse_all(s: String) -> Result<Vec<std::result::Result<i64, ParseIntError>>> {
+    Ok(s.split_whitespace().map(str::parse).collect())
+}
+
+#[defun]
+fn sum(numbers: Vec<i64>) -> Result<i64> {
+    Ok(numbers.iter().sum())
+}
+
+/// Return the lengths of STRINGS, a list or vector of strings.
+#[defun]
+fn lengths(strings: Vec<String>) -> Result<Vec<i64>> {
+    Ok(strings.iter().map(|s| s.chars().count() as i64).collect())
+}
+
+/// Return the length of S, or nil if S is nil.
+#[defun]
+fn maybe_length(s: Option<String>) -> Result<Option<i64>> {
+    Ok(s.map(|s| s.chars().count() as i64))
+}
+
+/// Return the name and age in PERSON, a list (NAME AGE), with the age incremented.
+#[defun]
+fn birthday(person: (String, i64)) -> Result<(String, i64)> {
+    let (name, age) = person;
+    Ok((name, age + 1))
+}
+
+/// Return the sum of the 12 numbers in the list NUMBERS.
+#[defun]
+#[allow(clippy::type_complexity)]
+fn sum_12(numbers: (i64, i64, i64, i64, i64, i64, i64, i64, i64, i64, i64, i64)) -> Result<i64> {
+    let (a, b, c, d, e, f, g, h, i, j, k, l) = numbers;
+    Ok(a + b + c + d + e + f + g + h + i + j + k + l)
+}
@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use emacs::{defun, Handle, Result};
+
+static CLOSED: AtomicUsize = AtomicUsize::new(0);
+
+/// An in-memory key-value store, standing in for a database connection.
+struct Db(HashMap<String, String>);
+
+impl Drop for Db {
+    fn drop(&mut self) {
+        CLOSED.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// Open a new, empty database.
+#[defun(user_ptr(direct))]
+fn open() -> Result<Handle<Db>> {
+    Ok(Handle::new(Db(HashMap::new())))
+}
+
+/// Associate KEY with VALUE in DB.
+#[defun(direct(db))]
+fn put(db: &Handle<Db>, key: String, value: String) -> Result<()> {
+    db.borrow_mut()?.0.insert(key, value);
+    Ok(())
+}
+
+/// Return the value associated with KEY in DB, or nil if there is none.
+#[defun(direct(db))]
+fn get(db: &Handle<Db>, key: String) -> Result<Option<String>> {
+    Ok(db.borrow()?.0.get(&key).cloned())
+}
+
+/// Close DB. Return t if it was open.
+#[defun(direct(db))]
+fn close(db: &Handle<Db>) -> Result<bool> {
+    Ok(db.close()?.is_some())
+}
+
+/// Return t if DB is closed.
+#[defun(direct(db))]
+fn closed_p(db: &Handle<Db>) -> Result<bool> {
+    Ok(db.is_closed())
+}
+
+/// Return the number of databases closed so far.
+#[defun]
+fn closed_count() -> Result<i64> {
+    Ok(CLOSED.load(Ordering::SeqCst) as i64)
+}
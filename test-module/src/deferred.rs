@@ -0,0 +1,36 @@
+use std::thread;
+
+use lazy_static::lazy_static;
+
+use emacs::{defun, DeferredQueue, Env, IntoLisp, Result};
+
+lazy_static! {
+    static ref QUEUE: DeferredQueue = DeferredQueue::new();
+}
+
+/// Schedule, from a background thread, appending 0..COUNT to the current buffer's contents.
+#[defun]
+fn schedule_inserts(count: i64) -> Result<usize> {
+    thread::spawn(move || {
+        for i in 0..count {
+            QUEUE.push(move |env| {
+                env.call("insert", &[env.call("number-to-string", &[i.into_lisp(env)?])?])?;
+                Ok(())
+            });
+        }
+    })
+    .join()
+    .expect("Failed to join worker thread");
+    Ok(QUEUE.len())
+}
+
+#[defun]
+fn schedule_panic() -> Result<()> {
+    QUEUE.push(|_| panic!("deferred oops"));
+    Ok(())
+}
+
+#[defun]
+fn drain(env: &Env) -> Result<usize> {
+    QUEUE.drain(env)
+}
@@ -0,0 +1,6 @@
+use emacs::{defun, Env, Result, Value};
+
+#[defun]
+fn get(env: &Env, name: String) -> Result<Value<'_>> {
+    env.face(&name)
+}
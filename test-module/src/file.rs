@@ -0,0 +1,32 @@
+use emacs::{defun, Env, IntoLisp, Result, Value};
+
+/// Read the file at PATH, decoding it with CODING if non-nil.
+#[defun]
+fn read(env: &Env, path: String, coding: Option<String>) -> Result<String> {
+    let coding = coding.as_ref().map(String::as_str);
+    env.with_coding_system(coding, None, || env.insert_file_contents(&path))
+}
+
+/// Write CONTENTS to the file at PATH, encoding it with CODING if non-nil.
+#[defun]
+fn write(env: &Env, path: String, contents: String, coding: Option<String>) -> Result<()> {
+    let coding = coding.as_ref().map(String::as_str);
+    env.with_coding_system(None, coding, || env.write_file(&path, &contents))
+}
+
+/// Return the names of the files in DIR matching REGEXP, as a sorted list of strings.
+#[defun]
+fn list(
+    env: &Env,
+    dir: String,
+    full: Option<Value<'_>>,
+    regexp: Option<String>,
+) -> Result<Value<'_>> {
+    let regexp = regexp.as_ref().map(String::as_str);
+    let files = env.directory_files_matching(&dir, full.is_some(), regexp)?;
+    let names = files
+        .iter()
+        .map(|file| file.to_string_lossy().into_lisp(env))
+        .collect::<Result<Vec<_>>>()?;
+    env.list(&names)
+}
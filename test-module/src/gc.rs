@@ -0,0 +1,21 @@
+use emacs::{defun, Env, GcStats, IntoLisp, Result, Value};
+
+#[defun]
+fn collect(env: &Env) -> Result<Value<'_>> {
+    env.garbage_collect()
+}
+
+/// Run a garbage collection, returning the parsed statistics about NAME as a list
+/// `(SIZE USED FREE)`, or nil if there are none.
+#[defun]
+fn stats<'e>(env: &'e Env, name: String) -> Result<Value<'e>> {
+    let stats: GcStats = env.garbage_collect()?.into_rust()?;
+    match stats.get(&name) {
+        Some(entry) => env.list(&[
+            entry.size.into_lisp(env)?,
+            entry.used.into_lisp(env)?,
+            entry.free.into_lisp(env)?,
+        ]),
+        None => env.intern("nil"),
+    }
+}
@@ -0,0 +1,13 @@
+use emacs::Result;
+
+fn clamp<T: PartialOrd>(x: T, min: T, max: T) -> Result<T> {
+    Ok(if x < min {
+        min
+    } else if x > max {
+        max
+    } else {
+        x
+    })
+}
+
+emacs::defun_for_types!(clamp, [i64, f64, String]);
@@ -0,0 +1,26 @@
+use std::sync::Mutex;
+
+use lazy_static::lazy_static;
+
+use emacs::{defun, Env, GlobalRef, Result, Value};
+
+lazy_static! {
+    static ref REMEMBERED: Mutex<Option<GlobalRef>> = Mutex::new(None);
+}
+
+#[defun]
+fn remember(value: Value<'_>) -> Result<()> {
+    *REMEMBERED.lock().expect("Failed to lock") = Some(GlobalRef::new(value));
+    Ok(())
+}
+
+#[defun]
+fn recall(env: &Env) -> Result<Option<Value<'_>>> {
+    Ok(REMEMBERED.lock().expect("Failed to lock").as_ref().map(|r| r.bind(env)))
+}
+
+#[defun]
+fn forget() -> Result<()> {
+    *REMEMBERED.lock().expect("Failed to lock") = None;
+    Ok(())
+}
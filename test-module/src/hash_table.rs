@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+
+use emacs::{defun, Env, HashTableTest, HashTableWeakness, Result, Value};
+
+/// Return an `equal' hash table mapping each string in KEYS to its length.
+#[defun]
+fn lengths<'e>(env: &'e Env, keys: Vec<String>) -> Result<Value<'e>> {
+    let table = env.make_hash_table(HashTableTest::Equal, keys.len(), None)?;
+    for key in &keys {
+        env.puthash(table, key.as_str(), key.chars().count() as i64)?;
+    }
+    Ok(table)
+}
+
+#[defun]
+fn make_eq(env: &Env) -> Result<Value<'_>> {
+    env.make_hash_table(HashTableTest::Eq, 0, None)
+}
+
+#[defun]
+fn make_weak(env: &Env) -> Result<Value<'_>> {
+    env.make_hash_table(HashTableTest::Eq, 0, Some(HashTableWeakness::Key))
+}
+
+/// Return an `equal' hash table mapping each word in TEXT to its number of occurrences.
+#[defun]
+fn count_words(env: &Env, text: String) -> Result<Value<'_>> {
+    let table = env.make_hash_table(HashTableTest::Equal, 0, None)?;
+    for word in text.split_whitespace() {
+        let count: i64 = table.gethash(word)?.unwrap_or(0);
+        table.puthash(word, count + 1)?;
+    }
+    Ok(table)
+}
+
+/// Return (VALUE) if KEY is in TABLE, nil otherwise.
+#[defun]
+fn lookup<'e>(env: &'e Env, table: Value<'e>, key: Value<'e>) -> Result<Value<'e>> {
+    match table.gethash::<Value, _>(key)? {
+        Some(value) => env.list(&[value]),
+        None => env.list(&[]),
+    }
+}
+
+#[defun]
+fn delete<'e>(table: Value<'e>, key: Value<'e>) -> Result<()> {
+    table.remhash(key)
+}
+
+#[defun]
+fn get<'e>(env: &'e Env, table: Value<'e>, key: Value<'e>, default: Value<'e>) -> Result<Value<'e>> {
+    env.gethash(table, key, default)
+}
+
+#[defun]
+fn remove<'e>(env: &'e Env, table: Value<'e>, key: Value<'e>) -> Result<()> {
+    env.remhash(table, key)
+}
+
+/// Return a hash table with the same keys as MAP, and values incremented by 1.
+#[defun]
+fn inc_values(map: HashMap<String, i64>) -> Result<HashMap<String, i64>> {
+    Ok(map.into_iter().map(|(k, v)| (k, v + 1)).collect())
+}
@@ -0,0 +1,88 @@
+use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use emacs::{defun, Env, IntoLisp, Result, Value};
+
+/// Write "killed" to FILE when Emacs exits.
+#[defun]
+fn write_on_kill(env: &Env, file: String) -> Result<()> {
+    env.add_kill_emacs_hook(move |_| {
+        fs::write(&file, "killed")?;
+        Ok(())
+    })?;
+    Ok(())
+}
+
+/// Increment `t--command-count` after each command, in the current buffer only if LOCAL is non-nil.
+/// Return the function added to `post-command-hook`.
+#[defun]
+fn count_commands(env: &Env, local: Option<Value<'_>>) -> Result<Value<'_>> {
+    let count = |env: &Env| -> Result<()> {
+        let symbol = env.intern("t--command-count")?;
+        let count: i64 = env.call("symbol-value", &[symbol])?.into_rust()?;
+        env.call("set", &[symbol, (count + 1).into_lisp(env)?])?;
+        Ok(())
+    };
+    match local {
+        Some(_) => env.add_local_post_command_hook(count),
+        None => env.add_post_command_hook(count),
+    }
+}
+
+#[defun]
+fn remove_post_command(env: &Env, function: Value<'_>, local: Option<Value<'_>>) -> Result<()> {
+    env.remove_hook("post-command-hook", function, local.is_some())
+}
+
+/// Complete the symbol at point from WORDS, a string of space-separated candidates, in the current
+/// buffer. Return the completion-at-point function.
+#[defun]
+fn complete_from(env: &Env, words: String) -> Result<Value<'_>> {
+    let words: Vec<String> = words.split_whitespace().map(str::to_owned).collect();
+    env.add_capf(move |env| {
+        let end = env.call("point", &[])?;
+        env.call("skip-syntax-backward", &["w_".into_lisp(env)?])?;
+        let start = env.call("point", &[])?;
+        env.call("goto-char", &[end])?;
+        let candidates =
+            words.iter().map(|word| word.into_lisp(env)).collect::<Result<Vec<_>>>()?;
+        env.list(&[start, end, env.list(&candidates)?])
+    })
+}
+
+static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+/// A value captured by hook functions, counting how many of them have been dropped.
+struct Tracked;
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        DROPPED.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// Add a function that does nothing to `pre-command-hook` in the current buffer.
+#[defun]
+fn add_tracked_pre_command(env: &Env) -> Result<Value<'_>> {
+    let tracked = Tracked;
+    env.add_local_pre_command_hook(move |_| {
+        let _ = &tracked;
+        Ok(())
+    })
+}
+
+/// Add a completion-at-point function that never applies to the current buffer.
+#[defun]
+fn add_tracked_capf(env: &Env) -> Result<Value<'_>> {
+    let tracked = Tracked;
+    env.add_capf(move |env| {
+        let _ = &tracked;
+        env.nil()
+    })
+}
+
+/// Return the number of hook functions with tracked state dropped so far.
+#[defun]
+fn dropped() -> Result<i64> {
+    Ok(DROPPED.load(Ordering::SeqCst) as i64)
+}
@@ -0,0 +1,11 @@
+use emacs::{defun, Env, Result};
+
+#[defun]
+fn read_char(env: &Env, prompt: String) -> Result<String> {
+    Ok(env.read_char(&prompt)?.to_string())
+}
+
+#[defun]
+fn read_key_sequence(env: &Env, prompt: String) -> Result<String> {
+    env.read_key_sequence(&prompt)
+}
@@ -0,0 +1,17 @@
+use emacs::{defun, Env, Result, Value};
+
+/// Return a keymap with the described prefix "C-c p", under which "a" is bound to COMMAND.
+#[defun]
+fn make_prefixed(env: &Env, command: Value<'_>) -> Result<Value<'_>> {
+    let map = env.make_sparse_keymap(None)?;
+    let prefix = env.make_sparse_keymap(None)?;
+    env.define_key_with_desc(prefix, "a", command, "do-a")?;
+    env.define_key_with_desc(map, "C-c p", prefix, "my-prefix")?;
+    Ok(map)
+}
+
+/// Define the prefix command `t/dispatch`, under which "a" is bound to A, and "b" to B.
+#[defun]
+fn define_dispatch<'e>(env: &'e Env, a: Value<'_>, b: Value<'_>) -> Result<Value<'e>> {
+    env.define_dispatch("dispatch", &[("a", a, "do-a"), ("b", b, "do-b")])
+}
@@ -12,6 +12,47 @@ mod test_lifetime;
 mod ref_cell;
 mod vector;
 mod hash_map;
+mod variable;
+mod keymap;
+mod overlay;
+mod outcome;
+mod coding;
+mod read;
+mod progress;
+mod bytes;
+mod generic;
+mod obarray;
+mod seq;
+mod hook;
+mod value;
+mod marker;
+mod lisp_data;
+mod plist;
+mod command;
+mod face;
+mod closure;
+mod file;
+mod gc;
+mod convert;
+mod constant;
+mod input;
+mod symbol;
+mod buffer;
+mod lisp_vector;
+mod position;
+mod trace;
+mod hash_table;
+mod global;
+mod mode;
+mod lisp_macro;
+mod deferred;
+mod buffer_local;
+mod shared;
+mod db;
+mod matrix;
+mod cons;
+mod time;
+mod timer;
 
 emacs::plugin_is_GPL_compatible!();
 
@@ -20,7 +61,7 @@ lazy_static! {
     static ref MODULE_PREFIX: String = format!("{}/", MODULE);
 }
 
-#[emacs::module(name(fn), separator = "/")]
+#[emacs::module(name(fn), separator = "/", panic_condition = "t-panic")]
 fn t(env: &Env) -> Result<()> {
     env.message("Hello, Emacs!")?;
 
@@ -46,6 +87,15 @@ fn identity(x: Value) -> Result<Value> {
     Ok(x)
 }
 
+/// Return S with a "prefix" added.
+///
+/// The prefix is the literal `\\', followed by a tab:
+///   (t/prefix "a") ; => "\\\ta"
+#[defun]
+fn prefix(s: String) -> Result<String> {
+    Ok(format!("\\\t{}", s))
+}
+
 #[defun]
 fn to_uppercase(s: String) -> Result<String> {
     Ok(s.to_uppercase())
@@ -0,0 +1,114 @@
+use emacs::{defun, FromLisp, IntoLisp, LispRecord, LispType, Result};
+
+#[derive(IntoLisp, FromLisp)]
+struct Server {
+    host: String,
+    port: i64,
+}
+
+#[derive(IntoLisp, FromLisp)]
+struct Limits {
+    max_connections: i64,
+    timeout: Option<f64>,
+}
+
+#[derive(IntoLisp, FromLisp)]
+struct Config {
+    server: Server,
+    limits: Limits,
+}
+
+#[defun]
+fn default_config() -> Result<Config> {
+    Ok(Config {
+        server: Server { host: "localhost".to_owned(), port: 8080 },
+        limits: Limits { max_connections: 10, timeout: None },
+    })
+}
+
+#[defun]
+fn bump_port(config: Config) -> Result<Config> {
+    let Config { server: Server { host, port }, limits } = config;
+    Ok(Config { server: Server { host, port: port + 1 }, limits })
+}
+
+/// Mirrors `(cl-defstruct t--point x y)`.
+#[derive(LispRecord)]
+#[lisp_record(name = "t--point")]
+struct Point {
+    x: i64,
+    y: i64,
+}
+
+#[defun]
+fn make_point(x: i64, y: i64) -> Result<Point> {
+    Ok(Point { x, y })
+}
+
+#[defun]
+fn transpose_point(point: Point) -> Result<Point> {
+    let Point { x, y } = point;
+    Ok(Point { x: y, y: x })
+}
+
+/// Defines `(cl-defstruct t--span start end text)` when the module is loaded.
+#[derive(LispRecord)]
+#[lisp_record(name = "t--span", define)]
+struct Span {
+    start: i64,
+    end: i64,
+    text: String,
+}
+
+/// Return the span of the first occurrence of NEEDLE in HAYSTACK, or nil.
+#[defun]
+fn find_span(haystack: String, needle: String) -> Result<Option<Span>> {
+    Ok(haystack.find(&needle).map(|start| Span {
+        start: start as i64,
+        end: (start + needle.len()) as i64,
+        text: needle,
+    }))
+}
+
+#[defun]
+fn span_length(span: Span) -> Result<i64> {
+    Ok(span.end - span.start)
+}
+
+#[derive(IntoLisp, FromLisp)]
+struct UserId(i64);
+
+#[derive(IntoLisp, FromLisp)]
+struct Owner {
+    id: UserId,
+    name: String,
+}
+
+#[defun]
+fn user_id(id: i64) -> Result<UserId> {
+    Ok(UserId(id))
+}
+
+#[defun]
+fn next_user_id(id: UserId) -> Result<UserId> {
+    Ok(UserId(id.0 + 1))
+}
+
+#[defun]
+fn owner_id(owner: Owner) -> Result<UserId> {
+    Ok(owner.id)
+}
+
+/// Constructed from Lisp with `t/lisp-data-make-server-address`.
+#[derive(LispType)]
+struct ServerAddress {
+    host: String,
+    port: i64,
+    retries: Option<i64>,
+}
+
+/// Return ADDRESS with the number of retries defaulted to 3.
+#[defun]
+fn with_default_retries(address: ServerAddress) -> Result<ServerAddress> {
+    Ok(ServerAddress { retries: address.retries.or(Some(3)), ..address })
+}
@@ -0,0 +1,15 @@
+use emacs::{defun, Env, Result, Value};
+
+/// Define `t-reversed', a macro that reverses its arguments, so that `(t-reversed 1 2 -)' expands
+/// to `(- 2 1)'. It signals an error if there are no arguments.
+#[defun]
+fn define_reversed(env: &Env) -> Result<Value<'_>> {
+    env.define_macro("t-reversed", |env, forms| {
+        if forms.is_empty() {
+            return Err(env.wrong_type("consp", env.nil()?));
+        }
+        let mut forms = forms.to_vec();
+        forms.reverse();
+        env.list(&forms)
+    })
+}
@@ -0,0 +1,44 @@
+use emacs::{defun, Env, Result, Value, Vector, VectorMut};
+
+#[defun]
+fn sort_floats(mut v: VectorMut<'_, f64>) -> Result<()> {
+    v.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    v.commit()
+}
+
+/// Double each element of V in place. Signal an error before writing back if FAIL is non-nil.
+#[defun]
+fn double(mut v: VectorMut<'_, i64>, fail: Option<Value<'_>>) -> Result<()> {
+    for x in v.iter_mut() {
+        *x *= 2;
+    }
+    if fail.is_some() {
+        return Err(v.value().env.error("Failed before committing"));
+    }
+    v.commit()
+}
+
+#[defun]
+fn make<'e>(env: &'e Env, len: i64, init: Value<'e>) -> Result<Vector<'e>> {
+    env.make_vector(len as usize, init)
+}
+
+#[defun]
+fn get(v: Vector<'_>, i: i64) -> Result<Value<'_>> {
+    v.get(i as usize)
+}
+
+/// Swap the elements at indices I and J of V, returning V.
+#[defun]
+fn swap(v: Vector<'_>, i: i64, j: i64) -> Result<Vector<'_>> {
+    let (i, j) = (i as usize, j as usize);
+    let (x, y) = (v.get(i)?, v.get(j)?);
+    v.set(i, y)?;
+    v.set(j, x)?;
+    Ok(v)
+}
+
+#[defun]
+fn count_nils(v: Vector<'_>) -> Result<i64> {
+    Ok(v.iter()?.filter(|item| !item.env.is_not_nil(*item)).count() as i64)
+}
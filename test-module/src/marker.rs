@@ -0,0 +1,23 @@
+use emacs::{defun, Env, Result, Value};
+
+/// Return a new marker at POSITION in the current buffer.
+#[defun]
+fn make(env: &Env, position: i64) -> Result<Value<'_>> {
+    let marker = env.make_marker()?;
+    env.set_marker(marker, Some(position), None)
+}
+
+#[defun]
+fn detach(marker: Value<'_>) -> Result<Value<'_>> {
+    marker.env.set_marker(marker, None, None)
+}
+
+#[defun]
+fn position(marker: Value<'_>) -> Result<Option<i64>> {
+    marker.marker_position()
+}
+
+#[defun]
+fn buffer(marker: Value<'_>) -> Result<Option<Value<'_>>> {
+    marker.marker_buffer()
+}
@@ -0,0 +1,45 @@
+use emacs::{defun, Matrix, Result};
+
+/// Return the transpose of M.
+#[defun]
+fn transpose(m: Matrix<i64>) -> Result<Matrix<i64>> {
+    let (rows, cols) = m.dims();
+    Ok(Matrix((0..cols).map(|j| (0..rows).map(|i| m.0[i][j]).collect()).collect()))
+}
+
+/// Return the 3x3 identity matrix.
+#[defun]
+fn identity() -> Result<Matrix<i64>> {
+    let grid = [[1, 0, 0], [0, 1, 0], [0, 0, 1]];
+    Ok(Matrix::from_rows(&grid))
+}
+
+/// Return the lengths of the rows of M, which may be ragged.
+#[defun]
+fn row_lengths(m: Vec<Vec<i64>>) -> Result<Vec<i64>> {
+    Ok(m.iter().map(|row| row.len() as i64).collect())
+}
+
+/// Return the 3x3 matrix M, with each item multiplied by K.
+#[defun]
+fn scale(m: [[i64; 3]; 3], k: i64) -> Result<[[i64; 3]; 3]> {
+    let mut m = m;
+    for row in m.iter_mut() {
+        for item in row.iter_mut() {
+            *item *= k;
+        }
+    }
+    Ok(m)
+}
+
+/// Return ROWS as a matrix, without checking that they have the same length.
+#[defun]
+fn from_rows(rows: Vec<Vec<i64>>) -> Result<Matrix<i64>> {
+    Ok(Matrix(rows))
+}
+
+/// Return ROWS unchanged, converted to and from nested `Vec`s.
+#[defun]
+fn nested(rows: Vec<Vec<i64>>) -> Result<Vec<Vec<i64>>> {
+    Ok(rows)
+}
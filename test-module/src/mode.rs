@@ -0,0 +1,16 @@
+use emacs::{defun, Env, IntoLisp, Result, Value};
+
+/// Define `t-lang-mode', deriving from `prog-mode', whose setup sets `t--mode-setup' to the mode.
+/// Signal an error in the setup if FAIL is non-nil.
+#[defun]
+fn define_lang(env: &Env, fail: Option<Value<'_>>) -> Result<Value<'_>> {
+    let fail = fail.is_some();
+    env.define_derived_mode("t-lang-mode", "prog-mode", "Mode for testing.", move |env| {
+        let major_mode = env.call("symbol-value", &[env.intern("major-mode")?])?;
+        env.call("set", &[env.intern("t--mode-setup")?, major_mode])?;
+        if fail {
+            env.call("error", &["Failed in mode setup".into_lisp(env)?])?;
+        }
+        Ok(())
+    })
+}
@@ -0,0 +1,23 @@
+use emacs::{defun, Env, IntoLisp, Result, Value};
+
+/// Read S with a fresh obarray, returning a list of the form and the obarray.
+#[defun]
+fn read_isolated(env: &Env, s: String) -> Result<Value<'_>> {
+    let obarray = env.call("make-vector", &[1511.into_lisp(env)?, 0.into_lisp(env)?])?;
+    let read = env.intern("read")?;
+    let s = s.into_lisp(env)?;
+    let form = env.with_obarray(obarray, || env.funcall(read, &[s]))?;
+    env.list(&[form, obarray])
+}
+
+#[defun]
+fn intern_in<'e>(env: &'e Env, name: String, obarray: Value<'_>) -> Result<Value<'e>> {
+    env.intern_in(&name, obarray)
+}
+
+/// Panic with `obarray' bound to a fresh obarray.
+#[defun]
+fn panic_isolated(env: &Env) -> Result<()> {
+    let obarray = env.call("make-vector", &[1511.into_lisp(env)?, 0.into_lisp(env)?])?;
+    env.with_obarray(obarray, || panic!("Oops"))
+}
@@ -0,0 +1,14 @@
+use emacs::{defun, Outcome, Result};
+
+#[defun]
+fn parse(s: String) -> Result<Outcome<i64>> {
+    Ok(s.parse().map_err(|e| format!("{}", e)).into())
+}
+
+#[defun]
+fn describe(outcome: Outcome<i64>) -> Result<String> {
+    Ok(match outcome.0 {
+        Ok(i) => format!("ok: {}", i),
+        Err(message) => format!("error: {}", message),
+    })
+}
@@ -0,0 +1,19 @@
+use emacs::{defun, Env, Result, Value};
+
+/// Highlight the region between START and END in the current buffer with FACE.
+#[defun]
+fn highlight<'e>(env: &'e Env, start: i64, end: i64, face: Value<'_>) -> Result<Value<'e>> {
+    let overlay = env.make_overlay(start, end, None)?;
+    overlay.overlay_put("face", face)?;
+    Ok(overlay)
+}
+
+#[defun]
+fn face(overlay: Value<'_>) -> Result<Value<'_>> {
+    overlay.overlay_get("face")
+}
+
+#[defun]
+fn delete(overlay: Value<'_>) -> Result<()> {
+    overlay.delete_overlay()
+}
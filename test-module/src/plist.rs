@@ -0,0 +1,18 @@
+use emacs::{defun, Env, IntoLisp, KeywordArgs, Plist, Result, Value};
+
+#[defun]
+fn request_options(env: &Env, url: String, timeout: Option<f64>) -> Result<Value<'_>> {
+    Plist::new(env)
+        .put(":url", url)?
+        .put("method", "GET")?
+        .put(":timeout", timeout)?
+        .put("follow-redirects", true)?
+        .build()
+}
+
+/// Return OPTIONS as a plist, with `:forwarded` set to t.
+#[defun]
+fn forward<'e>(env: &'e Env, mut options: KeywordArgs<'e>) -> Result<Value<'e>> {
+    options.insert("forwarded".to_owned(), true.into_lisp(env)?);
+    options.into_lisp(env)
+}
@@ -0,0 +1,17 @@
+use emacs::{defun, Env, Result};
+
+/// Return the line and column of point, in the form "LINE:COLUMN".
+#[defun]
+fn status(env: &Env) -> Result<String> {
+    Ok(format!("{}:{}", env.line_number_at_pos(None)?, env.current_column()?))
+}
+
+#[defun]
+fn line_at(env: &Env, pos: i64) -> Result<i64> {
+    env.line_number_at_pos(Some(pos))
+}
+
+#[defun]
+fn count_lines(env: &Env, start: i64, end: i64) -> Result<i64> {
+    env.count_lines(start, end)
+}
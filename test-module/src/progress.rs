@@ -0,0 +1,14 @@
+use emacs::{defun, Env, Result};
+
+/// Count to N, reporting progress.
+#[defun]
+fn count(env: &Env, n: i64) -> Result<i64> {
+    let reporter = env.make_progress_reporter("Counting...", 0, n)?;
+    let mut count = 0;
+    for i in 1..=n {
+        count += 1;
+        reporter.update(i)?;
+    }
+    reporter.done()?;
+    Ok(count)
+}
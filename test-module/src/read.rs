@@ -0,0 +1,24 @@
+use emacs::{defun, Env, ErrorKind, Result, Value};
+
+#[defun]
+fn sexp(env: &Env, s: String) -> Result<Value<'_>> {
+    env.read(&s)
+}
+
+#[defun]
+fn eval(env: &Env, s: String) -> Result<Value<'_>> {
+    env.eval(&s)
+}
+
+/// Evaluate S, returning `read' or `eval' depending on where an error was signaled, or nil.
+#[defun]
+fn failed_phase(env: &Env, s: String) -> Result<Value<'_>> {
+    match env.eval(&s) {
+        Ok(_) => env.nil(),
+        Err(error) => match error.downcast_ref::<ErrorKind>() {
+            Some(ErrorKind::Read { .. }) => env.intern("read"),
+            Some(ErrorKind::Signal { .. }) => env.intern("eval"),
+            _ => Err(error),
+        },
+    }
+}
@@ -1,5 +1,8 @@
 use emacs::{defun, Result, Value};
+use lazy_static::lazy_static;
 use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 
 // TODO: Add tests for Mutex and RwLock, and more tests for RefCell.
 
@@ -29,3 +32,31 @@ fn unwrap_and_call(_: &i64, lambda: Value<'_>) -> Result<()> {
     lambda.env.call("funcall", &[lambda])?;
     Ok(())
 }
+
+lazy_static! {
+    static ref DROP_FLAGS: Mutex<Vec<Arc<AtomicBool>>> = Mutex::new(vec![]);
+}
+
+/// Sets its flag when dropped.
+struct Tracked(Arc<AtomicBool>);
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Return a new tracked object, whose drop is counted by `t/ref-cell-dropped-count'.
+#[defun(user_ptr)]
+fn make_tracked() -> Result<Tracked> {
+    let flag = Arc::new(AtomicBool::new(false));
+    DROP_FLAGS.lock().expect("Failed to lock drop flags").push(flag.clone());
+    Ok(Tracked(flag))
+}
+
+/// Return the number of tracked objects that were dropped.
+#[defun]
+fn dropped_count() -> Result<i64> {
+    let flags = DROP_FLAGS.lock().expect("Failed to lock drop flags");
+    Ok(flags.iter().filter(|flag| flag.load(Ordering::SeqCst)).count() as i64)
+}
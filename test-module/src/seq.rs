@@ -0,0 +1,34 @@
+use emacs::{defun, Env, Result, Value};
+
+#[defun]
+fn subseq(seq: Value<'_>, start: i64, end: Option<i64>) -> Result<Value<'_>> {
+    seq.subseq(start, end)
+}
+
+#[defun]
+fn nth_int(seq: Value<'_>, i: i64) -> Result<i64> {
+    seq.nth(i)
+}
+
+#[defun]
+fn substring_chars(s: Value<'_>, start: i64, end: Option<i64>) -> Result<String> {
+    s.substring_chars(start, end)
+}
+
+/// Return the list of integers from 0 below N, or signal an error if there are more than MAX.
+#[defun]
+fn range_bounded(env: &Env, n: Option<i64>, max: i64) -> Result<Value<'_>> {
+    match n {
+        Some(n) => env.list_from_bounded(0..n, max as usize),
+        None => env.list_from_bounded(0.., max as usize),
+    }
+}
+
+/// Sort RECTS, a list or vector of (WIDTH HEIGHT) lists, by decreasing area.
+#[defun]
+fn sort_by_area<'e>(env: &'e Env, rects: Value<'e>) -> Result<Value<'e>> {
+    env.sort(rects, |_, a, b| {
+        let (a, b): ((i64, i64), (i64, i64)) = (a.into_rust()?, b.into_rust()?);
+        Ok((b.0 * b.1).cmp(&(a.0 * a.1)))
+    })
+}
@@ -0,0 +1,32 @@
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use emacs::{defun, Result};
+
+/// Return a counter starting from N, which can be shared with background threads.
+#[defun]
+fn make_counter(n: i64) -> Result<Arc<Mutex<i64>>> {
+    Ok(Arc::new(Mutex::new(n)))
+}
+
+/// Return the current value of COUNTER.
+#[defun(direct(counter))]
+fn get(counter: &Arc<Mutex<i64>>) -> Result<i64> {
+    Ok(*counter.lock().expect("Failed to lock counter"))
+}
+
+/// Increment COUNTER from COUNT background threads, waiting for them to finish.
+#[defun]
+fn inc_in_threads(counter: Arc<Mutex<i64>>, count: i64) -> Result<i64> {
+    let handles: Vec<_> = (0..count)
+        .map(|_| {
+            let counter = counter.clone();
+            thread::spawn(move || *counter.lock().expect("Failed to lock counter") += 1)
+        })
+        .collect();
+    for handle in handles {
+        handle.join().expect("Failed to join thread");
+    }
+    let value = *counter.lock().expect("Failed to lock counter");
+    Ok(value)
+}
@@ -0,0 +1,25 @@
+use emacs::{defun, Env, Result, Value};
+
+#[defun]
+fn put_version(env: &Env, symbol: String, version: i64) -> Result<()> {
+    env.put(&symbol, "t--version", version)
+}
+
+#[defun]
+fn get_version(env: &Env, symbol: String) -> Result<Option<i64>> {
+    env.get(&symbol, "t--version")
+}
+
+#[defun]
+fn core(env: &Env, which: String) -> Result<Value<'_>> {
+    match which.as_str() {
+        "nil" => env.nil(),
+        _ => env.t(),
+    }
+}
+
+/// Return t, converted from `bool`.
+#[defun]
+fn truth() -> Result<bool> {
+    Ok(true)
+}
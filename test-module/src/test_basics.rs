@@ -1,5 +1,9 @@
+use std::ffi::CString;
+use std::thread;
+
 use emacs::{defun, CallEnv, Env, IntoLisp, Result, Value};
 use emacs::func::Manage;
+use emacs::raw::emacs_value;
 
 use super::MODULE_PREFIX;
 
@@ -30,6 +34,110 @@ fn to_lowercase_or_nil(env: &Env, input: Option<String>) -> Result<Value<'_>> {
     output.as_ref().into_lisp(env)
 }
 
+/// Greet NAME, ending with PUNCT (default "."), repeated TIMES times (default 1).
+#[defun(mod_in_name = false, optional)]
+fn greet(name: String, punct: Option<String>, times: Option<i64>) -> Result<String> {
+    let greeting = format!("Hello, {}{}", name, punct.as_ref().map_or(".", String::as_str));
+    Ok(vec![greeting; times.unwrap_or(1) as usize].join(" "))
+}
+
+/// Like `greet', but with strict argument checking.
+#[defun(mod_in_name = false, strict_args, optional)]
+fn greet_strictly(name: String, punct: Option<String>) -> Result<String> {
+    Ok(format!("Hello, {}{}", name, punct.as_ref().map_or(".", String::as_str)))
+}
+
+/// Return the sum of NUMBERS.
+#[defun(mod_in_name = false, rest)]
+fn sum(numbers: Vec<i64>) -> Result<i64> {
+    Ok(numbers.iter().sum())
+}
+
+/// Return a list of SEPARATOR, then the number of ARGS, then ARGS in reverse order.
+#[defun(mod_in_name = false, rest, optional)]
+fn count_rest<'e>(
+    env: &'e Env,
+    separator: String,
+    limit: Option<i64>,
+    args: &[Value<'e>],
+) -> Result<Value<'e>> {
+    let mut items = vec![separator.into_lisp(env)?, limit.into_lisp(env)?];
+    items.push((args.len() as i64).into_lisp(env)?);
+    items.extend(args.iter().rev());
+    env.list(&items)
+}
+
+/// Return the address of HOST and PORT, which default to "localhost" and 8080.
+#[defun(defaults(host = "localhost".to_owned(), port = 8080), mod_in_name = false)]
+fn address(scheme: String, host: String, port: i64) -> Result<String> {
+    Ok(format!("{}://{}:{}", scheme, host, port))
+}
+
+/// Return X, which is not optional since it is not trailing.
+#[defun(mod_in_name = false, optional)]
+fn non_trailing_option(x: Option<i64>, y: i64) -> Result<Option<i64>> {
+    Ok(x.map(|x| x + y))
+}
+
+/// Return t if N is even.
+#[defun(full_name = "t-even?")]
+fn even(n: i64) -> Result<bool> {
+    Ok(n % 2 == 0)
+}
+
+/// Return (F (F X)).
+#[defun(mod_in_name = false)]
+fn call_twice<'e>(f: Value<'e>, x: Value<'e>) -> Result<Value<'e>> {
+    let env = f.env;
+    let y = env.funcall(f, &[x])?;
+    env.funcall(f, &[y])
+}
+
+/// Apply F to the list ARGS.
+#[defun(mod_in_name = false)]
+fn apply_list<'e>(f: Value<'e>, args: Value<'e>) -> Result<Value<'e>> {
+    f.env.apply(f, args)
+}
+
+/// Display TEXT in the echo area, returning it.
+#[defun(mod_in_name = false)]
+fn message(env: &Env, text: String) -> Result<Value<'_>> {
+    env.message(&text)
+}
+
+/// Format NAME and VALUE as an assignment.
+#[defun(mod_in_name = false)]
+fn format_assignment<'e>(env: &'e Env, name: String, value: Value<'e>) -> Result<Value<'e>> {
+    env.format("%s = %S", &[name.into_lisp(env)?, value])
+}
+
+/// Intern NAME through the raw module API, returning the symbol without conversion.
+#[defun(mod_in_name = false, raw_return)]
+fn raw_intern(env: &Env, name: String) -> Result<emacs_value> {
+    let name = CString::new(name)?;
+    unsafe {
+        let raw = env.raw();
+        let intern = (*raw).intern.unwrap();
+        Ok(intern(raw, name.as_ptr()))
+    }
+}
+
+/// Return t if using the `Env` on another thread is caught (in debug builds).
+#[defun(mod_in_name = false)]
+fn env_on_other_thread_caught(env: &Env) -> Result<bool> {
+    if !cfg!(debug_assertions) {
+        // This would actually call into Emacs from another thread.
+        return Ok(true);
+    }
+    let ptr = env as *const Env as usize;
+    let result = thread::spawn(move || {
+        let env = unsafe { &*(ptr as *const Env) };
+        env.intern("nil").map(|_| ())
+    })
+    .join();
+    Ok(result.is_err())
+}
+
 pub fn init(env: &Env) -> Result<()> {
     using_fset(env)?;
 
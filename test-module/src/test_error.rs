@@ -1,5 +1,9 @@
-use emacs::{defun, CallEnv, Env, Result, Value};
-use emacs::ErrorKind::{self, Signal, Throw};
+use std::ffi::CString;
+use std::fs;
+use std::num::ParseIntError;
+
+use emacs::{defun, CallEnv, DataSchema, Env, Error, IntoLisp, Result, StandardCondition, Value};
+use emacs::ErrorKind::{self, Signal};
 
 use super::MODULE_PREFIX;
 
@@ -26,9 +30,7 @@ fn get_type(f: Value<'_>) -> Result<Value<'_>> {
     match env.call("funcall", &[f]) {
         Err(error) => {
             if let Some(&Signal { ref symbol, .. }) = error.downcast_ref::<ErrorKind>() {
-                unsafe {
-                    return Ok(symbol.value(env));
-                }
+                return Ok(symbol.bind(env));
             }
             Err(error)
         }
@@ -38,19 +40,133 @@ fn get_type(f: Value<'_>) -> Result<Value<'_>> {
 
 #[defun(mod_in_name = false, name = "error:catch")]
 fn catch<'e>(expected_tag: Value<'e>, f: Value<'e>) -> Result<Value<'e>> {
-    let env = expected_tag.env;
-    match env.call("funcall", &[f]) {
-        Err(error) => {
-            if let Some(&Throw { ref tag, ref value }) = error.downcast_ref::<ErrorKind>() {
-                unsafe {
-                    if env.eq(tag.value(env), expected_tag) {
-                        return Ok(value.value(env));
-                    }
-                }
-            }
-            Err(error)
-        }
-        v => v,
+    expected_tag.env.catch(expected_tag, |env| env.call("funcall", &[f]))
+}
+
+/// Call F, catching a throw to TAG, whose value must be an integer.
+#[defun(mod_in_name = false, name = "error:catch-integer")]
+fn catch_integer<'e>(tag: Value<'e>, f: Value<'e>) -> Result<i64> {
+    tag.env.catch(tag, |env| {
+        env.call("funcall", &[f])?;
+        Ok(-1)
+    })
+}
+
+#[defun(mod_in_name = false, name = "error:checked-div")]
+fn checked_div(env: &Env, x: i64, y: i64) -> Result<i64> {
+    if y == 0 {
+        return Err(env.signal("arith-error", &[x.into_lisp(env)?]));
+    }
+    if x < 0 {
+        return Err(env.error("Negative dividend"));
+    }
+    Ok(x / y)
+}
+
+#[defun(mod_in_name = false, name = "error:call-with-timeout")]
+fn call_with_timeout<'e>(f: Value<'e>, secs: f64) -> Result<Value<'e>> {
+    f.env.call_with_timeout("funcall", &[f], secs)
+}
+
+#[defun(mod_in_name = false, name = "error:check-format")]
+fn check_format(value: Value<'_>) -> Result<()> {
+    Err(value.env.wrong_type("my-valid-format-p", value))
+}
+
+#[defun(mod_in_name = false, name = "error:strict-add", strict_args)]
+fn strict_add(x: i64, y: i64) -> Result<i64> {
+    Ok(x + y)
+}
+
+#[defun(mod_in_name = false, name = "error:signal-standard")]
+fn signal_standard(env: &Env, name: String) -> Result<()> {
+    let condition = match name.as_str() {
+        "beginning-of-buffer" => StandardCondition::BeginningOfBuffer,
+        "end-of-buffer" => StandardCondition::EndOfBuffer,
+        "buffer-read-only" => StandardCondition::BufferReadOnly,
+        "mark-inactive" => StandardCondition::MarkInactive,
+        "quit" => StandardCondition::Quit,
+        _ => StandardCondition::ScanError { message: name, start: 1, end: 5 },
+    };
+    Err(env.signal_standard(condition))
+}
+
+#[defun(mod_in_name = false, name = "error:read-file")]
+fn read_file(env: &Env, path: String) -> Result<String> {
+    std::fs::read_to_string(&path)
+        .map_err(|error| env.file_error("Reading file", &path, &error.to_string()))
+}
+
+#[defun(mod_in_name = false, name = "error:read-file-unmapped")]
+fn read_file_unmapped(path: String) -> Result<String> {
+    Ok(fs::read_to_string(path)?)
+}
+
+fn map_parse_error(env: &Env, error: &Error) -> Option<Error> {
+    let error = error.downcast_ref::<ParseIntError>()?;
+    Some(env.signal_typed("t--parse-error", error.to_string()))
+}
+
+#[defun(mod_in_name = false, name = "error:parse-int")]
+fn parse_int(s: String) -> Result<i64> {
+    Ok(s.parse()?)
+}
+
+/// Define `t--timeout`, whose data has the form `(SECONDS . HOST)`.
+#[defun(mod_in_name = false, name = "error:define-timeout")]
+fn define_timeout(env: &Env) -> Result<Value<'_>> {
+    let schema = DataSchema::Cons(
+        Box::new(DataSchema::Predicate("integerp")),
+        Box::new(DataSchema::Predicate("stringp")),
+    );
+    env.define_typed_error("t--timeout", "Timed out", "error", schema)
+}
+
+#[defun(mod_in_name = false, name = "error:signal-timeout")]
+fn signal_timeout(data: Value<'_>) -> Result<()> {
+    Err(data.env.signal_typed("t--timeout", data))
+}
+
+/// Return the square root of X, signaling `arith-error` if X is negative. The data is computed by
+/// calling DUMP.
+#[defun(mod_in_name = false, name = "error:checked-sqrt")]
+fn checked_sqrt<'e>(env: &'e Env, x: f64, dump: Value<'e>) -> Result<f64> {
+    if x >= 0.0 {
+        return Ok(x.sqrt());
+    }
+    Err(env.signal_with("arith-error", || env.call("funcall", &[dump])))
+}
+
+/// Call F, returning `(:recovered . DATA)` if it signals CONDITION.
+#[defun(mod_in_name = false, name = "error:with-restart")]
+fn with_restart<'e>(env: &'e Env, condition: String, f: Value<'e>) -> Result<Value<'e>> {
+    env.with_restart(
+        &condition,
+        || env.call("funcall", &[f]),
+        |data| env.call("cons", &[env.intern(":recovered")?, data]),
+    )
+}
+
+/// Call `(car 1)` through raw module functions, returning the symbol of the pending signal.
+#[defun(mod_in_name = false, name = "error:check-exit")]
+fn check_exit(env: &Env) -> Result<Value<'_>> {
+    env.check_exit()?;
+    let name = CString::new("car")?;
+    unsafe {
+        let raw = env.raw();
+        let intern = (*raw).intern.unwrap();
+        let make_integer = (*raw).make_integer.unwrap();
+        let funcall = (*raw).funcall.unwrap();
+        let car = intern(raw, name.as_ptr());
+        let mut args = [make_integer(raw, 1)];
+        funcall(raw, car, 1, args.as_mut_ptr());
+    }
+    match env.check_exit() {
+        Err(error) => match error.downcast_ref::<ErrorKind>() {
+            Some(&Signal { ref symbol, .. }) => Ok(symbol.bind(env)),
+            _ => Err(error),
+        },
+        Ok(()) => env.intern("nil"),
     }
 }
 
@@ -61,6 +177,9 @@ fn parse_arg(env: &CallEnv) -> Result<String> {
 }
 
 pub fn init(env: &Env) -> Result<()> {
+    env.define_error("t--parse-error", "Invalid integer", "error")?;
+    env.add_error_mapper(map_parse_error);
+
     emacs::export_functions! {
         env, format!("{}error:", *MODULE_PREFIX), {
             "parse-arg"   => (parse_arg   , 2..5),
@@ -92,9 +92,7 @@ fn gc_after_catching_1<'e>(env: &'e Env, f: Value<'_>) -> Result<Value<'e>> {
         match env.call("funcall", &[f]) {
             Err(error) => {
                 if let Some(&Signal { ref data, .. }) = error.downcast_ref::<ErrorKind>() {
-                    unsafe {
-                        return Ok(data.value(env));
-                    }
+                    return Ok(data.bind(env));
                 }
                 Err(error)
             }
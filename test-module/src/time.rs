@@ -0,0 +1,42 @@
+use std::fs;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use emacs::{defun, Result};
+
+#[defun]
+fn round_trip(time: SystemTime) -> Result<SystemTime> {
+    Ok(time)
+}
+
+/// Return the modification time of the file at PATH.
+#[defun]
+fn file_mtime(path: String) -> Result<SystemTime> {
+    Ok(fs::metadata(path)?.modified()?)
+}
+
+#[defun]
+fn add(time: SystemTime, duration: Duration) -> Result<SystemTime> {
+    Ok(time + duration)
+}
+
+#[defun]
+fn duration_secs(duration: Duration) -> Result<f64> {
+    Ok(duration.as_secs() as f64 + f64::from(duration.subsec_nanos()) * 1e-9)
+}
+
+#[defun]
+fn millis(n: i64) -> Result<Duration> {
+    Ok(Duration::from_millis(n as u64))
+}
+
+/// Return the time SECS seconds and NANOS nanoseconds after the epoch.
+#[defun]
+fn from_parts(secs: i64, nanos: i64) -> Result<SystemTime> {
+    Ok(UNIX_EPOCH + Duration::new(secs as u64, nanos as u32))
+}
+
+/// Return the nanoseconds of TIME, in addition to its whole seconds since the epoch.
+#[defun]
+fn subsec_nanos(time: SystemTime) -> Result<i64> {
+    Ok(i64::from(time.duration_since(UNIX_EPOCH)?.subsec_nanos()))
+}
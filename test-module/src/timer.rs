@@ -0,0 +1,20 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use emacs::{defun, Env, Result, Value};
+
+static FIRED: AtomicUsize = AtomicUsize::new(0);
+
+/// Set up an idle timer that counts how many times it fires.
+#[defun]
+fn schedule(env: &Env, secs: f64, repeat: Option<Value<'_>>) -> Result<Value<'_>> {
+    env.run_with_idle_timer(secs, repeat.is_some(), |_| {
+        FIRED.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    })
+}
+
+/// Return the number of times the timers set up by `t/timer-schedule' fired.
+#[defun]
+fn fired() -> Result<i64> {
+    Ok(FIRED.load(Ordering::SeqCst) as i64)
+}
@@ -0,0 +1,6 @@
+use emacs::{defun, Result};
+
+#[defun(trace)]
+fn square(x: i64) -> Result<i64> {
+    Ok(x * x)
+}
@@ -0,0 +1,71 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+
+use emacs::{defun, Env, EqualValue, IntoLisp, Result, Value};
+
+/// Return a list of symbols naming what kind of callable X is.
+#[defun]
+fn classify<'e>(env: &'e Env, x: Value<'_>) -> Result<Value<'e>> {
+    let mut kinds = vec![];
+    if x.is_function()? {
+        kinds.push(env.intern("function")?);
+    }
+    if x.is_command()? {
+        kinds.push(env.intern("command")?);
+    }
+    if x.is_macro()? {
+        kinds.push(env.intern("macro")?);
+    }
+    env.list(&kinds)
+}
+
+#[defun]
+fn lisp_hash(x: Value<'_>) -> Result<i64> {
+    x.lisp_hash()
+}
+
+/// Return the number of distinct elements of VECTOR, according to `equal`.
+#[defun]
+fn count_distinct(vector: Value<'_>) -> Result<i64> {
+    let env = vector.env;
+    let len: i64 = env.call("length", &[vector])?.into_rust()?;
+    let mut set = HashSet::new();
+    for i in 0..len {
+        let element = env.call("aref", &[vector, i.into_lisp(env)?])?;
+        set.insert(EqualValue::new(element)?);
+    }
+    Ok(set.len() as i64)
+}
+
+thread_local! {
+    static SEEN: RefCell<HashMap<EqualValue, i64>> = RefCell::new(HashMap::new());
+}
+
+/// Return the number of times a value `equal' to X has been passed to this function.
+#[defun]
+fn count_seen(x: Value<'_>) -> Result<i64> {
+    let key = EqualValue::new(x)?;
+    Ok(SEEN.with(|seen| {
+        let mut seen = seen.borrow_mut();
+        let count = seen.entry(key).or_insert(0);
+        *count += 1;
+        *count
+    }))
+}
+
+/// Compare A and B, returning a list of symbols naming the comparisons they satisfy, among `nil`
+/// (A is nil), `eq` and `equal`.
+#[defun]
+fn compare<'e>(env: &'e Env, a: Value<'e>, b: Value<'e>) -> Result<Value<'e>> {
+    let mut results = vec![];
+    if a.is_nil() {
+        results.push(env.intern("nil")?);
+    }
+    if a.eq(b) {
+        results.push(env.intern("eq")?);
+    }
+    if a.equal(b)? {
+        results.push(env.intern("equal")?);
+    }
+    env.list(&results)
+}
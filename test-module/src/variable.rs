@@ -0,0 +1,42 @@
+use emacs::{defun, Env, IntoLisp, Result, Value};
+
+/// Watch SYMBOL, storing the arguments of its last change in LOG.
+#[defun]
+fn watch(symbol: Value<'_>, log: Value<'_>) -> Result<()> {
+    let env = symbol.env;
+    let symbol: String = env.call("symbol-name", &[symbol])?.into_rust()?;
+    let log: String = env.call("symbol-name", &[log])?.into_rust()?;
+    env.add_variable_watcher(&symbol, move |env, symbol, newval, operation, where_| {
+        let args = env.list(&[symbol, newval, operation, where_])?;
+        env.call("set", &[env.intern(&log)?, args])?;
+        Ok(())
+    })?;
+    Ok(())
+}
+
+/// Call F with the variable SYMBOL bound to VALUE.
+#[defun]
+fn call_with_bound<'e>(symbol: Value<'e>, value: Value<'e>, f: Value<'e>) -> Result<Value<'e>> {
+    let env = symbol.env;
+    let symbol: String = env.call("symbol-name", &[symbol])?.into_rust()?;
+    env.with_var_bound(&symbol, value, || env.call("funcall", &[f]))
+}
+
+/// Insert TEXT into the current buffer, even if it is read-only. Signal an error afterwards if
+/// FAIL is non-nil.
+#[defun]
+fn insert_read_only(env: &Env, text: String, fail: Option<Value<'_>>) -> Result<()> {
+    env.with_inhibit_read_only(|| {
+        env.call("insert", &[text.into_lisp(env)?])?;
+        if fail.is_some() {
+            env.call("error", &["Failed after inserting".into_lisp(env)?])?;
+        }
+        Ok(())
+    })
+}
+
+/// Panic with `inhibit-read-only' temporarily set to t.
+#[defun]
+fn panic_read_only(env: &Env) -> Result<()> {
+    env.with_inhibit_read_only(|| panic!("Oops"))
+}
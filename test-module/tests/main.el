@@ -1,4 +1,6 @@
 (require 'subr-x)
+(require 'cl-lib)
+(require 'ert-x)
 
 (when-let ((module-path (getenv "MODULE_DIR")))
   (add-to-list 'load-path module-path))
@@ -21,6 +23,14 @@
     (should (eq (t/identity x) x))
     (should (equal (documentation #'t/identity) "Return the input (not a copy)."))))
 
+(ert-deftest defun::multi-line-doc ()
+  (should (equal (t/prefix "a") "\\\ta"))
+  (should (equal (documentation 't/prefix t)
+                 (concat "Return S with a \"prefix\" added.\n"
+                         "\n"
+                         "The prefix is the literal `\\\\', followed by a tab:\n"
+                         "  (t/prefix \"a\") ; => \"\\\\\\ta\""))))
+
 (ert-deftest convert::string ()
   (should (equal (t/to-uppercase "abc") "ABC")))
 
@@ -29,6 +39,98 @@
   (should (equal (t/to-lowercase-or-nil nil) nil))
   (should-error (t/to-lowercase-or-nil 1) :type 'wrong-type-argument))
 
+(ert-deftest defun::optional-args ()
+  (should (equal (func-arity 't/greet) '(1 . 3)))
+  (should (equal (t/greet "a") "Hello, a."))
+  (should (equal (t/greet "a" "!") "Hello, a!"))
+  (should (equal (t/greet "a" nil 2) "Hello, a. Hello, a."))
+  (should (equal (t/greet "a" "!" 2) "Hello, a! Hello, a!"))
+  (should-error (t/greet) :type 'wrong-number-of-arguments)
+  (should-error (t/greet "a" "!" 2 3) :type 'wrong-number-of-arguments)
+  (should-error (t/greet "a" 1) :type 'wrong-type-argument)
+  (should (equal (t/greet-strictly "a") "Hello, a."))
+  (should (equal (should-error (t/greet-strictly "a" 1) :type 'rust-wrong-type-argument)
+                 '(rust-wrong-type-argument punct stringp 1)))
+  (should (equal (func-arity 't/non-trailing-option) '(2 . 2)))
+  (should (= (t/non-trailing-option 1 2) 3))
+  ;; Without the option `optional', a trailing `Option<T>' is still required.
+  (should (equal (func-arity 't/to-lowercase-or-nil) '(1 . 1))))
+
+(ert-deftest defun::default-args ()
+  (should (equal (func-arity 't/address) '(1 . 3)))
+  (should (equal (t/address "http") "http://localhost:8080"))
+  (should (equal (t/address "http" "example.com") "http://example.com:8080"))
+  (should (equal (t/address "http" "example.com" 80) "http://example.com:80"))
+  (should (equal (t/address "http" nil 80) "http://localhost:80"))
+  (should-error (t/address "http" "example.com" "80") :type 'wrong-type-argument))
+
+(ert-deftest defun::rest-args ()
+  (should (equal (func-arity 't/sum) '(0 . many)))
+  (should (= (t/sum) 0))
+  (should (= (t/sum 1 2 3 4) 10))
+  (should (= (apply #'t/sum (number-sequence 1 100)) 5050))
+  (should (equal (should-error (t/sum 1 2 "3") :type 'wrong-type-argument)
+                 '(rust-wrong-type-argument numbers 2 integerp "3")))
+  (should (equal (func-arity 't/count-rest) '(1 . many)))
+  (should (equal (t/count-rest "-") '("-" nil 0)))
+  (should (equal (t/count-rest "-" 5) '("-" 5 0)))
+  (should (equal (t/count-rest "-" nil 'a "b" 3) '("-" nil 3 3 "b" a))))
+
+(ert-deftest defun::full-name ()
+  (should (fboundp 't-even?))
+  (should-not (fboundp 't/even))
+  (should-not (fboundp 't/t-even?))
+  (should (eq (t-even? 4) t))
+  (should (eq (t-even? 3) nil))
+  (should (equal (documentation 't-even?) "Return t if N is even.")))
+
+(ert-deftest call::message-and-format ()
+  (should (equal (t/message "100% done") "100% done"))
+  (should (equal (t/message "%s %d") "%s %d"))
+  (should (equal (t/format-assignment "x" "a\"b") "x = \"a\\\"b\""))
+  (should (equal (t/format-assignment "y" '(1 2)) "y = (1 2)")))
+
+(ert-deftest call::function-values ()
+  (should (= (t/call-twice #'1+ 1) 3))
+  (should (= (t/call-twice (lambda (x) (* x x)) 3) 81))
+  (let ((n 10))
+    (should (= (t/call-twice (lambda (x) (+ x n)) 1) 21)))
+  (should (= (t/apply-list #'+ '(1 2 3)) 6))
+  (should (equal (t/apply-list (lambda (&rest xs) (reverse xs)) '(a b)) '(b a)))
+  (should (= (t/apply-list #'+ nil) 0))
+  (should-error (t/call-twice 5 1) :type 'invalid-function)
+  (should-error (t/apply-list #'car '(1)) :type 'wrong-type-argument))
+
+(ert-deftest convert::raw-return ()
+  (should (eq (t/raw-intern "t--raw") 't--raw))
+  (should (eq (t/raw-intern "nil") nil))
+  (should-error (t/raw-intern "a\000b") :type 'rust-error))
+
+(ert-deftest convert::outcome ()
+  (should (equal (t/outcome-parse "12") '(:ok . 12)))
+  (should (equal (t/outcome-parse "x") '(:error . "invalid digit found in string")))
+  (should (equal (t/outcome-describe (t/outcome-parse "12")) "ok: 12"))
+  (should (equal (t/outcome-describe '(:error . "bad")) "error: bad"))
+  (should (equal (should-error (t/outcome-describe '(:maybe . 1)) :type 'wrong-type-argument)
+                 '(wrong-type-argument outcomep (:maybe . 1))))
+  (should-error (t/outcome-describe 5) :type 'wrong-type-argument))
+
+(ert-deftest convert::base64 ()
+  (should (equal (t/bytes-base64-roundtrip)
+                 (base64-encode-string (apply #'unibyte-string (number-sequence 0 255)) t)))
+  (should (= (t/bytes-base64-decoded-len "AP8=") 2))
+  (should-error (t/bytes-base64-decoded-len "!!!")))
+
+(ert-deftest convert::byte-vector ()
+  (should (equal (t/bytes-invert [0 1 255]) [255 254 0]))
+  (should (equal (t/bytes-invert (t/bytes-invert [7 128])) [7 128]))
+  (should (equal (t/bytes-invert []) []))
+  (should (equal (should-error (t/bytes-invert [1 256]) :type 'args-out-of-range)
+                 '(args-out-of-range 256 0 255)))
+  (should-error (t/bytes-invert [-1]) :type 'args-out-of-range)
+  (should (equal (should-error (t/bytes-invert '(1 2)) :type 'wrong-type-argument)
+                 '(wrong-type-argument vectorp (1 2)))))
+
 (ert-deftest error::propagating-signal ()
   (should-error (t/error:lisp-divide 1 0) :type 'arith-error))
 
@@ -50,11 +152,137 @@
                 msg)))
   (should-error (t/error:catch 'ball
                                (lambda () (throw 'knife "Watch out!")))
-                :type 'no-catch))
+                :type 'no-catch)
+  (should (eq (catch 'knife
+                (t/error:catch 'ball (lambda () (throw 'knife 'passed))))
+              'passed))
+  (should-error (t/error:catch 'ball (lambda () (error "Not a throw"))) :type 'error)
+  (should (= (t/error:catch-integer 'ball (lambda () (throw 'ball 5))) 5))
+  (should (= (t/error:catch-integer 'ball #'ignore) -1))
+  (should-error (t/error:catch-integer 'ball (lambda () (throw 'ball "5")))
+                :type 'wrong-type-argument))
+
+(ert-deftest error::signal-and-error ()
+  (should (= (t/error:checked-div 7 2) 3))
+  (should (equal (should-error (t/error:checked-div 7 0) :type 'arith-error)
+                 '(arith-error 7)))
+  (let ((data (should-error (t/error:checked-div -7 2) :type 'error)))
+    (should (equal data '(error "Negative dividend")))
+    (should (equal (error-message-string data) "Negative dividend"))))
+
+(ert-deftest error::call-with-timeout ()
+  (should (= (t/error:call-with-timeout (lambda () 42) 1.0) 42))
+  (should (equal (should-error (t/error:call-with-timeout (lambda () (sleep-for 5)) 0.1)
+                               :type 'rust-timeout)
+                 '(rust-timeout 0.1)))
+  (should-error (t/error:call-with-timeout (lambda () (sleep-for 5)) 0.1) :type 'rust-error)
+  (should-error (t/error:call-with-timeout (lambda () (error "Fast failure")) 1.0) :type 'error))
+
+(ert-deftest error::wrong-type ()
+  (should (equal (should-error (t/error:check-format "abc") :type 'wrong-type-argument)
+                 '(wrong-type-argument my-valid-format-p "abc"))))
+
+(ert-deftest error::signal-standard ()
+  (should (equal (should-error (t/error:signal-standard "end-of-buffer") :type 'end-of-buffer)
+                 '(end-of-buffer)))
+  (should (equal (should-error (t/error:signal-standard "beginning-of-buffer")
+                               :type 'beginning-of-buffer)
+                 '(beginning-of-buffer)))
+  (should-error (t/error:signal-standard "mark-inactive") :type 'mark-inactive)
+  (with-temp-buffer
+    (should (equal (should-error (t/error:signal-standard "buffer-read-only")
+                                 :type 'buffer-read-only)
+                   (list 'buffer-read-only (current-buffer)))))
+  (should (equal (should-error (t/error:signal-standard "Unbalanced parentheses") :type 'scan-error)
+                 '(scan-error "Unbalanced parentheses" 1 5)))
+  (should (eq (condition-case nil
+                  (t/error:signal-standard "quit")
+                (error 'error)
+                (quit 'quit))
+              'quit)))
+
+(ert-deftest error::file-error ()
+  (let* ((path (make-temp-name (expand-file-name "t-missing-" temporary-file-directory)))
+         (data (should-error (t/error:read-file path) :type 'file-error)))
+    (should (equal (length data) 4))
+    (should (equal (nth 1 data) "Reading file"))
+    (should (stringp (nth 2 data)))
+    (should (equal (nth 3 data) path))
+    (should (string-prefix-p "Reading file: " (error-message-string data))))
+  (let ((path (make-temp-file "t-file-error-")))
+    (unwind-protect
+        (progn
+          (with-temp-file path (insert "abc"))
+          (should (equal (t/error:read-file path) "abc")))
+      (delete-file path))))
+
+(ert-deftest error::mapped ()
+  (let* ((path (make-temp-name (expand-file-name "t-missing-" temporary-file-directory)))
+         (data (should-error (t/error:read-file-unmapped path) :type 'file-missing)))
+    (should (eq (car data) 'file-missing))
+    (should (equal (nth 1 data) "Rust I/O error"))
+    (should (stringp (nth 2 data))))
+  (should-error (t/error:read-file-unmapped temporary-file-directory) :type 'file-error)
+  (should (= (t/error:parse-int "42") 42))
+  (should (equal (should-error (t/error:parse-int "x") :type 't--parse-error)
+                 '(t--parse-error . "invalid digit found in string")))
+  (should-error (t/error:parse-int "x") :type 'error))
+
+(ert-deftest error::signal-typed ()
+  (t/error:define-timeout)
+  (should (equal (should-error (t/error:signal-timeout '(5 . "example.com")) :type 't--timeout)
+                 '(t--timeout 5 . "example.com")))
+  ;; Tests are run against a debug build, so wrong-shaped data is a panic.
+  (should-error (t/error:signal-timeout '("5" . "example.com")) :type 'rust-panic)
+  (should-error (t/error:signal-timeout 5) :type 'rust-panic))
+
+(ert-deftest error::signal-with ()
+  (let* ((calls 0)
+         (dump (lambda () (setq calls (1+ calls)) (list :calls calls))))
+    (should (= (t/error:checked-sqrt 4.0 dump) 2.0))
+    (should (= calls 0))
+    (should (equal (should-error (t/error:checked-sqrt -1.0 dump) :type 'arith-error)
+                   '(arith-error :calls 1)))
+    (should (= calls 1))
+    (should (equal (should-error (t/error:checked-sqrt -1.0 (lambda () (car 1)))
+                                 :type 'wrong-type-argument)
+                   '(wrong-type-argument listp 1)))))
+
+(ert-deftest error::with-restart ()
+  (should (equal (t/error:with-restart "arith-error" (lambda () (/ 6 2))) 3))
+  (should (equal (t/error:with-restart "arith-error" (lambda () (/ 1 0))) '(:recovered)))
+  (should (equal (t/error:with-restart "file-error" (lambda () (signal 'file-error '("x"))))
+                 '(:recovered "x")))
+  ;; Sub-types are matched.
+  (should (equal (t/error:with-restart "error" (lambda () (car 1)))
+                 '(:recovered listp 1)))
+  (should-error (t/error:with-restart "arith-error" (lambda () (car 1)))
+                :type 'wrong-type-argument)
+  (should (equal (catch 't--tag
+                   (t/error:with-restart "error" (lambda () (throw 't--tag 5))))
+                 5)))
+
+(ert-deftest error::strict-args ()
+  (should (= (t/error:strict-add 1 2) 3))
+  (should (equal (should-error (t/error:strict-add 1 "2") :type 'wrong-type-argument)
+                 '(rust-wrong-type-argument y integerp "2")))
+  (should (equal (should-error (t/error:strict-add nil 2) :type 'rust-wrong-type-argument)
+                 '(rust-wrong-type-argument x integerp nil))))
+
+(ert-deftest error::check-exit ()
+  (should (eq (t/error:check-exit) 'wrong-type-argument)))
 
 (ert-deftest error::panic-parsing-arg ()
   (should-error (t/error:parse-arg 5 "1") :type 'rust-panic))
 
+(ert-deftest error::panic-condition ()
+  (should (memq 'rust-panic (get 't-panic 'error-conditions)))
+  (should (eq (car (should-error (t/error:parse-arg 5 "1") :type 't-panic)) 't-panic))
+  (should (eq (car (should-error (t/error:signal-timeout 5) :type 'rust-panic)) 't-panic)))
+
+(ert-deftest env::thread-check ()
+  (should (t/env-on-other-thread-caught)))
+
 (ert-deftest function::create ()
   (let ((dec (t/make-dec)))
     (should (= (funcall dec 9) 8))
@@ -77,6 +305,13 @@
     (should (equal (t/sum x y)
                    (+ x y)))))
 
+(ert-deftest function::generic ()
+  (should (= (t/generic-clamp-i64 15 0 10) 10))
+  (should (= (t/generic-clamp-f64 -0.5 0.0 1.0) 0.0))
+  (should (equal (t/generic-clamp-string "m" "a" "k") "k"))
+  (should-error (t/generic-clamp-i64 1.5 0 10) :type 'wrong-type-argument)
+  (should-error (t/generic-clamp-string "m" "a") :type 'wrong-number-of-arguments))
+
 (ert-deftest function::fset ()
   (let ((x 5)
         (y 3))
@@ -144,6 +379,1000 @@
     (should (equal (t/hash-map-set m "a" "2") "1"))
     (should (equal (t/hash-map-get m "a") "2"))))
 
+(defvar t--watched nil)
+(defvar t--watch-log nil)
+
+(ert-deftest variable::watcher ()
+  (skip-unless (fboundp 'add-variable-watcher))
+  (t/variable-watch 't--watched 't--watch-log)
+  (setq t--watched 42)
+  (should (equal t--watch-log '(t--watched 42 set nil)))
+  (with-temp-buffer
+    (setq-local t--watched 7)
+    (should (equal t--watch-log (list 't--watched 7 'set (current-buffer))))))
+
+(defvar t--bound 1)
+
+(ert-deftest variable::with-var-bound ()
+  (should (= (t/variable-call-with-bound 't--bound 2 (lambda () t--bound)) 2))
+  (should (= t--bound 1))
+  (should-error (t/variable-call-with-bound 't--bound 3 (lambda () (error "%s" t--bound))))
+  (should (= t--bound 1)))
+
+(ert-deftest variable::with-var-bound-like-let ()
+  ;; Void variables can be bound, and are void again afterwards.
+  (should (= (t/variable-call-with-bound 't--void-variable 2 (lambda () t--void-variable)) 2))
+  (should-not (boundp 't--void-variable))
+  ;; Only the value in the current buffer is bound, even if F switches buffers.
+  (with-temp-buffer
+    (setq-local t--bound 5)
+    (let ((buffer (current-buffer)))
+      (should (equal (t/variable-call-with-bound
+                      't--bound 2
+                      (lambda () (list t--bound (with-temp-buffer t--bound))))
+                     '(2 1)))
+      (with-temp-buffer
+        (should (= (t/variable-call-with-bound
+                    't--bound 3
+                    (lambda () (set-buffer buffer) t--bound))
+                   5)))
+      (should (= t--bound 5))))
+  (should (= t--bound 1)))
+
+(ert-deftest variable::with-inhibit-read-only ()
+  (with-temp-buffer
+    (setq buffer-read-only t)
+    (should-error (insert "x") :type 'buffer-read-only)
+    (t/variable-insert-read-only "foo" nil)
+    (should (equal (buffer-string) "foo"))
+    (should (null inhibit-read-only))
+    (should-error (t/variable-insert-read-only "bar" t) :type 'error)
+    (should (equal (buffer-string) "foobar"))
+    (should (null inhibit-read-only))
+    (should-error (t/variable-panic-read-only) :type 'rust-panic)
+    (should (null inhibit-read-only))))
+
+(ert-deftest keymap::described-prefix ()
+  (let ((map (t/keymap-make-prefixed #'ignore)))
+    (should (keymapp (lookup-key map (kbd "C-c p"))))
+    (should (eq (lookup-key map (kbd "C-c p a")) #'ignore))
+    (should (equal (cadr (assq ?p (lookup-key map (kbd "C-c")))) "my-prefix"))
+    (should (equal (cdr (assq ?a (lookup-key map (kbd "C-c p"))))
+                   '("do-a" . ignore)))))
+
+(ert-deftest keymap::dispatch ()
+  (let* ((log nil)
+         (dispatch (t/keymap-define-dispatch (lambda () (interactive) (push 'a log))
+                                             (lambda () (interactive) (push 'b log))))
+         (map (make-sparse-keymap)))
+    (should (eq dispatch 't/dispatch))
+    (should (keymapp (symbol-function 't/dispatch)))
+    (should (eq (symbol-value 't/dispatch) (symbol-function 't/dispatch)))
+    (define-key map (kbd "C-c d") dispatch)
+    (call-interactively (lookup-key map (kbd "C-c d b")))
+    (call-interactively (lookup-key map (kbd "C-c d a")))
+    (should (equal log '(a b)))
+    (should (equal (car (cdr (assq ?a (symbol-function 't/dispatch)))) "do-a"))))
+
+(ert-deftest overlay::create-and-delete ()
+  (with-temp-buffer
+    (insert "hello world")
+    (let ((o (t/overlay-highlight 1 6 'bold)))
+      (should (overlayp o))
+      (should (= (overlay-start o) 1))
+      (should (= (overlay-end o) 6))
+      (should (eq (t/overlay-face o) 'bold))
+      (should (equal (overlays-at 2) (list o)))
+      (t/overlay-delete o)
+      (should (null (overlay-buffer o)))
+      (should (null (overlays-at 2))))))
+
+(ert-deftest coding::scoped-binding ()
+  (let ((coding-system-for-read 'latin-1)
+        (coding-system-for-write 'latin-1))
+    (should (equal (t/coding-with-utf-8-read
+                    (lambda () (list coding-system-for-read coding-system-for-write)))
+                   '(utf-8 nil)))
+    (should (eq coding-system-for-read 'latin-1))
+    (should (eq coding-system-for-write 'latin-1))
+    (should-error (t/coding-with-utf-8-read (lambda () (error "Oops"))))
+    (should (eq coding-system-for-read 'latin-1))
+    (should (eq coding-system-for-write 'latin-1))
+    (should-error (t/coding-panic-with-utf-8-read) :type 'rust-panic)
+    (should (eq coding-system-for-read 'latin-1))
+    (should (eq coding-system-for-write 'latin-1))))
+
+(ert-deftest read::sexp ()
+  (should (equal (t/read-sexp "(key . \"value\")") '(key . "value")))
+  (should (equal (t/read-sexp "  (1 2 (3))\n") '(1 2 (3))))
+  (should (equal (t/read-sexp "(+ 1 2)") '(+ 1 2)))
+  (should-error (t/read-sexp "(a b") :type 'end-of-file)
+  (should (equal (should-error (t/read-sexp "(a) b") :type 'invalid-read-syntax)
+                 '(invalid-read-syntax "Trailing garbage following expression" " b"))))
+
+(ert-deftest read::eval ()
+  (should (= (t/read-eval "(+ 1 2)") 3))
+  (should (equal (t/read-eval "(let ((x 1)) (funcall (lambda () x)))") 1))
+  (should (eq (t/read-eval "(featurep 'emacs)") t))
+  (should-error (t/read-eval "(a b") :type 'end-of-file)
+  (should-error (t/read-eval "(car 1)") :type 'wrong-type-argument)
+  (should (null (t/read-failed-phase "(+ 1 2)")))
+  (should (eq (t/read-failed-phase "(a b") 'read))
+  (should (eq (t/read-failed-phase "(a) b") 'read))
+  (should (eq (t/read-failed-phase "(car 1)") 'eval)))
+
+(ert-deftest progress::update-and-done ()
+  (let (calls)
+    (cl-letf* ((update (symbol-function 'progress-reporter-update))
+               (done (symbol-function 'progress-reporter-done))
+               ((symbol-function 'progress-reporter-update)
+                (lambda (reporter &rest args)
+                  (push 'update calls)
+                  (apply update reporter args)))
+               ((symbol-function 'progress-reporter-done)
+                (lambda (reporter)
+                  (push 'done calls)
+                  (funcall done reporter))))
+      (should (= (t/progress-count 5) 5)))
+    (should (equal (reverse calls) '(update update update update update done)))))
+
+(ert-deftest obarray::isolation ()
+  (let* ((result (t/obarray-read-isolated "(t--obarray-private-a t--obarray-private-b)"))
+         (form (car result))
+         (ob (cadr result))
+         (global obarray))
+    (should (equal (mapcar #'symbol-name form)
+                   '("t--obarray-private-a" "t--obarray-private-b")))
+    (should (eq (intern-soft "t--obarray-private-a" ob) (car form)))
+    (should-not (intern-soft "t--obarray-private-a"))
+    (should-not (intern-soft "t--obarray-private-b"))
+    (should (eq obarray global))
+    (let ((sym (t/obarray-intern-in "t--obarray-private-c" ob)))
+      (should (eq (intern-soft "t--obarray-private-c" ob) sym))
+      (should-not (intern-soft "t--obarray-private-c")))
+    (should-error (t/obarray-panic-isolated) :type 'rust-panic)
+    (should (eq obarray global))))
+
+(ert-deftest seq::slicing ()
+  (should (equal (t/seq-subseq [1 2 3 4 5] 1 -1) [2 3 4]))
+  (should (equal (t/seq-subseq '(1 2 3 4 5) -3 -1) '(3 4)))
+  (should (equal (t/seq-subseq '(1 2 3 4 5) 2 nil) '(3 4 5)))
+  (should (equal (t/seq-subseq "hello" 0 -2) "hel"))
+  (should-error (t/seq-subseq [1 2 3] 1 10))
+  (should (= (t/seq-nth-int [10 20 30] 1) 20))
+  (should (= (t/seq-nth-int '(10 20 30) 2) 30))
+  (should-error (t/seq-nth-int [10 20 30] 3) :type 'args-out-of-range))
+
+(ert-deftest seq::substring-chars ()
+  (should (equal (t/seq-substring-chars "héllo wörld" 1 4) "éll"))
+  (should (equal (t/seq-substring-chars "日本語テキスト" 2 nil) "語テキスト"))
+  (should (equal (t/seq-substring-chars "日本語テキスト" -3 -1) "キス"))
+  (should (equal (t/seq-substring-chars "ab" 2 nil) ""))
+  (should-error (t/seq-substring-chars "日本" 0 3) :type 'args-out-of-range)
+  (should-error (t/seq-substring-chars [1 2 3] 0 1) :type 'wrong-type-argument))
+
+(ert-deftest seq::list-from-bounded ()
+  (should (equal (t/seq-range-bounded 3 5) '(0 1 2)))
+  (should (equal (t/seq-range-bounded 5 5) '(0 1 2 3 4)))
+  (should (equal (t/seq-range-bounded 0 0) nil))
+  (should (equal (cdr (should-error (t/seq-range-bounded 6 5) :type 'rust-error))
+                 '("more than 5 items")))
+  (should-error (t/seq-range-bounded nil 100) :type 'rust-error))
+
+(ert-deftest seq::sort ()
+  (should (equal (t/seq-sort-by-area (list '(1 1) '(2 3) '(4 1) '(3 3) '(6 1)))
+                 '((3 3) (2 3) (6 1) (4 1) (1 1))))
+  (should (equal (t/seq-sort-by-area (vector '(1 2) '(2 2))) [(2 2) (1 2)]))
+  (should (equal (t/seq-sort-by-area nil) nil))
+  (should-error (t/seq-sort-by-area (list '(1 1) '(2 x))) :type 'wrong-type-argument))
+
+(defun t/kill-emacs-after-hooking ()
+  (t/hook-write-on-kill (getenv "KILL_FILE"))
+  (kill-emacs 0))
+
+;;; This kills Emacs, so it is run under a sub-process Emacs.
+(ert-deftest hook::kill-emacs ()
+  (let* ((file (make-temp-file "kill-emacs-hook"))
+         (process-environment (cons (format "KILL_FILE=%s" file) process-environment)))
+    (should (= (call-process (format "%s/%s" (getenv "PROJECT_ROOT") "bin/fn.sh")
+                             nil nil nil
+                             "t/kill-emacs-after-hooking")
+               0))
+    (should (equal (with-temp-buffer
+                     (insert-file-contents file)
+                     (buffer-string))
+                   "killed"))))
+
+(defvar t--command-count 0)
+
+(ert-deftest hook::post-command ()
+  (let ((overriding-local-map (make-sparse-keymap))
+        (t--command-count 0))
+    (define-key overriding-local-map (kbd "C-c i") #'ignore)
+    (let ((f (t/hook-count-commands nil)))
+      (execute-kbd-macro (kbd "C-c i C-c i"))
+      (should (= t--command-count 2))
+      (t/hook-remove-post-command f nil)
+      (execute-kbd-macro (kbd "C-c i"))
+      (should (= t--command-count 2)))
+    ;; The command loop runs commands in the selected window's buffer.
+    (let ((buffer (generate-new-buffer " *t--post-command*")))
+      (unwind-protect
+          (save-window-excursion
+            (switch-to-buffer buffer)
+            (let ((f (t/hook-count-commands t)))
+              (should (memq f post-command-hook))
+              (should-not (memq f (default-value 'post-command-hook)))
+              (execute-kbd-macro (kbd "C-c i"))
+              (should (= t--command-count 3))
+              (t/hook-remove-post-command f t)
+              (execute-kbd-macro (kbd "C-c i"))
+              (should (= t--command-count 3))))
+        (kill-buffer buffer)))))
+
+(ert-deftest hook::dropped-with-buffer ()
+  (let ((dropped (t/hook-dropped)))
+    ;; Conservative stack scanning may keep some objects alive, so create many of them.
+    (funcall (lambda ()
+               (dotimes (_ 100)
+                 (with-temp-buffer
+                   (should (memq (t/hook-add-tracked-pre-command) pre-command-hook))))))
+    (garbage-collect)
+    (should (> (t/hook-dropped) dropped))))
+
+(ert-deftest lisp-macro::define ()
+  (should (eq (t/lisp-macro-define-reversed) 't-reversed))
+  (should (macrop 't-reversed))
+  (should (equal (macroexpand '(t-reversed 1 2 -)) '(- 2 1)))
+  (should (equal (macroexpand '(t-reversed (list 1 2) car)) '(car (list 1 2))))
+  (should (= (eval '(t-reversed 1 5 -)) 4))
+  (should (equal (funcall (lambda (x) (t-reversed x 10 list)) 1) '(10 1)))
+  (should-error (macroexpand '(t-reversed)) :type 'wrong-type-argument))
+
+(defvar t--mode-setup nil)
+
+(ert-deftest mode::derived ()
+  (should (eq (t/mode-define-lang nil) 't-lang-mode))
+  (should (commandp 't-lang-mode))
+  (with-temp-buffer
+    (let ((t--mode-setup nil))
+      (t-lang-mode)
+      (should (eq major-mode 't-lang-mode))
+      (should (equal mode-name "t-lang"))
+      (should (derived-mode-p 'prog-mode))
+      (should (eq t--mode-setup 't-lang-mode))))
+  (t/mode-define-lang t)
+  (with-temp-buffer
+    (should-error (t-lang-mode) :type 'error)))
+
+(ert-deftest hook::capf ()
+  (with-temp-buffer
+    (let ((f (t/hook-complete-from "foobar bar")))
+      (should (memq f completion-at-point-functions))
+      (insert "x fo")
+      (should (equal (funcall f) '(3 5 ("foobar" "bar"))))
+      (completion-at-point)
+      (should (equal (buffer-string) "x foobar"))))
+  (let ((dropped (t/hook-dropped)))
+    (funcall (lambda ()
+               (dotimes (_ 100)
+                 (with-temp-buffer
+                   (should (memq (t/hook-add-tracked-capf) completion-at-point-functions))))))
+    (garbage-collect)
+    (should (> (t/hook-dropped) dropped))))
+
+(ert-deftest value::callable-predicates ()
+  (should (equal (t/value-classify (lambda (x) x)) '(function)))
+  (should (equal (t/value-classify (lambda () (interactive) 1)) '(function command)))
+  (should (equal (t/value-classify 'forward-char) '(function command)))
+  (should (equal (t/value-classify 'when) '(macro)))
+  (should (equal (t/value-classify 5) nil)))
+
+(ert-deftest marker::tracking-edits ()
+  (with-temp-buffer
+    (insert "hello world")
+    (let ((m (t/marker-make 7)))
+      (should (markerp m))
+      (should (= (t/marker-position m) 7))
+      (should (eq (t/marker-buffer m) (current-buffer)))
+      (goto-char (point-min))
+      (insert "well, ")
+      (should (= (t/marker-position m) 13))
+      (t/marker-detach m)
+      (should (null (t/marker-position m)))
+      (should (null (t/marker-buffer m))))))
+
+(ert-deftest lisp-data::nested-plists ()
+  (let ((config (t/lisp-data-default-config)))
+    (should (equal config '(:server (:host "localhost" :port 8080)
+                            :limits (:max-connections 10 :timeout nil))))
+    (should (equal (t/lisp-data-bump-port config)
+                   '(:server (:host "localhost" :port 8081)
+                     :limits (:max-connections 10 :timeout nil))))
+    (should (equal (t/lisp-data-bump-port '(:limits (:max-connections 1 :timeout 2.5)
+                                            :server (:port 1 :host "h")))
+                   '(:server (:host "h" :port 2)
+                     :limits (:max-connections 1 :timeout 2.5))))
+    (should-error (t/lisp-data-bump-port '(:server (:host "h"))) :type 'wrong-type-argument)))
+
+(ert-deftest plist::builder ()
+  (let ((options (t/plist-request-options "https://example.com" 2.5)))
+    (should (equal (plist-get options :url) "https://example.com"))
+    (should (equal (plist-get options :method) "GET"))
+    (should (equal (plist-get options :timeout) 2.5))
+    (should (eq (plist-get options :follow-redirects) t))
+    (should (equal options '(:url "https://example.com" :method "GET"
+                             :timeout 2.5 :follow-redirects t))))
+  (should (null (plist-get (t/plist-request-options "" nil) :timeout))))
+
+(ert-deftest plist::keyword-args ()
+  (let ((plist (t/plist-forward '(:a 1 :b "x" :unknown-key nil))))
+    (should (= (length plist) 8))
+    (should (eq (plist-get plist :a) 1))
+    (should (equal (plist-get plist :b) "x"))
+    (should (plist-member plist :unknown-key))
+    (should (eq (plist-get plist :forwarded) t)))
+  (should (equal (t/plist-forward nil) '(:forwarded t)))
+  (should (equal (should-error (t/plist-forward '(:a 1 :b)) :type 'wrong-type-argument)
+                 '(wrong-type-argument plistp (:a 1 :b))))
+  (should (equal (should-error (t/plist-forward '(a 1)) :type 'wrong-type-argument)
+                 '(wrong-type-argument keywordp a))))
+
+(ert-deftest command::this-and-last ()
+  (let ((this-command nil)
+        (last-command nil))
+    (should (null (t/command-this)))
+    (should (null (t/command-last)))
+    (should (= (t/command-repeat-count) 0)))
+  (let ((this-command 't--cycle)
+        (last-command 'forward-char))
+    (should (eq (t/command-this) 't--cycle))
+    (should (eq (t/command-last) 'forward-char))
+    (should (= (t/command-repeat-count) 1))
+    (setq last-command this-command)
+    (should (= (t/command-repeat-count) 2))
+    (should (= (t/command-repeat-count) 3))))
+
+(cl-defstruct t--point x y)
+
+(ert-deftest lisp-data::record-defined-by-module ()
+  (should (fboundp 'make-t--span))
+  (let ((span (t/lisp-data-find-span "hello world" "world")))
+    (should (t--span-p span))
+    (should (= (t--span-start span) 6))
+    (should (= (t--span-end span) 11))
+    (should (equal (t--span-text span) "world"))
+    (should (= (t/lisp-data-span-length span) 5)))
+  (should (null (t/lisp-data-find-span "hello" "x")))
+  (should (= (t/lisp-data-span-length (make-t--span :start 1 :end 4 :text "abc")) 3))
+  (should-error (t/lisp-data-span-length '(1 4 "abc")) :type 'wrong-type-argument))
+
+(ert-deftest lisp-data::type-constructor ()
+  (let ((address (t/lisp-data-make-server-address :port 8080 :host "localhost")))
+    (should (equal address '(:host "localhost" :port 8080 :retries nil)))
+    (should (equal (t/lisp-data-with-default-retries address)
+                   '(:host "localhost" :port 8080 :retries 3)))
+    (should (equal (apply #'t/lisp-data-make-server-address address) address)))
+  (should (string-match-p ":host, :port, :retries"
+                          (documentation #'t/lisp-data-make-server-address)))
+  (should-error (t/lisp-data-make-server-address :host "localhost" :port "80")
+                :type 'wrong-type-argument)
+  (should-error (t/lisp-data-make-server-address :port 80) :type 'wrong-type-argument))
+
+(ert-deftest lisp-data::newtype ()
+  (should (eq (t/lisp-data-user-id 5) 5))
+  (should (eq (t/lisp-data-next-user-id (t/lisp-data-user-id 5)) 6))
+  (should (eq (t/lisp-data-owner-id '(:id 7 :name "alice")) 7))
+  (should (equal (should-error (t/lisp-data-next-user-id "5") :type 'wrong-type-argument)
+                 '(wrong-type-argument integerp "5"))))
+
+(ert-deftest lisp-data::record ()
+  (let ((p (t/lisp-data-make-point 1 2)))
+    (should (cl-typep p 't--point))
+    (should (= (t--point-x p) 1))
+    (should (= (t--point-y p) 2))
+    (setq p (t/lisp-data-transpose-point p))
+    (should (cl-typep p 't--point))
+    (should (equal (list (t--point-x p) (t--point-y p)) '(2 1))))
+  (should-error (t/lisp-data-transpose-point '(:x 1 :y 2)) :type 'wrong-type-argument))
+
+(defun t--prefix-command (n)
+  (interactive "p")
+  (list n (called-interactively-p 'any)))
+
+(ert-deftest command::funcall-interactively ()
+  (should (equal (t--prefix-command 3) '(3 nil)))
+  (should (equal (t/command-call-interactively-with #'t--prefix-command 3) '(3 t))))
+
+(ert-deftest command::interactive-form ()
+  (should (commandp #'t/command-greet))
+  (should (equal (t/command-greet "alice") "Hello, alice!"))
+  (let (prompts)
+    (cl-letf (((symbol-function 'read-string)
+               (lambda (prompt &rest _) (push prompt prompts) "bob")))
+      (should (equal (call-interactively #'t/command-greet) "Hello, bob!")))
+    (should (equal prompts '("Name: ")))))
+
+(ert-deftest command::interactive-spec ()
+  (should (commandp #'t/command-double))
+  (should (commandp #'t/command-answer))
+  (should (= (t/command-double 4) 8))
+  (let ((current-prefix-arg 5))
+    (should (= (call-interactively #'t/command-double) 10)))
+  (should (= (call-interactively #'t/command-answer) 42))
+  (should (equal (interactive-form #'t/command-answer) '(interactive nil)))
+  (should (equal (interactive-form #'t/command-quoted-prompt)
+                 '(interactive "sYour \"name\": "))))
+
+(ert-deftest command::called-interactively-p ()
+  (should-not (t/command-interactive-p nil))
+  (should (funcall-interactively #'t/command-interactive-p nil))
+  ;; Only direct calls by the user count, which never happens in batch mode.
+  (should-not (funcall-interactively #'t/command-interactive-p t))
+  (let ((hooks called-interactively-p-functions))
+    (with-temp-buffer
+      (setq-local called-interactively-p-functions (list #'ignore))
+      (should (funcall-interactively #'t/command-interactive-p nil))
+      (should (equal called-interactively-p-functions (list #'ignore))))
+    (should (equal called-interactively-p-functions hooks))))
+
+(defvar t--steps nil)
+
+(defun t--step ()
+  (interactive)
+  (push (t/command-step-direction) t--steps))
+
+(ert-deftest command::this-command-keys ()
+  (let ((overriding-local-map (make-sparse-keymap))
+        (t--steps nil))
+    (define-key overriding-local-map (kbd "C-c n") #'t--step)
+    (define-key overriding-local-map (kbd "C-c p") #'t--step)
+    (define-key overriding-local-map (kbd "C-c x") #'t--step)
+    (execute-kbd-macro (kbd "C-c n C-c p C-c x C-c n"))
+    (should (equal (reverse t--steps) '(1 -1 0 1)))))
+
+(ert-deftest face::cached ()
+  (let ((face (t/face-get "bold")))
+    (should (eq face 'bold))
+    (should (facep face))
+    (should (eq (t/face-get "bold") face)))
+  (should-error (t/face-get "t--no-such-face") :type 'wrong-type-argument))
+
+(ert-deftest closure::captured-state ()
+  (let ((add-3 (t/closure-make-adder 3)))
+    (should (= (funcall add-3 4) 7))
+    (should (= (funcall add-3 -3) 0))
+    (should-error (funcall add-3) :type 'wrong-number-of-arguments)
+    (should-error (funcall add-3 "4") :type 'wrong-type-argument))
+  (let ((c1 (t/closure-make-counter))
+        (c2 (t/closure-make-counter)))
+    (should (= (funcall c1) 1))
+    (should (= (funcall c1) 2))
+    (should (= (funcall c2) 1)))
+  (let ((caller (t/closure-make-caller)))
+    (should (eq (funcall caller (lambda () 'called)) 'called))
+    (should-error (funcall caller (lambda () (funcall caller #'ignore)))))
+  (let ((count (t/closure-make-arg-counter)))
+    (should (= (funcall count 'a) 1))
+    (should (= (funcall count 'a 'b 'c) 3))
+    (should-error (funcall count) :type 'wrong-number-of-arguments)))
+
+(ert-deftest file::insert-file-contents ()
+  (let ((file (make-temp-file "emacs-module-rs"))
+        (buffer (current-buffer)))
+    (unwind-protect
+        (progn
+          (let ((coding-system-for-write 'utf-8))
+            (write-region "h\u00e9llo w\u00f6rld\n" nil file))
+          (should (equal (t/file-read file nil) "h\u00e9llo w\u00f6rld\n"))
+          (let ((coding-system-for-write 'latin-1))
+            (write-region "caf\u00e9\n" nil file))
+          (should (equal (t/file-read file "latin-1") "caf\u00e9\n"))
+          (should (eq (current-buffer) buffer)))
+      (delete-file file)))
+  (should-error (t/file-read "/nonexistent/emacs-module-rs" nil) :type 'file-error))
+
+(ert-deftest file::write-file ()
+  (let ((file (make-temp-file "emacs-module-rs")))
+    (unwind-protect
+        (progn
+          (t/file-write file "first\n" nil)
+          (t/file-write file "h\u00e9llo w\u00f6rld\n" "utf-8")
+          (should (equal (t/file-read file "utf-8") "h\u00e9llo w\u00f6rld\n"))
+          (t/file-write file "caf\u00e9\n" "latin-1")
+          (should (equal (t/file-read file "latin-1") "caf\u00e9\n"))
+          (should (= (nth 7 (file-attributes file)) 5)))
+      (delete-file file))))
+
+(ert-deftest file::directory-files ()
+  (let ((dir (make-temp-file "emacs-module-rs" t)))
+    (unwind-protect
+        (progn
+          (dolist (name '("a.rs" "b.el" "c.rs"))
+            (write-region "" nil (expand-file-name name dir)))
+          (should (equal (t/file-list dir nil nil) '("." ".." "a.rs" "b.el" "c.rs")))
+          (should (equal (t/file-list dir nil "\\.rs\\'") '("a.rs" "c.rs")))
+          (should (equal (t/file-list dir t "\\.el\\'") (list (expand-file-name "b.el" dir)))))
+      (delete-directory dir t))))
+
+(ert-deftest value::comparisons ()
+  (should (equal (t/value-compare nil nil) '(nil eq equal)))
+  (should (equal (t/value-compare 'a 'a) '(eq equal)))
+  (should (equal (t/value-compare "a" (copy-sequence "a")) '(equal)))
+  (let ((s "a"))
+    (should (equal (t/value-compare s s) '(eq equal))))
+  (should (equal (t/value-compare '(1 2) '(1 3)) nil))
+  (should (equal (t/value-compare nil '()) '(nil eq equal))))
+
+(ert-deftest value::equal-hash ()
+  (should (= (t/value-lisp-hash "abc") (t/value-lisp-hash (copy-sequence "abc"))))
+  (should (= (t/value-lisp-hash '(1 (2 . "x"))) (t/value-lisp-hash (list 1 (cons 2 "x")))))
+  (should (= (t/value-count-distinct (vector "a" (copy-sequence "a") '(1 2) (list 1 2) 'x 1.0 1))
+             5))
+  ;; Keys are kept alive across calls.
+  (should (= (t/value-count-seen (list "t--seen" 1)) 1))
+  (garbage-collect)
+  (should (= (t/value-count-seen (list "t--seen" 1)) 2))
+  (should (= (t/value-count-seen (list "t--seen" 2)) 1))
+  (should (= (t/value-count-seen (list (copy-sequence "t--seen") 1)) 3)))
+
+(ert-deftest gc::stats ()
+  (let ((stats (t/gc-collect)))
+    (should (consp stats))
+    (should (assq 'conses stats)))
+  (cl-destructuring-bind (size used free) (t/gc-stats "conses")
+    (should (> size 0))
+    (should (> used 0))
+    (should (integerp free)))
+  (should (null (t/gc-stats "no-such-kind"))))
+
+(ert-deftest convert::nonzero ()
+  (should (= (t/convert-batch-count 10 3) 4))
+  (should (equal (should-error (t/convert-batch-count 10 0) :type 'wrong-type-argument)
+                 '(wrong-type-argument nonzero-usize-p 0)))
+  (should-error (t/convert-batch-count 10 -1) :type 'wrong-type-argument)
+  (should (= (t/convert-nonzero-u8-identity 255) 255))
+  (should-error (t/convert-nonzero-u8-identity 256) :type 'wrong-type-argument)
+  (should (= (t/convert-nonzero-i64-negate 5) -5))
+  (should-error (t/convert-nonzero-i64-negate 0) :type 'wrong-type-argument))
+
+(ert-deftest convert::tuple ()
+  (should (equal (t/convert-birthday '("Ada" 36)) '("Ada" 37)))
+  (should (equal (t/convert-birthday ["Ada" 36]) '("Ada" 37)))
+  (should (= (t/convert-sum-12 (number-sequence 1 12)) 78))
+  (should (equal (should-error (t/convert-birthday '("Ada")) :type 'wrong-length-argument)
+                 '(wrong-length-argument 2 1)))
+  (should (equal (should-error (t/convert-birthday '("Ada" 36 x)) :type 'wrong-length-argument)
+                 '(wrong-length-argument 2 3)))
+  (should-error (t/convert-birthday '(36 "Ada")) :type 'wrong-type-argument)
+  (should-error (t/convert-birthday "Ada") :type 'wrong-type-argument))
+
+(ert-deftest convert::option-round-trip ()
+  (should (eq (t/convert-maybe-length nil) nil))
+  (should (= (t/convert-maybe-length "abc") 3))
+  (should-error (t/convert-maybe-length 'abc) :type 'wrong-type-argument))
+
+(ert-deftest convert::option-result ()
+  (should (null (t/convert-maybe-parse nil)))
+  (should (= (t/convert-maybe-parse "42") 42))
+  (should (equal (should-error (t/convert-maybe-parse "x") :type 'wrong-type-argument)
+                 '(wrong-type-argument t--integer-string-p "x"))))
+
+(ert-deftest convert::vec-into-list ()
+  (should (equal (t/convert-one-two-three) '(1 2 3)))
+  (should (equal (t/convert-words "a bb  ccc") '("a" "bb" "ccc")))
+  (should (null (t/convert-words "  ")))
+  (should (equal (t/convert-parse-all "1 2 3") '(1 2 3)))
+  (should-error (t/convert-parse-all "1 x 3") :type 'rust-error))
+
+(ert-deftest convert::vec-from-sequence ()
+  (should (= (t/convert-sum '(1 2 3)) 6))
+  (should (= (t/convert-sum [1 2 3 4]) 10))
+  (should (= (t/convert-sum nil) 0))
+  (should (= (t/convert-sum []) 0))
+  (should (equal (t/convert-lengths '("a" "bb" "")) '(1 2 0)))
+  (should (equal (t/convert-lengths ["abc"]) '(3)))
+  (should (equal (should-error (t/convert-sum '(1 2 . 3)) :type 'wrong-type-argument)
+                 '(wrong-type-argument listp 3)))
+  (should (equal (should-error (t/convert-sum 5) :type 'wrong-type-argument)
+                 '(wrong-type-argument listp 5)))
+  (should (equal (should-error (t/convert-sum '(1 "2")) :type 'wrong-type-argument)
+                 '(wrong-type-argument integerp "2"))))
+
+(ert-deftest convert::vec-from-circular-list ()
+  (let ((x (list 1)))
+    (setcdr x x)
+    (should (eq (cadr (should-error (t/convert-sum x) :type 'circular-list)) x)))
+  (let ((x (list 1 2 3)))
+    (setcdr (last x) (cdr x))
+    (should (eq (cadr (should-error (t/convert-sum x) :type 'circular-list)) x)))
+  (let ((x (list :a 1 :b 2)))
+    (setcdr (last x) x)
+    (should (eq (cadr (should-error (t/plist-forward x) :type 'circular-list)) x))))
+
+(ert-deftest constant::defconst ()
+  (should (eq (t/constant-define) 't--max-batch-size))
+  (should (= t--max-batch-size 1024))
+  (should (special-variable-p 't--max-batch-size))
+  (should (get 't--max-batch-size 'risky-local-variable))
+  (should (equal (documentation-property 't--max-batch-size 'variable-documentation)
+                 "Largest batch size."))
+  (should (get 't/constant-square 'pure))
+  (should (get 't/constant-square 'side-effect-free))
+  (should (= (t/constant-square 3) 9)))
+
+(ert-deftest input::scripted ()
+  (let ((unread-command-events (listify-key-sequence "\u00e9a")))
+    (should (equal (t/input-read-char "Char: ") "\u00e9"))
+    (should (equal (t/input-read-char "Char: ") "a")))
+  (let ((unread-command-events (listify-key-sequence (kbd "C-M-a"))))
+    (should-error (t/input-read-char "Char: ")))
+  (let ((unread-command-events (listify-key-sequence (kbd "C-x C-f"))))
+    (should (equal (t/input-read-key-sequence "Key: ") "C-x C-f"))))
+
+(ert-deftest symbol::properties ()
+  (should (null (t/symbol-get-version "t--tagged")))
+  (t/symbol-put-version "t--tagged" 2)
+  (should (= (get 't--tagged 't--version) 2))
+  (should (= (t/symbol-get-version "t--tagged") 2))
+  (put 't--tagged 't--version "2")
+  (should-error (t/symbol-get-version "t--tagged") :type 'wrong-type-argument))
+
+(ert-deftest symbol::core ()
+  (should (eq (t/symbol-core "t") t))
+  (should (eq (t/symbol-core "t") t))
+  (should (eq (t/symbol-core "nil") nil))
+  (should (eq (t/symbol-truth) t))
+  (should (eq (t/symbol-truth) t)))
+
+(ert-deftest time::round-trip ()
+  (let ((now (current-time)))
+    (should (equal (t/time-round-trip now) now)))
+  (should (equal (t/time-round-trip '(-1 65535 999999 0)) '(-1 65535 999999 0)))
+  (should (equal (t/time-round-trip '(1 2)) '(1 2 0 0)))
+  (should (equal (t/time-round-trip 70000) '(1 4464 0 0)))
+  (should (equal (t/time-round-trip 1.5) '(0 1 500000 0)))
+  (should (equal (t/time-add '(0 1 500000) (t/time-millis 600)) '(0 2 100000 0)))
+  (should (= (t/time-duration-secs '(0 2 250000)) 2.25))
+  (should (equal (t/time-millis 1500) '(0 1 500000 0)))
+  (should-error (t/time-duration-secs -1) :type 'error)
+  (should-error (t/time-round-trip nil) :type 'error)
+  (should-error (t/time-round-trip "now") :type 'error))
+
+(ert-deftest time::nanoseconds ()
+  (let ((time (t/time-from-parts 1 123456789)))
+    (should (equal time '(0 1 123456 789000)))
+    (should (= (t/time-subsec-nanos time) 123456789))
+    (should (= (t/time-subsec-nanos (t/time-round-trip time)) 123456789)))
+  (when (fboundp 'time-convert)
+    (should (= (t/time-subsec-nanos '(1123456789 . 1000000000)) 123456789))
+    (let ((time (time-convert (t/time-from-parts 1 123456789) 1000000000)))
+      (should (equal (t/time-round-trip time) '(0 1 123456 789000))))))
+
+(ert-deftest time::file-mtime ()
+  (let ((file (make-temp-file "mtime")))
+    (unwind-protect
+        (should (time-equal-p
+                 (t/time-file-mtime file)
+                 (file-attribute-modification-time (file-attributes file))))
+      (delete-file file))))
+
+(ert-deftest timer::idle ()
+  (let ((fired (t/timer-fired))
+        (once (t/timer-schedule 0.1 nil))
+        (repeating (t/timer-schedule 0.1 t)))
+    (unwind-protect
+        (progn
+          (should (timerp once))
+          (should (memq once timer-idle-list))
+          (should (memq repeating timer-idle-list))
+          (ert-run-idle-timers)
+          (should (= (t/timer-fired) (+ 2 fired)))
+          (should-not (memq once timer-idle-list))
+          (ert-run-idle-timers)
+          (should (= (t/timer-fired) (+ 3 fired)))
+          (cancel-timer repeating)
+          (should-not (memq repeating timer-idle-list))
+          (ert-run-idle-timers)
+          (should (= (t/timer-fired) (+ 3 fired))))
+      (cancel-timer once)
+      (cancel-timer repeating))))
+
+(ert-deftest cons::primitives ()
+  (should (equal (t/cons-make 1 2) '(1 . 2)))
+  (should (equal (t/cons-make 1 nil) '(1)))
+  (should (= (t/cons-car '(1 . 2)) 1))
+  (should (= (t/cons-cdr '(1 . 2)) 2))
+  (should (null (t/cons-car nil)))
+  (should (null (t/cons-cdr nil)))
+  (should (equal (should-error (t/cons-car 5) :type 'wrong-type-argument)
+                 '(wrong-type-argument listp 5)))
+  (should-error (t/cons-cdr [1 2]) :type 'wrong-type-argument)
+  (should (equal (t/cons-swap '(a . b)) '(b . a)))
+  (should (equal (should-error (t/cons-swap nil) :type 'wrong-type-argument)
+                 '(wrong-type-argument consp nil)))
+  (should (equal (t/cons-lengths '("a" "abc")) '(("a" . 1) ("abc" . 3))))
+  (should (null (t/cons-lengths nil))))
+
+(ert-deftest lisp-vector::matrix ()
+  (should (equal (t/matrix-transpose [[1 2 3] [4 5 6] [7 8 9]])
+                 [[1 4 7] [2 5 8] [3 6 9]]))
+  (should (equal (t/matrix-transpose [[1 2 3]]) [[1] [2] [3]]))
+  (should (equal (t/matrix-transpose []) []))
+  (should (equal (t/matrix-identity) [[1 0 0] [0 1 0] [0 0 1]]))
+  (should (equal (should-error (t/matrix-transpose [[1 2] [3]]) :type 'wrong-length-argument)
+                 '(wrong-length-argument 2 [3])))
+  (should (equal (t/matrix-row-lengths [[1 2] [3]]) '(2 1)))
+  (should-error (t/matrix-transpose [(1 2)]) :type 'wrong-type-argument)
+  (should-error (t/matrix-transpose [[1 "2"]]) :type 'wrong-type-argument)
+  (should (equal (should-error (t/matrix-from-rows '((1 2) (3))) :type 'wrong-length-argument)
+                 '(wrong-length-argument 2 [3])))
+  (should (equal (t/matrix-from-rows '((1 2) (3 4))) [[1 2] [3 4]]))
+  (should (equal (t/matrix-nested [[1 2] [3]]) '((1 2) (3)))))
+
+(ert-deftest lisp-vector::matrix-array ()
+  (should (equal (t/matrix-scale [[1 2 3] [4 5 6] [7 8 9]] 2)
+                 [[2 4 6] [8 10 12] [14 16 18]]))
+  (should (equal (should-error (t/matrix-scale [[1 2 3] [4 5 6]] 2) :type 'wrong-length-argument)
+                 '(wrong-length-argument 3 [[1 2 3] [4 5 6]])))
+  (should (equal (should-error (t/matrix-scale [[1 2 3] [4 5] [7 8 9]] 2)
+                               :type 'wrong-length-argument)
+                 '(wrong-length-argument 3 [4 5]))))
+
+(ert-deftest buffer::with-temp-buffer ()
+  (let ((buffer (current-buffer))
+        (count (length (buffer-list))))
+    (should (equal (t/buffer-with-temp "hello" nil) "hello"))
+    (should (eq (current-buffer) buffer))
+    (should-error (t/buffer-with-temp "hello" t) :type 'error)
+    (should (eq (current-buffer) buffer))
+    (should (= (length (buffer-list)) count))
+    (should-error (t/buffer-panic-in-temp " *t--panicked*") :type 'rust-panic)
+    (should (eq (current-buffer) buffer))
+    (should-not (get-buffer " *t--panicked*"))
+    (should (= (length (buffer-list)) count))))
+
+(ert-deftest buffer::buffer-list ()
+  (should (equal (t/buffer-names) (mapcar #'buffer-name (buffer-list)))))
+
+(ert-deftest buffer::buffer-or-name ()
+  (with-temp-buffer
+    (insert "hello")
+    (let ((name (buffer-name)))
+      (should (= (t/buffer-size (current-buffer)) 5))
+      (should (= (t/buffer-size name) 5))))
+  (should (equal (should-error (t/buffer-size " no such buffer") :type 'error)
+                 '(error "No such buffer  no such buffer")))
+  (should (equal (should-error (t/buffer-size 5) :type 'wrong-type-argument)
+                 '(wrong-type-argument stringp 5))))
+
+(ert-deftest buffer::with-buffer ()
+  (let ((buffer (current-buffer))
+        (other (generate-new-buffer " *other*")))
+    (unwind-protect
+        (progn
+          (should (equal (t/buffer-name-in other nil) (buffer-name other)))
+          (should (eq (current-buffer) buffer))
+          (should-error (t/buffer-name-in other t) :type 'error)
+          (should (eq (current-buffer) buffer))
+          (should-error (t/buffer-panic-in other) :type 'rust-panic)
+          (should (eq (current-buffer) buffer)))
+      (kill-buffer other))
+    (should-error (t/buffer-name-in other nil) :type 'error)
+    (should (eq (current-buffer) buffer))))
+
+(ert-deftest lisp-vector::mutate ()
+  (let ((v (vector 3.5 -1.0 2.25)))
+    (t/lisp-vector-sort-floats v)
+    (should (equal v [-1.0 2.25 3.5])))
+  (let ((v (vector 1 2 3)))
+    (t/lisp-vector-double v nil)
+    (should (equal v [2 4 6]))
+    (should-error (t/lisp-vector-double v t) :type 'error)
+    (should (equal v [2 4 6])))
+  (should-error (t/lisp-vector-double (vector 1 "2") nil) :type 'wrong-type-argument)
+  (should-error (t/lisp-vector-double '(1 2) nil) :type 'wrong-type-argument))
+
+(ert-deftest lisp-vector::typed ()
+  (should (equal (t/lisp-vector-make 3 'x) [x x x]))
+  (should (equal (t/lisp-vector-make 0 nil) []))
+  (should (eq (t/lisp-vector-get [a b c] 1) 'b))
+  (let ((v (vector 1 2 3)))
+    (should (eq (t/lisp-vector-swap v 0 2) v))
+    (should (equal v [3 2 1])))
+  (should (= (t/lisp-vector-count-nils [nil 1 nil 2]) 2))
+  (should (= (t/lisp-vector-count-nils []) 0))
+  (should (equal (cdr (should-error (t/lisp-vector-get [a b c] 3) :type 'rust-error))
+                 '("index 3 is out of bounds for a vector of length 3")))
+  (should-error (t/lisp-vector-swap (vector 1 2) 0 2) :type 'rust-error)
+  (should (equal (should-error (t/lisp-vector-get '(a b) 0) :type 'wrong-type-argument)
+                 '(wrong-type-argument vectorp (a b)))))
+
+(ert-deftest deferred::drain ()
+  (should (= (t/deferred-drain) 0))
+  (with-temp-buffer
+    (should (= (t/deferred-schedule-inserts 3) 3))
+    (should (equal (buffer-string) ""))
+    (should (= (t/deferred-drain) 3))
+    (should (equal (buffer-string) "012"))
+    (should (= (t/deferred-drain) 0)))
+  (with-temp-buffer
+    (t/deferred-schedule-panic)
+    (t/deferred-schedule-inserts 2)
+    (should (string-match-p "deferred oops"
+                            (cadr (should-error (t/deferred-drain) :type 'rust-error))))
+    (should (equal (buffer-string) "01"))
+    (should (= (t/deferred-drain) 0)))
+  ;; Failing to report the later errors does not stop the remaining calls.
+  (with-temp-buffer
+    (t/deferred-schedule-panic)
+    (t/deferred-schedule-panic)
+    (t/deferred-schedule-inserts 2)
+    (cl-letf (((symbol-function 'message) (lambda (&rest _) (error "No messages"))))
+      (should (string-match-p "deferred oops"
+                              (cadr (should-error (t/deferred-drain) :type 'rust-error)))))
+    (should (equal (buffer-string) "01"))))
+
+(ert-deftest buffer-local::isolated-and-dropped ()
+  (let ((a (generate-new-buffer "a"))
+        (b (generate-new-buffer "b"))
+        (dropped (t/buffer-local-dropped)))
+    (with-current-buffer a (t/buffer-local-start 10))
+    (with-current-buffer b (t/buffer-local-start 20))
+    (should (= (with-current-buffer a (t/buffer-local-increment)) 11))
+    (should (= (with-current-buffer a (t/buffer-local-increment)) 12))
+    (should (= (with-current-buffer b (t/buffer-local-increment)) 21))
+    (with-temp-buffer
+      (should (null (t/buffer-local-increment)))
+      (should (null (t/buffer-local-stop))))
+    (should (= (with-current-buffer b (t/buffer-local-stop)) 21))
+    (should (= (t/buffer-local-dropped) (1+ dropped)))
+    (should (null (with-current-buffer b (t/buffer-local-increment))))
+    (kill-buffer b)
+    (should (= (t/buffer-local-dropped) (1+ dropped)))
+    (kill-buffer a)
+    (should (= (t/buffer-local-dropped) (+ 2 dropped)))))
+
+(ert-deftest buffer-local::many-buffers ()
+  (let ((dropped (t/buffer-local-dropped)))
+    (dotimes (i 100)
+      (with-temp-buffer
+        (t/buffer-local-start i)
+        (t/buffer-local-start (* 2 i))
+        (should (= (t/buffer-local-increment) (1+ (* 2 i))))
+        ;; Restarting reuses the cleanup function already in the hook.
+        (should (= (length (remq t kill-buffer-hook)) 1))))
+    ;; Each buffer dropped its first counter when restarted, and its second when killed.
+    (should (= (t/buffer-local-dropped) (+ 200 dropped)))))
+
+(ert-deftest transfer::arc-mutex ()
+  (let ((counter (t/shared-make-counter 5)))
+    (should (user-ptrp counter))
+    (should (= (t/shared-get counter) 5))
+    (should (= (t/shared-inc-in-threads counter 10) 15))
+    (should (= (t/shared-get counter) 15))
+    (should-error (t/shared-get (t/ref-cell-wrap 5)) :type 'rust-wrong-type-user-ptr)
+    (should-error (t/shared-get 5) :type 'wrong-type-argument)))
+
+(ert-deftest transfer::handle-close ()
+  (let ((db (t/db-open))
+        (closed (t/db-closed-count)))
+    (should (user-ptrp db))
+    (t/db-put db "a" "1")
+    (should (equal (t/db-get db "a") "1"))
+    (should (null (t/db-get db "b")))
+    (should (null (t/db-closed-p db)))
+    (should (eq (t/db-close db) t))
+    (should (= (t/db-closed-count) (1+ closed)))
+    (should (t/db-closed-p db))
+    (should (equal (should-error (t/db-get db "a") :type 'rust-handle-closed)
+                   '(rust-handle-closed)))
+    (should-error (t/db-put db "a" "2") :type 'rust-error)
+    (should (null (t/db-close db)))
+    (should (= (t/db-closed-count) (1+ closed)))))
+
+(ert-deftest transfer::dropped-on-gc ()
+  (let ((dropped (t/ref-cell-dropped-count)))
+    ;; Conservative stack scanning may keep some objects alive, so create many of them.
+    (funcall (lambda () (dotimes (_ 100) (t/ref-cell-make-tracked))))
+    (garbage-collect)
+    (should (> (t/ref-cell-dropped-count) dropped))))
+
+(ert-deftest global::across-calls ()
+  (let ((value (list (copy-sequence "kept") 1 2)))
+    (t/global-remember value)
+    (garbage-collect)
+    (should (eq (t/global-recall) value))
+    (t/global-remember (list (copy-sequence "replaced")))
+    (garbage-collect)
+    (should (equal (t/global-recall) '("replaced")))
+    (t/global-forget)
+    (should (null (t/global-recall)))))
+
+(ert-deftest hash-table::accessors ()
+  (let ((table (t/hash-table-lengths '("a" "bcd" "été"))))
+    (should (hash-table-p table))
+    (should (eq (hash-table-test table) 'equal))
+    (should (= (hash-table-count table) 3))
+    (should (= (t/hash-table-get table (concat "b" "cd") nil) 3))
+    (should (= (t/hash-table-get table "été" nil) 3))
+    (should (eq (t/hash-table-get table "missing" 'none) 'none))
+    (t/hash-table-remove table "a")
+    (should (= (hash-table-count table) 2))
+    (should (null (t/hash-table-get table "a" nil))))
+  (let ((table (t/hash-table-make-eq)))
+    (should (eq (hash-table-test table) 'eq))
+    (puthash (copy-sequence "k") 1 table)
+    (should (null (t/hash-table-get table "k" nil))))
+  (should-error (t/hash-table-get '((a . 1)) 'a nil) :type 'wrong-type-argument))
+
+(ert-deftest hash-table::value-methods ()
+  (let ((table (t/hash-table-count-words "a b a c a b")))
+    (should (eq (hash-table-test table) 'equal))
+    (should (= (hash-table-count table) 3))
+    (should (= (gethash "a" table) 3))
+    (should (= (gethash "b" table) 2))
+    (should (= (gethash "c" table) 1))
+    (puthash "nil" nil table)
+    (puthash "t" t table)
+    (puthash "sym" 'missing table)
+    (should (equal (t/hash-table-lookup table "a") '(3)))
+    (should (equal (t/hash-table-lookup table "nil") '(nil)))
+    (should (equal (t/hash-table-lookup table "t") '(t)))
+    (should (equal (t/hash-table-lookup table "sym") '(missing)))
+    (should (null (t/hash-table-lookup table "missing")))
+    (t/hash-table-delete table "a")
+    (should (null (t/hash-table-lookup table "a")))
+    (should (= (hash-table-count table) 5)))
+  (should (= (hash-table-count (t/hash-table-count-words "")) 0))
+  (let ((table (t/hash-table-make-weak)))
+    (should (eq (hash-table-weakness table) 'key))
+    (should (eq (hash-table-test table) 'eq)))
+  (should (null (hash-table-weakness (t/hash-table-make-eq)))))
+
+(ert-deftest hash-table::hash-map-round-trip ()
+  (let ((table (make-hash-table :test 'equal)))
+    (puthash "a" 1 table)
+    (puthash "b" 2 table)
+    (puthash "c" 3 table)
+    (let ((result (t/hash-table-inc-values table)))
+      (should (hash-table-p result))
+      (should (eq (hash-table-test result) 'equal))
+      (should (= (hash-table-count result) 3))
+      (should (= (gethash "a" result) 2))
+      (should (= (gethash "b" result) 3))
+      (should (= (gethash "c" result) 4))
+      (should (= (gethash "a" table) 1))))
+  (let ((table (make-hash-table :test 'eq)))
+    (puthash (copy-sequence "a") 1 table)
+    (puthash (copy-sequence "a") 2 table)
+    (should (= (hash-table-count (t/hash-table-inc-values table)) 1)))
+  (should (= (hash-table-count (t/hash-table-inc-values (make-hash-table))) 0))
+  (should (equal (should-error (t/hash-table-inc-values '(("a" . 1))) :type 'wrong-type-argument)
+                 '(wrong-type-argument hash-table-p (("a" . 1))))))
+
+(ert-deftest position::line-and-column ()
+  (with-temp-buffer
+    (insert "first\n\tsecond\nthird")
+    (goto-char (point-min))
+    (should (equal (t/position-status) "1:0"))
+    (forward-line 1)
+    (forward-char 2)
+    (should (equal (t/position-status) (format "2:%d" (1+ tab-width))))
+    (should (= (t/position-line-at (point-max)) 3))
+    (should (= (t/position-count-lines (point-min) (point-max)) 3))
+    (should (= (t/position-count-lines (point-min) (point-min)) 0))))
+
+(ert-deftest trace::call-stats ()
+  (let ((calls (plist-get (t/trace-square-stats) :calls)))
+    (dotimes (i 3)
+      (should (= (t/trace-square i) (* i i))))
+    (should-error (t/trace-square "x") :type 'wrong-type-argument)
+    (let ((stats (t/trace-square-stats)))
+      (should (= (plist-get stats :calls) (+ calls 3)))
+      (should (floatp (plist-get stats :total-time)))
+      (should (>= (plist-get stats :total-time) 0)))))
+
 ;;; Tests that, if failed, crash the whole process unrecoverably. They will be run under a
 ;;; sub-process Emacs.
 (defmacro destructive-test (name)